@@ -0,0 +1,801 @@
+use std::env;
+use std::sync::Arc;
+
+use crate::server::Server;
+
+use crate::repositories::
+{
+    Upstream,
+    places::repository::Repository     as PlaceRepository,
+    routes::repository::Repository     as RouteRepository,
+    places::memory::Repository         as PlaceMemory,
+    routes::memory::Repository         as RouteMemory,
+    sources::repository::Repository    as SourceRepository,
+    users::repository::Repository      as UserRepository,
+    progress::repository::Repository   as ProgressRepository,
+    ascents::repository::Repository    as AscentRepository,
+    comments::repository::Repository   as CommentRepository,
+    grades::repository::Repository     as GradeRepository,
+    moderation::repository::Repository as ModerationRepository,
+    ratings::repository::Repository    as RatingRepository,
+    users::sqlite::Repository          as UserStorage,
+    progress::sqlite::Repository       as ProgressStorage,
+    ascents::sqlite::Repository        as AscentStorage,
+    comments::sqlite::Repository       as CommentStorage,
+    grades::sqlite::Repository         as GradeStorage,
+    moderation::sqlite::Repository     as ModerationStorage,
+    ratings::sqlite::Repository        as RatingStorage,
+    storage::Pool,
+};
+
+use crate::contexts::
+{
+    places::use_cases::get::UseCase      as PlaceGetUseCase,
+    places::use_cases::post::UseCase     as PlacePostUseCase,
+    places::use_cases::put::UseCase      as PlacePutUseCase,
+    places::use_cases::delete::UseCase   as PlaceDeleteUseCase,
+    routes::use_cases::get::UseCase      as RouteGetUseCase,
+    routes::use_cases::post::UseCase     as RoutePostUseCase,
+    routes::use_cases::put::UseCase      as RoutePutUseCase,
+    routes::use_cases::delete::UseCase   as RouteDeleteUseCase,
+    sources::use_cases::get::UseCase     as SourceGetUseCase,
+    users::use_cases::get::UseCase       as UserGetUseCase,
+    users::use_cases::post::UseCase      as UserPostUseCase,
+    users::use_cases::put::UseCase       as UserPutUseCase,
+    users::use_cases::delete::UseCase    as UserDeleteUseCase,
+    users::use_cases::delete::Traces     as UserTraces,
+    comments::use_cases::get::UseCase    as CommentGetUseCase,
+    comments::use_cases::post::UseCase   as CommentPostUseCase,
+    comments::use_cases::put::UseCase    as CommentPutUseCase,
+    comments::use_cases::delete::UseCase as CommentDeleteUseCase,
+    moderation::use_cases::get::UseCase  as ModerationGetUseCase,
+    moderation::use_cases::post::UseCase as ModerationPostUseCase,
+    moderation::use_cases::put::UseCase  as ModerationPutUseCase,
+    ascents::use_cases::get::UseCase     as AscentGetUseCase,
+    ascents::use_cases::post::UseCase    as AscentPostUseCase,
+    ascents::use_cases::put::UseCase     as AscentPutUseCase,
+    ascents::use_cases::delete::UseCase  as AscentDeleteUseCase,
+    grades::use_cases::get::UseCase      as GradeGetUseCase,
+    grades::use_cases::put::UseCase      as GradePutUseCase,
+    grades::use_cases::delete::UseCase   as GradeDeleteUseCase,
+    ratings::use_cases::get::UseCase     as RatingGetUseCase,
+    ratings::use_cases::put::UseCase     as RatingPutUseCase,
+    ratings::use_cases::delete::UseCase  as RatingDeleteUseCase,
+    progress::use_cases::get::UseCase    as ProgressGetUseCase,
+    progress::use_cases::put::UseCase    as ProgressPutUseCase,
+    auth::use_cases::get::UseCase        as AuthGetUseCase,
+    auth::use_cases::post::UseCase       as AuthPostUseCase,
+    auth::use_cases::put::UseCase        as AuthPutUseCase,
+    auth::domain::tokens::Issuer         as TokenIssuer,
+    auth::domain::platform_admins_from_env,
+    moderation::domain::WordFilter,
+    places, routes, sources, users, auth, progress, ascents, comments, grades, moderation, ratings,
+};
+
+/// Where places and routes come from, `CLIMBHUB_BACKEND` being `sources` (the default) or `memory`.
+enum Backend
+{
+    /// The sources of the configuration file.
+    Sources,
+    /// Kept in memory and seeded with the examples of `schemas/`, for demos.
+    Memory,
+}
+
+impl Backend
+{
+    fn from_env() -> Self
+    {
+        match env::var("CLIMBHUB_BACKEND").as_deref()
+        {
+            Ok("memory") => Backend::Memory,
+            Ok("sources") | Err(_) => Backend::Sources,
+            Ok(other) => panic!("Unknown CLIMBHUB_BACKEND `{other}`, expected `sources` or `memory`"),
+        }
+    }
+}
+
+/// The place repository, shared behind each of its traits.
+#[derive(Clone)]
+pub struct Places
+{
+    pub get    : Arc<dyn places::irepository::get::IRepository>,
+    pub post   : Arc<dyn places::irepository::post::IRepository>,
+    pub put    : Arc<dyn places::irepository::put::IRepository>,
+    pub delete : Arc<dyn places::irepository::delete::IRepository>,
+}
+
+impl Places
+{
+    pub fn new<R>(repository: R) -> Self
+    where
+        R : places::irepository::get::IRepository + places::irepository::post::IRepository + places::irepository::put::IRepository
+          + places::irepository::delete::IRepository + 'static,
+    {
+        let repository = Arc::new(repository);
+        Places { get: repository.clone(), post: repository.clone(), put: repository.clone(), delete: repository }
+    }
+}
+
+/// The route repository, shared behind each of its traits.
+#[derive(Clone)]
+pub struct Routes
+{
+    pub get    : Arc<dyn routes::irepository::get::IRepository>,
+    pub post   : Arc<dyn routes::irepository::post::IRepository>,
+    pub put    : Arc<dyn routes::irepository::put::IRepository>,
+    pub delete : Arc<dyn routes::irepository::delete::IRepository>,
+}
+
+impl Routes
+{
+    pub fn new<R>(repository: R) -> Self
+    where
+        R : routes::irepository::get::IRepository + routes::irepository::post::IRepository + routes::irepository::put::IRepository
+          + routes::irepository::delete::IRepository + 'static,
+    {
+        let repository = Arc::new(repository);
+        Routes { get: repository.clone(), post: repository.clone(), put: repository.clone(), delete: repository }
+    }
+}
+
+/// The repository of the health of the sources.
+#[derive(Clone)]
+pub struct Sources
+{
+    pub get : Arc<dyn sources::irepository::get::IRepository>,
+}
+
+impl Sources
+{
+    pub fn new<R>(repository: R) -> Self
+    where
+        R : sources::irepository::get::IRepository + 'static,
+    {
+        Sources { get: Arc::new(repository) }
+    }
+}
+
+/// The user repository, which also holds the credentials and accesses, shared behind each of its traits.
+#[derive(Clone)]
+pub struct Users
+{
+    pub get      : Arc<dyn users::irepository::get::IRepository>,
+    pub post     : Arc<dyn users::irepository::post::IRepository>,
+    pub put      : Arc<dyn users::irepository::put::IRepository>,
+    pub delete   : Arc<dyn users::irepository::delete::IRepository>,
+    pub auth_get : Arc<dyn auth::irepository::get::IRepository>,
+    pub auth_put : Arc<dyn auth::irepository::put::IRepository>,
+}
+
+impl Users
+{
+    pub fn new<R>(repository: R) -> Self
+    where
+        R : users::irepository::get::IRepository + users::irepository::post::IRepository + users::irepository::put::IRepository
+          + users::irepository::delete::IRepository + auth::irepository::get::IRepository + auth::irepository::put::IRepository + 'static,
+    {
+        let repository = Arc::new(repository);
+        Users {
+            get      : repository.clone(),
+            post     : repository.clone(),
+            put      : repository.clone(),
+            delete   : repository.clone(),
+            auth_get : repository.clone(),
+            auth_put : repository,
+        }
+    }
+}
+
+/// The progress repository, shared behind each of its traits.
+#[derive(Clone)]
+pub struct Progress
+{
+    pub get    : Arc<dyn progress::irepository::get::IRepository>,
+    pub put    : Arc<dyn progress::irepository::put::IRepository>,
+    pub delete : Arc<dyn progress::irepository::delete::IRepository>,
+}
+
+impl Progress
+{
+    pub fn new<R>(repository: R) -> Self
+    where
+        R : progress::irepository::get::IRepository + progress::irepository::put::IRepository
+          + progress::irepository::delete::IRepository + 'static,
+    {
+        let repository = Arc::new(repository);
+        Progress { get: repository.clone(), put: repository.clone(), delete: repository }
+    }
+}
+
+/// The ascent repository, shared behind each of its traits.
+#[derive(Clone)]
+pub struct Ascents
+{
+    pub get    : Arc<dyn ascents::irepository::get::IRepository>,
+    pub post   : Arc<dyn ascents::irepository::post::IRepository>,
+    pub put    : Arc<dyn ascents::irepository::put::IRepository>,
+    pub delete : Arc<dyn ascents::irepository::delete::IRepository>,
+}
+
+impl Ascents
+{
+    pub fn new<R>(repository: R) -> Self
+    where
+        R : ascents::irepository::get::IRepository + ascents::irepository::post::IRepository + ascents::irepository::put::IRepository
+          + ascents::irepository::delete::IRepository + 'static,
+    {
+        let repository = Arc::new(repository);
+        Ascents { get: repository.clone(), post: repository.clone(), put: repository.clone(), delete: repository }
+    }
+}
+
+/// The comment repository, shared behind each of its traits.
+#[derive(Clone)]
+pub struct Comments
+{
+    pub get    : Arc<dyn comments::irepository::get::IRepository>,
+    pub post   : Arc<dyn comments::irepository::post::IRepository>,
+    pub put    : Arc<dyn comments::irepository::put::IRepository>,
+    pub delete : Arc<dyn comments::irepository::delete::IRepository>,
+}
+
+impl Comments
+{
+    pub fn new<R>(repository: R) -> Self
+    where
+        R : comments::irepository::get::IRepository + comments::irepository::post::IRepository + comments::irepository::put::IRepository
+          + comments::irepository::delete::IRepository + 'static,
+    {
+        let repository = Arc::new(repository);
+        Comments { get: repository.clone(), post: repository.clone(), put: repository.clone(), delete: repository }
+    }
+}
+
+/// The repository of grade suggestions, shared behind each of its traits.
+#[derive(Clone)]
+pub struct Grades
+{
+    pub get    : Arc<dyn grades::irepository::get::IRepository>,
+    pub put    : Arc<dyn grades::irepository::put::IRepository>,
+    pub delete : Arc<dyn grades::irepository::delete::IRepository>,
+}
+
+impl Grades
+{
+    pub fn new<R>(repository: R) -> Self
+    where
+        R : grades::irepository::get::IRepository + grades::irepository::put::IRepository + grades::irepository::delete::IRepository + 'static,
+    {
+        let repository = Arc::new(repository);
+        Grades { get: repository.clone(), put: repository.clone(), delete: repository }
+    }
+}
+
+/// The repository of reports and of the audit trail, shared behind each of its traits.
+#[derive(Clone)]
+pub struct Moderation
+{
+    pub get    : Arc<dyn moderation::irepository::get::IRepository>,
+    pub post   : Arc<dyn moderation::irepository::post::IRepository>,
+    pub delete : Arc<dyn moderation::irepository::delete::IRepository>,
+}
+
+impl Moderation
+{
+    pub fn new<R>(repository: R) -> Self
+    where
+        R : moderation::irepository::get::IRepository + moderation::irepository::post::IRepository
+          + moderation::irepository::delete::IRepository + 'static,
+    {
+        let repository = Arc::new(repository);
+        Moderation { get: repository.clone(), post: repository.clone(), delete: repository }
+    }
+}
+
+/// The rating repository, shared behind each of its traits.
+#[derive(Clone)]
+pub struct Ratings
+{
+    pub get    : Arc<dyn ratings::irepository::get::IRepository>,
+    pub put    : Arc<dyn ratings::irepository::put::IRepository>,
+    pub delete : Arc<dyn ratings::irepository::delete::IRepository>,
+}
+
+impl Ratings
+{
+    pub fn new<R>(repository: R) -> Self
+    where
+        R : ratings::irepository::get::IRepository + ratings::irepository::put::IRepository + ratings::irepository::delete::IRepository + 'static,
+    {
+        let repository = Arc::new(repository);
+        Ratings { get: repository.clone(), put: repository.clone(), delete: repository }
+    }
+}
+
+/// Every repository, built once so that the use cases of a resource share the same instance:
+/// reads see the writes, and the caches, circuit breakers and connection pools are shared.
+pub struct Container
+{
+    pub places     : Places,
+    pub routes     : Routes,
+    pub sources    : Sources,
+    pub users      : Users,
+    pub progress   : Progress,
+    pub ascents    : Ascents,
+    pub comments   : Comments,
+    pub grades     : Grades,
+    pub moderation : Moderation,
+    pub ratings    : Ratings,
+    /// The database the data owned by the core is kept in, if any.
+    pub storage    : Option<Pool>,
+    /// The sources places and routes come from, if any, probed in the background once the server runs.
+    pub upstream   : Option<Upstream>,
+}
+
+impl Container
+{
+    /// Places and routes come from the backend of `CLIMBHUB_BACKEND`,
+    /// the data owned by the core is kept in the database of `CLIMBHUB_DATABASE`, or in memory when it is not set.
+    pub fn from_env() -> Self
+    {
+        let (places, routes, sources, upstream) = match Backend::from_env()
+        {
+            Backend::Sources =>
+            {
+                let upstream = Upstream::from_env();
+                let places = Places::new(PlaceRepository::new(&upstream));
+                let routes = Routes::new(RouteRepository::new(&upstream));
+                (places, routes, Sources::new(SourceRepository::new(&upstream)), Some(upstream))
+            },
+            Backend::Memory =>
+            {
+                let (places, routes) = Self::seeded();
+                (places, routes, Sources::new(SourceRepository::default()), None)
+            },
+        };
+        Container { upstream, ..Self::owning(places, routes, sources, Pool::from_env()) }
+    }
+
+    /// Every repository in memory, places and routes being seeded with the examples.
+    #[cfg(test)]
+    pub fn in_memory() -> Self
+    {
+        let (places, routes) = Self::seeded();
+        Self::owning(places, routes, Sources::new(SourceRepository::default()), None)
+    }
+
+    fn seeded() -> (Places, Routes)
+    {
+        let places = PlaceMemory::seeded();
+        (Places::new(places.clone()), Routes::new(RouteMemory::seeded(places)))
+    }
+
+    /// The repositories of the data owned by the core, in the database or else in memory, next to the given places, routes and sources.
+    fn owning(places: Places, routes: Routes, sources: Sources, storage: Option<Pool>) -> Self
+    {
+        match storage
+        {
+            Some(pool) => Container {
+                places,
+                routes,
+                sources,
+                users      : Users::new(UserStorage::new(pool.clone())),
+                progress   : Progress::new(ProgressStorage::new(pool.clone())),
+                ascents    : Ascents::new(AscentStorage::new(pool.clone())),
+                comments   : Comments::new(CommentStorage::new(pool.clone())),
+                grades     : Grades::new(GradeStorage::new(pool.clone())),
+                moderation : Moderation::new(ModerationStorage::new(pool.clone())),
+                ratings    : Ratings::new(RatingStorage::new(pool.clone())),
+                storage    : Some(pool),
+                upstream   : None,
+            },
+            None => Container {
+                places,
+                routes,
+                sources,
+                users      : Users::new(UserRepository::default()),
+                progress   : Progress::new(ProgressRepository::default()),
+                ascents    : Ascents::new(AscentRepository::default()),
+                comments   : Comments::new(CommentRepository::default()),
+                grades     : Grades::new(GradeRepository::default()),
+                moderation : Moderation::new(ModerationRepository::default()),
+                ratings    : Ratings::new(RatingRepository::default()),
+                storage    : None,
+                upstream   : None,
+            },
+        }
+    }
+
+    /// Hands the repositories to the use cases of the server.
+    pub fn server(self, issuer: TokenIssuer) -> Server
+    {
+        let Container { places, routes, sources, users, progress, ascents, comments, grades, moderation, ratings, storage, upstream } = self;
+        let filter = WordFilter::from_env();
+        let server = Server::new(
+            PlaceGetUseCase::new(places.get),
+            PlacePostUseCase::new(places.post),
+            PlacePutUseCase::new(places.put),
+            PlaceDeleteUseCase::new(places.delete),
+            RouteGetUseCase::new(routes.get.clone(), grades.get.clone(), ratings.get.clone()),
+            RoutePostUseCase::new(routes.post),
+            RoutePutUseCase::new(routes.put),
+            RouteDeleteUseCase::new(routes.delete),
+            SourceGetUseCase::new(sources.get),
+            UserGetUseCase::new(users.get),
+            UserPostUseCase::new(users.post),
+            UserPutUseCase::new(users.put),
+            UserDeleteUseCase::new(users.delete, UserTraces {
+                progress   : progress.delete,
+                ascents    : ascents.delete.clone(),
+                comments   : comments.delete.clone(),
+                grades     : grades.delete.clone(),
+                ratings    : ratings.delete.clone(),
+                moderation : moderation.delete.clone(),
+            }),
+            CommentGetUseCase::new(comments.get.clone()),
+            CommentPostUseCase::new(comments.post, comments.get.clone(), routes.get.clone(), moderation.post.clone(), filter.clone()),
+            CommentPutUseCase::new(comments.put.clone(), comments.get.clone(), moderation.post.clone(), filter),
+            CommentDeleteUseCase::new(comments.delete.clone(), comments.get.clone()),
+            ModerationGetUseCase::new(moderation.get, comments.get.clone()),
+            ModerationPostUseCase::new(moderation.post.clone(), comments.get.clone()),
+            ModerationPutUseCase::new(moderation.post, moderation.delete, comments.get, comments.put, comments.delete),
+            AscentGetUseCase::new(ascents.get.clone()),
+            AscentPostUseCase::new(ascents.post, routes.get.clone()),
+            AscentPutUseCase::new(ascents.put, routes.get.clone()),
+            AscentDeleteUseCase::new(ascents.delete),
+            GradeGetUseCase::new(grades.get.clone(), routes.get.clone()),
+            GradePutUseCase::new(grades.put, grades.get, routes.get.clone()),
+            GradeDeleteUseCase::new(grades.delete),
+            RatingGetUseCase::new(ratings.get.clone(), routes.get.clone()),
+            RatingPutUseCase::new(ratings.put, ratings.get, routes.get.clone()),
+            RatingDeleteUseCase::new(ratings.delete),
+            ProgressGetUseCase::new(progress.get, ascents.get.clone()),
+            ProgressPutUseCase::new(progress.put, routes.get, ascents.get),
+            AuthGetUseCase::new(users.auth_get.clone()),
+            AuthPostUseCase::new(users.auth_get, issuer.clone()),
+            AuthPutUseCase::new(users.auth_put),
+            issuer,
+        );
+        let server = server.platform_admins(platform_admins_from_env());
+        let server = match storage
+        {
+            Some(pool) => server.storage(pool),
+            None => server,
+        };
+        match upstream
+        {
+            Some(upstream) => server.probing(upstream),
+            None => server,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use rocket::{http::{ContentType, Header, Status}, local::asynchronous::Client};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::contexts::auth::domain::{password, tokens::Subject, Access};
+    use crate::contexts::users::domain::{Profile, User};
+
+    /// A client of the server on the seeded in-memory backend, along with a token of the platform admin `root`.
+    async fn client() -> (Client, Header<'static>)
+    {
+        let container = Container::in_memory();
+        let root = User {
+            username : String::from("root"),
+            profile  : Profile { first_name: String::from("Root"), last_name: String::new(), email: String::from("root@example.com") },
+        };
+        container.users.post.create(root, password::hash("password"), String::from("1")).await.unwrap();
+        container.users.auth_put.set_access(String::from("root"), Access::platform_admin()).await.unwrap();
+
+        let issuer = TokenIssuer::new(b"secret");
+        let root = Subject { username: String::from("root"), account: String::from("1") };
+        let token = Header::new("Authorization", format!("Bearer {}", issuer.issue(&root).access_token));
+        let server = container.server(issuer);
+        (Client::tracked(server.rocket()).await.unwrap(), token)
+    }
+
+    /// The `Authorization` header for the access token `username` gets by logging in.
+    async fn login(client: &Client, username: &str, password: &str) -> Header<'static>
+    {
+        let credentials = json!({ "username": username, "password": password });
+        let session: Value = client.post("/auth/login").header(ContentType::JSON).body(credentials.to_string()).dispatch().await.into_json().await.unwrap();
+        Header::new("Authorization", format!("Bearer {}", session["access_token"].as_str().unwrap()))
+    }
+
+    #[tokio::test]
+    async fn test_seeded()
+    {
+        let (client, _) = client().await;
+        let places: Value = client.get("/places").dispatch().await.into_json().await.unwrap();
+        assert_eq!(places[0]["name"], "COUM");
+
+        let response = client.get("/routes/0001-00000001").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        let route: Value = response.into_json().await.unwrap();
+        assert_eq!((&route["name"], &route["placeId"]), (&json!("Rocky Road"), &json!("0001-00000001")));
+
+        assert_eq!(client.get("/routes/0001-00000002").dispatch().await.status(), Status::NotFound);
+        assert_eq!(client.get("/routes/garbage").dispatch().await.status(), Status::BadRequest);
+    }
+
+    #[tokio::test]
+    async fn test_create_route()
+    {
+        let (client, token) = client().await;
+        let mut route: Value = client.get("/routes/0001-00000001").dispatch().await.into_json().await.unwrap();
+        route["name"] = json!("Smooth Road");
+
+        let create = || client.post("/routes").header(ContentType::JSON).body(route.to_string());
+        assert_eq!(create().dispatch().await.status(), Status::Unauthorized);
+        let created: Value = create().header(token).dispatch().await.into_json().await.unwrap();
+        assert_eq!(created["id"], "0001-00000002");
+
+        let routes: Value = client.get("/routes?sort=name").dispatch().await.into_json().await.unwrap();
+        let names: Vec<&Value> = routes.as_array().unwrap().iter().map(|route| &route["name"]).collect();
+        assert_eq!(names, [&json!("Rocky Road"), &json!("Smooth Road")]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_route()
+    {
+        let (client, token) = client().await;
+        let mut route: Value = client.get("/routes/0001-00000001").dispatch().await.into_json().await.unwrap();
+        route["name"] = json!("");
+        route["closingDate"] = json!("2020-12-31");
+
+        let response = client.put("/routes/0001-00000001").header(ContentType::JSON).header(token).body(route.to_string()).dispatch().await;
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        let problem: Value = response.into_json().await.unwrap();
+        assert_eq!(problem["invalid-params"], json!([
+            { "name": "name", "reason": "must not be blank" },
+            { "name": "closingDate", "reason": "must not be before the opening date" },
+        ]));
+
+        // users who may not manage the route are told so before anything about their data
+        let registration = json!({ "username": "jdoe", "password": "password", "first_name": "John", "last_name": "Doe", "email": "jdoe@example.com" });
+        assert_eq!(client.post("/users").header(ContentType::JSON).body(registration.to_string()).dispatch().await.status(), Status::Created);
+        let user = login(&client, "jdoe", "password").await;
+        let response = client.put("/routes/0001-00000001").header(ContentType::JSON).header(user.clone()).body(route.to_string()).dispatch().await;
+        assert_eq!(response.status(), Status::Forbidden);
+        let response = client.post("/routes").header(ContentType::JSON).header(user).body(route.to_string()).dispatch().await;
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_place_id()
+    {
+        let (client, token) = client().await;
+        let mut route: Value = client.get("/routes/0001-00000001").dispatch().await.into_json().await.unwrap();
+        route["placeId"] = json!("abc");
+
+        let responses = [
+            client.post("/routes").header(ContentType::JSON).header(token.clone()).body(route.to_string()).dispatch().await,
+            client.put("/routes/0001-00000001").header(ContentType::JSON).header(token).body(route.to_string()).dispatch().await,
+        ];
+        for response in responses
+        {
+            assert_eq!(response.status(), Status::UnprocessableEntity);
+            let problem: Value = response.into_json().await.unwrap();
+            assert_eq!(problem["invalid-params"], json!([{ "name": "placeId", "reason": "must be an id of the form `FFFF-FFFFFFFF`" }]));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_move_route_to_another_source()
+    {
+        let (client, token) = client().await;
+        let mut route: Value = client.get("/routes/0001-00000001").dispatch().await.into_json().await.unwrap();
+        route["placeId"] = json!("0002-00000001");
+
+        let response = client.put("/routes/0001-00000001").header(token).header(ContentType::JSON).body(route.to_string()).dispatch().await;
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        let problem: Value = response.into_json().await.unwrap();
+        assert_eq!(problem["invalid-params"], json!([{ "name": "placeId", "reason": "must be a place of the source of the route" }]));
+    }
+
+    #[tokio::test]
+    async fn test_platform_admins()
+    {
+        let container = Container::in_memory();
+        let boss = User {
+            username : String::from("Boss"),
+            profile  : Profile { first_name: String::from("Boss"), last_name: String::new(), email: String::from("boss@example.com") },
+        };
+        container.users.post.create(boss, password::hash("password"), String::from("1")).await.unwrap();
+
+        let issuer = TokenIssuer::new(b"secret");
+        let boss = Subject { username: String::from("Boss"), account: String::from("1") };
+        let boss = Header::new("Authorization", format!("Bearer {}", issuer.issue(&boss).access_token));
+        let server = container.server(issuer).platform_admins(vec![String::from("boss"), String::from("ghost")]);
+        let client = Client::tracked(server.rocket()).await.unwrap();
+
+        let access: Value = client.get("/admin/users/boss/access").header(boss).dispatch().await.into_json().await.unwrap();
+        assert_eq!(access["role"], "platform_admin");
+
+        // listed usernames registered once the server runs are not admins
+        let registration = json!({ "username": "ghost", "password": "password", "first_name": "Ghost", "last_name": "", "email": "ghost@example.com" });
+        assert_eq!(client.post("/users").header(ContentType::JSON).body(registration.to_string()).dispatch().await.status(), Status::Created);
+        let ghost = login(&client, "ghost", "password").await;
+        assert_eq!(client.get("/admin/users/ghost/access").header(ghost).dispatch().await.status(), Status::Forbidden);
+    }
+
+    #[tokio::test]
+    async fn test_authorization_scheme()
+    {
+        let (client, root) = client().await;
+        let token = root.value().strip_prefix("Bearer ").unwrap().to_string();
+        for scheme in ["Bearer", "bearer", "BEARER"]
+        {
+            let header = Header::new("Authorization", format!("{scheme} {token}"));
+            assert_eq!(client.get("/admin/users/root/access").header(header).dispatch().await.status(), Status::Ok);
+        }
+        let header = Header::new("Authorization", format!("Basic {token}"));
+        assert_eq!(client.get("/admin/users/root/access").header(header).dispatch().await.status(), Status::Unauthorized);
+    }
+
+    #[tokio::test]
+    async fn test_reregistered_account()
+    {
+        let (client, _) = client().await;
+        let registration = json!({ "username": "jdoe", "password": "password", "first_name": "John", "last_name": "Doe", "email": "jdoe@example.com" });
+        let register = || client.post("/users").header(ContentType::JSON).body(registration.to_string());
+        assert_eq!(register().dispatch().await.status(), Status::Created);
+        let credentials = json!({ "username": "jdoe", "password": "password" });
+        let session: Value = client.post("/auth/login").header(ContentType::JSON).body(credentials.to_string()).dispatch().await.into_json().await.unwrap();
+        let token = Header::new("Authorization", format!("Bearer {}", session["access_token"].as_str().unwrap()));
+        assert_eq!(client.delete("/users/jdoe").header(token.clone()).dispatch().await.status(), Status::NoContent);
+
+        // the tokens of the deleted account are no good for the new one with the same username
+        assert_eq!(register().dispatch().await.status(), Status::Created);
+        let profile = json!({ "first_name": "Jane", "last_name": "Doe", "email": "jane@example.com" });
+        assert_eq!(client.put("/users/jdoe").header(token).header(ContentType::JSON).body(profile.to_string()).dispatch().await.status(), Status::Unauthorized);
+        let refresh = json!({ "refresh_token": session["refresh_token"] });
+        assert_eq!(client.post("/auth/refresh").header(ContentType::JSON).body(refresh.to_string()).dispatch().await.status(), Status::Unauthorized);
+    }
+
+    #[tokio::test]
+    async fn test_private_email()
+    {
+        let (client, root) = client().await;
+        let registration = json!({ "username": "jdoe", "password": "password", "first_name": "John", "last_name": "Doe", "email": "jdoe@example.com" });
+        let registered: Value = client.post("/users").header(ContentType::JSON).body(registration.to_string()).dispatch().await.into_json().await.unwrap();
+        assert_eq!(registered["email"], "jdoe@example.com");
+        let token = login(&client, "jdoe", "password").await;
+
+        let anonymous: Value = client.get("/users/jdoe").dispatch().await.into_json().await.unwrap();
+        assert!(anonymous.get("email").is_none());
+        let own: Value = client.get("/users/jdoe").header(token.clone()).dispatch().await.into_json().await.unwrap();
+        assert_eq!(own["email"], "jdoe@example.com");
+        let admin: Value = client.get("/users/jdoe").header(root).dispatch().await.into_json().await.unwrap();
+        assert_eq!(admin["email"], "jdoe@example.com");
+
+        let users: Value = client.get("/users").header(token).dispatch().await.into_json().await.unwrap();
+        let emails: Vec<(&Value, Option<&Value>)> = users.as_array().unwrap().iter().map(|user| (&user["username"], user.get("email"))).collect();
+        assert_eq!(emails, [(&json!("jdoe"), Some(&json!("jdoe@example.com"))), (&json!("root"), None)]);
+    }
+
+    #[tokio::test]
+    async fn test_moderation_queue()
+    {
+        let (client, root) = client().await;
+        let registration = json!({ "username": "jdoe", "password": "password", "first_name": "John", "last_name": "Doe", "email": "jdoe@example.com" });
+        assert_eq!(client.post("/users").header(ContentType::JSON).body(registration.to_string()).dispatch().await.status(), Status::Created);
+        let token = login(&client, "jdoe", "password").await;
+        let mut ids = Vec::new();
+        for content in ["First", "Second"]
+        {
+            let comment: Value = client.post("/comments/0001-00000001").header(token.clone()).header(ContentType::JSON)
+                .body(json!({ "content": content }).to_string()).dispatch().await.into_json().await.unwrap();
+            let path = format!("/comments/0001-00000001/{}/reports", comment["id"]);
+            let report = json!({ "reason": "Rude" });
+            assert_eq!(client.post(path).header(root.clone()).header(ContentType::JSON).body(report.to_string()).dispatch().await.status(), Status::NoContent);
+            ids.push(comment["id"].clone());
+        }
+        // the author deleted the first comment, there is nothing left to moderate
+        assert_eq!(client.delete(format!("/comments/0001-00000001/{}", ids[0])).header(token).dispatch().await.status(), Status::NoContent);
+
+        let queue: Value = client.get("/moderation/1").header(root).dispatch().await.into_json().await.unwrap();
+        let queued: Vec<&Value> = queue.as_array().unwrap().iter().map(|case| &case["comment"]["id"]).collect();
+        assert_eq!(queued, [&ids[1]]);
+    }
+
+    #[tokio::test]
+    async fn test_deleted_user_data()
+    {
+        let (client, _) = client().await;
+        let registration = json!({ "username": "jdoe", "password": "password", "first_name": "John", "last_name": "Doe", "email": "jdoe@example.com" });
+        let register = || client.post("/users").header(ContentType::JSON).body(registration.to_string());
+        assert_eq!(register().dispatch().await.status(), Status::Created);
+        let token = login(&client, "jdoe", "password").await;
+        assert_eq!(client.put("/progress/jdoe/0001-00000001/liked").header(token.clone()).dispatch().await.status(), Status::Ok);
+        let suggestion = json!({ "grade": "6a" });
+        assert_eq!(client.put("/grades/0001-00000001").header(token.clone()).header(ContentType::JSON).body(suggestion.to_string()).dispatch().await.status(), Status::Ok);
+        let comment: Value = client.post("/comments/0001-00000001").header(token.clone()).header(ContentType::JSON)
+            .body(json!({ "content": "Nice route" }).to_string()).dispatch().await.into_json().await.unwrap();
+        assert_eq!(client.delete("/users/jdoe").header(token).dispatch().await.status(), Status::NoContent);
+
+        // whoever registers the username next does not inherit anything
+        assert_eq!(register().dispatch().await.status(), Status::Created);
+        let token = login(&client, "jdoe", "password").await;
+        let progress: Value = client.get("/progress/jdoe").dispatch().await.into_json().await.unwrap();
+        assert_eq!(progress, json!([]));
+        let grades: Value = client.get("/grades/0001-00000001").dispatch().await.into_json().await.unwrap();
+        assert_eq!(grades["votes"], json!([]));
+        let path = format!("/comments/0001-00000001/{}", comment["id"]);
+        assert_eq!(client.get(path.clone()).dispatch().await.status(), Status::NotFound);
+        let edit = json!({ "content": "Mine now" });
+        assert_ne!(client.put(path).header(token).header(ContentType::JSON).body(edit.to_string()).dispatch().await.status(), Status::Ok);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_registration()
+    {
+        let (client, _) = client().await;
+        let registration = json!({ "username": "jdoe", "password": "short", "first_name": "John", "last_name": "Doe", "email": "jdoe@example.com" });
+        let response = client.post("/users").header(ContentType::JSON).body(registration.to_string()).dispatch().await;
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        let problem: Value = response.into_json().await.unwrap();
+        assert_eq!(problem["invalid-params"], json!([{ "name": "password", "reason": "must be at least 8 characters long" }]));
+
+        let registration = json!({ "username": "jdoe", "password": "short", "first_name": "John", "last_name": "Doe", "email": "jdoe" });
+        let response = client.post("/users").header(ContentType::JSON).body(registration.to_string()).dispatch().await;
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        let problem: Value = response.into_json().await.unwrap();
+        assert_eq!(problem["invalid-params"], json!([
+            { "name": "email", "reason": "must be an email address" },
+            { "name": "password", "reason": "must be at least 8 characters long" },
+        ]));
+
+        let registration = json!({ "username": "j/doe", "password": "password", "first_name": "John", "last_name": "Doe", "email": "jdoe@example.com" });
+        let response = client.post("/users").header(ContentType::JSON).body(registration.to_string()).dispatch().await;
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        let problem: Value = response.into_json().await.unwrap();
+        assert_eq!(problem["invalid-params"], json!([{ "name": "username", "reason": "must not hold whitespace or `/`" }]));
+
+        let registration = json!({ "username": "jdoe", "password": "password", "first_name": "John", "last_name": "Doe", "email": "jdoe@example.com" });
+        assert_eq!(client.post("/users").header(ContentType::JSON).body(registration.to_string()).dispatch().await.status(), Status::Created);
+        let token = login(&client, "jdoe", "password").await;
+        let profile = json!({ "first_name": "John", "last_name": "Doe", "email": "jdoe at example.com" });
+        let response = client.put("/users/jdoe").header(token).header(ContentType::JSON).body(profile.to_string()).dispatch().await;
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        let problem: Value = response.into_json().await.unwrap();
+        assert_eq!(problem["invalid-params"], json!([{ "name": "email", "reason": "must be an email address" }]));
+    }
+
+    #[tokio::test]
+    async fn test_sources()
+    {
+        let (client, _) = client().await;
+        let sources: Value = client.get("/sources").dispatch().await.into_json().await.unwrap();
+        assert_eq!(sources, json!([]));
+        assert_eq!(client.get("/sources/1/health").dispatch().await.status(), Status::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_problems()
+    {
+        let (client, _) = client().await;
+        let response = client.get("/routes/0001-00000002").dispatch().await;
+        assert_eq!(response.content_type(), Some(ContentType::new("application", "problem+json")));
+        let problem: Value = response.into_json().await.unwrap();
+        assert_eq!(problem, json!({
+            "type"   : "/problems/not-found",
+            "title"  : "Not Found",
+            "status" : 404,
+            "detail" : "Route with id `0001-00000002` was not found.",
+            "id"     : "0001-00000002",
+        }));
+
+        // failed request guards go through the catcher
+        let problem: Value = client.delete("/routes/0001-00000001").dispatch().await.into_json().await.unwrap();
+        assert_eq!((&problem["type"], &problem["status"]), (&json!("/problems/unauthorized"), &json!(401)));
+
+        let openapi: Value = client.get("/openapi.json").dispatch().await.into_json().await.unwrap();
+        assert!(openapi["components"]["schemas"]["Problem"].is_object());
+        assert!(openapi["paths"]["/routes/{id}"]["get"]["responses"]["4XX"]["content"]["application/problem+json"].is_object());
+    }
+}
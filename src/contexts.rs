@@ -16,6 +16,105 @@ pub mod routes
     pub mod use_cases;
     pub mod irepository;
 
+    mod domain_to_router;
+    pub mod router_to_domain;
+}
+
+pub mod users
+{
+    pub mod router;
+    pub mod domain;
+    pub mod use_cases;
+    pub mod irepository;
+
     mod domain_to_router;
     mod router_to_domain;
-}
\ No newline at end of file
+}
+
+pub mod comments
+{
+    pub mod router;
+    pub mod domain;
+    pub mod use_cases;
+    pub mod irepository;
+
+    mod domain_to_router;
+    mod router_to_domain;
+}
+
+pub mod ascents
+{
+    pub mod router;
+    pub mod domain;
+    pub mod use_cases;
+    pub mod irepository;
+
+    mod domain_to_router;
+    mod router_to_domain;
+}
+
+pub mod grades
+{
+    pub mod router;
+    pub mod domain;
+    pub mod use_cases;
+    pub mod irepository;
+
+    mod domain_to_router;
+    mod router_to_domain;
+}
+
+pub mod moderation
+{
+    pub mod router;
+    pub mod domain;
+    pub mod use_cases;
+    pub mod irepository;
+
+    mod domain_to_router;
+    mod router_to_domain;
+}
+
+pub mod ratings
+{
+    pub mod router;
+    pub mod domain;
+    pub mod use_cases;
+    pub mod irepository;
+
+    mod domain_to_router;
+    mod router_to_domain;
+}
+
+pub mod progress
+{
+    pub mod router;
+    pub mod domain;
+    pub mod use_cases;
+    pub mod irepository;
+
+    mod domain_to_router;
+    mod router_to_domain;
+}
+
+pub mod auth
+{
+    pub mod router;
+    pub mod domain;
+    pub mod use_cases;
+    pub mod irepository;
+
+    mod domain_to_router;
+    mod router_to_domain;
+}
+
+pub mod sources
+{
+    pub mod router;
+    pub mod domain;
+    pub mod use_cases;
+    pub mod irepository;
+
+    mod domain_to_router;
+    pub mod router_to_domain;
+}
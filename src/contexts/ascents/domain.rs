@@ -0,0 +1,106 @@
+pub use super::super::users::domain::Username;
+pub use super::super::routes::domain::{Date, Grade, RouteId};
+
+pub type AscentId = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style
+{
+    Onsight,
+    Flash,
+    Redpoint,
+    Repeat,
+    Toprope,
+}
+
+impl Style
+{
+    /// Every style but top rope sends the route.
+    pub fn is_send(self) -> bool
+    {
+        !matches!(self, Style::Toprope)
+    }
+
+    /// Onsights and flashes are sent at the first attempt.
+    pub fn is_flash(self) -> bool
+    {
+        matches!(self, Style::Onsight | Style::Flash)
+    }
+}
+
+/// An entry of a user's logbook.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ascent
+{
+    pub id   : AscentId,
+    pub data : AscentData,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AscentData
+{
+    pub route_id : RouteId,
+    pub date     : Date,
+    pub style    : Style,
+    pub attempts : u32,
+    /// The grade the user thinks the route deserves.
+    pub grade    : Option<Grade>,
+    /// From 1 to 5 stars.
+    pub rating   : Option<u8>,
+    /// Only shown to the user.
+    pub notes    : String,
+}
+
+impl AscentData
+{
+    /// What makes the entry inconsistent, if anything.
+    pub fn problem(&self) -> Option<&'static str>
+    {
+        if self.attempts == 0
+        {
+            return Some("An ascent takes at least one attempt.");
+        }
+        if self.style.is_flash() && self.attempts != 1
+        {
+            return Some("Onsights and flashes take a single attempt.");
+        }
+        if let Some(rating) = self.rating
+        {
+            if !(1..=5).contains(&rating)
+            {
+                return Some("Ratings go from 1 to 5.");
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn data(style: Style, attempts: u32, rating: Option<u8>) -> AscentData
+    {
+        AscentData {
+            route_id : String::from("0001-00000001"),
+            date     : Date::from_ymd_opt(2023, 5, 1).unwrap(),
+            style,
+            attempts,
+            grade    : None,
+            rating,
+            notes    : String::new(),
+        }
+    }
+
+    #[test]
+    fn test_problem()
+    {
+        assert_eq!(data(Style::Redpoint, 4, Some(5)).problem(), None);
+        assert_eq!(data(Style::Flash, 1, None).problem(), None);
+        assert!(data(Style::Redpoint, 0, None).problem().is_some());
+        assert!(data(Style::Onsight, 2, None).problem().is_some());
+        assert!(data(Style::Repeat, 1, Some(0)).problem().is_some());
+        assert!(data(Style::Repeat, 1, Some(6)).problem().is_some());
+    }
+}
@@ -0,0 +1,29 @@
+use super::{domain, router};
+use crate::typeutil::routers::Date;
+
+pub fn style(s: domain::Style) -> router::Style
+{
+    match s
+    {
+        domain::Style::Onsight  => router::Style::Onsight,
+        domain::Style::Flash    => router::Style::Flash,
+        domain::Style::Redpoint => router::Style::Redpoint,
+        domain::Style::Repeat   => router::Style::Repeat,
+        domain::Style::Toprope  => router::Style::Toprope,
+    }
+}
+
+/// Notes are left out unless `notes` is set.
+pub fn ascent(a: domain::Ascent, notes: bool) -> router::Ascent
+{
+    router::Ascent {
+        id       : a.id,
+        route_id : a.data.route_id,
+        date     : Date(a.data.date),
+        style    : style(a.data.style),
+        attempts : a.data.attempts,
+        grade    : a.data.grade.map(|grade| grade.text),
+        rating   : a.data.rating,
+        notes    : notes.then_some(a.data.notes),
+    }
+}
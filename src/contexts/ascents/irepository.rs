@@ -0,0 +1,55 @@
+pub mod get
+{
+    use async_trait::async_trait;
+    use crate::errors::{GetAllError, GetError};
+    use super::super::domain::{Ascent, AscentId, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// Usernames are compared regardless of case.
+        async fn get_all(&self, username: Username) -> Result<Vec<Ascent>, GetAllError>;
+        async fn get(&self, username: Username, id: AscentId) -> Result<Ascent, GetError>;
+    }
+}
+
+pub mod post
+{
+    use async_trait::async_trait;
+    use crate::errors::CreateError;
+    use super::super::domain::{Ascent, AscentData, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        async fn create(&self, username: Username, data: AscentData) -> Result<Ascent, CreateError>;
+    }
+}
+
+pub mod put
+{
+    use async_trait::async_trait;
+    use crate::errors::UpdateError;
+    use super::super::domain::{AscentData, AscentId, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        async fn update(&self, username: Username, id: AscentId, data: AscentData) -> Result<(), UpdateError>;
+    }
+}
+
+pub mod delete
+{
+    use async_trait::async_trait;
+    use crate::errors::DeleteError;
+    use super::super::domain::{AscentId, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        async fn delete(&self, username: Username, id: AscentId) -> Result<(), DeleteError>;
+        /// Removes every ascent of the user, there may be none.
+        async fn forget(&self, username: Username) -> Result<(), DeleteError>;
+    }
+}
@@ -0,0 +1,187 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::typeutil::routers::Date;
+
+pub mod get
+{
+    use rocket::{get, serde::json::Json, State, http::Status};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::{GetAllError, GetError};
+    use crate::problems::Problem;
+
+    use super::super::{use_cases::get::UseCase, domain_to_router};
+    use super::Ascent;
+
+    /// # Get the logbook of a user
+    ///
+    /// Notes are only given to the user.
+    #[openapi(tag = "Ascent")]
+    #[get("/ascents/<username>")]
+    pub async fn get_ascents(username: String, user: Option<AuthenticatedUser>, use_case: &State<UseCase>) -> Result<Json<Vec<Ascent>>, Problem>
+    {
+        let notes = matches!(user, Some(user) if user.may_act_as(&username));
+        match use_case.get_all(username).await
+        {
+            Ok(ascents) => Ok(Json(ascents.into_iter().map(|ascent| domain_to_router::ascent(ascent, notes)).collect())),
+            Err(GetAllError::InternalServerError) => Err(Problem::internal()),
+        }
+    }
+
+    /// # Get an ascent of a user
+    ///
+    /// Notes are only given to the user.
+    #[openapi(tag = "Ascent")]
+    #[get("/ascents/<username>/<id>")]
+    pub async fn get_ascent(username: String, id: u64, user: Option<AuthenticatedUser>, use_case: &State<UseCase>) -> Result<Json<Ascent>, Problem>
+    {
+        let notes = matches!(user, Some(user) if user.may_act_as(&username));
+        match use_case.get(username, id).await
+        {
+            Ok(ascent) => Ok(Json(domain_to_router::ascent(ascent, notes))),
+            Err(GetError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("Ascent `{id}` was not found.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod post
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{post, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::CreateError;
+    use crate::problems::Problem;
+
+    use super::super::{use_cases::post::UseCase, domain_to_router, router_to_domain};
+    use super::{Ascent, AscentData};
+
+    /// # Log an ascent
+    ///
+    /// Returns the logged ascent with its id. Users can only log their own ascents.
+    #[openapi(tag = "Ascent")]
+    #[post("/ascents/<username>", data = "<data>")]
+    pub async fn log_ascent(username: String, data: Json<AscentData>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::Created<Json<Ascent>>, Problem>
+    {
+        user.acting_as(&username)?;
+        match use_case.log(username.clone(), router_to_domain::ascent_data(data.into_inner())?).await
+        {
+            Ok(ascent) => Ok(status::Created::new(format!("/ascents/{username}/{}", ascent.id)).body(Json(domain_to_router::ascent(ascent, true)))),
+            Err(CreateError::NonExistingReference(id)) => Err(Problem::new(Status::NotFound, format!("No existing route with id `{id}`.")).id(id)),
+            Err(CreateError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(CreateError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the route with id `{id}`.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod put
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{put, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::UpdateError;
+    use crate::problems::Problem;
+
+    use super::super::{use_cases::put::UseCase, router_to_domain};
+    use super::AscentData;
+
+    /// # Edit an ascent
+    ///
+    /// Users can only edit their own ascents.
+    #[openapi(tag = "Ascent")]
+    #[put("/ascents/<username>/<id>", data = "<data>")]
+    pub async fn edit_ascent(username: String, id: u64, data: Json<AscentData>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
+    {
+        user.acting_as(&username)?;
+        match use_case.edit(username, id, router_to_domain::ascent_data(data.into_inner())?).await
+        {
+            Ok(()) => Ok(status::NoContent),
+            Err(UpdateError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No existing ascent `{id}`.")).id(id)),
+            Err(UpdateError::NonExistingReference(id)) => Err(Problem::new(Status::NotFound, format!("No existing route with id `{id}`.")).id(id)),
+            Err(UpdateError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(UpdateError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the route with id `{id}`.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod delete
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{delete, State};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::DeleteError;
+    use crate::problems::Problem;
+
+    use super::super::use_cases::delete::UseCase;
+
+    /// # Delete an ascent
+    ///
+    /// Users can only delete their own ascents.
+    #[openapi(tag = "Ascent")]
+    #[delete("/ascents/<username>/<id>")]
+    pub async fn delete_ascent(username: String, id: u64, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
+    {
+        user.acting_as(&username)?;
+        match use_case.delete(username, id).await
+        {
+            Ok(()) => Ok(status::NoContent),
+            Err(DeleteError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No existing ascent `{id}`.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Style
+{
+    Onsight,
+    Flash,
+    Redpoint,
+    Repeat,
+    Toprope,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct AscentData
+{
+    pub route_id : String,
+    pub date     : Date,
+    pub style    : Style,
+    pub attempts : u32,
+    /// The grade the climber thinks the route deserves, in any grading system.
+    pub grade    : Option<String>,
+    /// From 1 to 5 stars.
+    pub rating   : Option<u8>,
+    /// Private notes, only shown to the climber.
+    #[serde(default)]
+    pub notes    : String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Ascent
+{
+    pub id       : u64,
+    pub route_id : String,
+    pub date     : Date,
+    pub style    : Style,
+    pub attempts : u32,
+    pub grade    : Option<String>,
+    pub rating   : Option<u8>,
+    /// Only given to the climber.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes    : Option<String>,
+}
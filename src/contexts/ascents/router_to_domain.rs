@@ -0,0 +1,35 @@
+use rocket::http::Status;
+
+use super::{domain, router};
+use crate::problems::Problem;
+
+pub fn style(s: router::Style) -> domain::Style
+{
+    match s
+    {
+        router::Style::Onsight  => domain::Style::Onsight,
+        router::Style::Flash    => domain::Style::Flash,
+        router::Style::Redpoint => domain::Style::Redpoint,
+        router::Style::Repeat   => domain::Style::Repeat,
+        router::Style::Toprope  => domain::Style::Toprope,
+    }
+}
+
+/// 400 for an inconsistent entry.
+pub fn ascent_data(ad: router::AscentData) -> Result<domain::AscentData, Problem>
+{
+    let data = domain::AscentData {
+        route_id : ad.route_id,
+        date     : *ad.date,
+        style    : style(ad.style),
+        attempts : ad.attempts,
+        grade    : ad.grade.as_deref().map(domain::Grade::parse),
+        rating   : ad.rating,
+        notes    : ad.notes,
+    };
+    match data.problem()
+    {
+        Some(problem) => Err(Problem::new(Status::BadRequest, String::from(problem))),
+        None => Ok(data),
+    }
+}
@@ -0,0 +1,128 @@
+pub mod get
+{
+    use std::sync::Arc;
+
+    use crate::errors::{GetAllError, GetError};
+
+    use super::super::{domain::{Ascent, AscentId, Username}, irepository::get::IRepository};
+    pub struct UseCase
+    {
+        repository: Arc<dyn IRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
+        {
+            Self { repository: repo }
+        }
+
+        pub async fn get_all(&self, username: Username) -> Result<Vec<Ascent>, GetAllError>
+        {
+            self.repository.get_all(username).await
+        }
+
+        pub async fn get(&self, username: Username, id: AscentId) -> Result<Ascent, GetError>
+        {
+            self.repository.get(username, id).await
+        }
+    }
+}
+
+pub mod post
+{
+    use std::sync::Arc;
+
+    use crate::errors::{CreateError, GetError};
+    use crate::contexts::routes::irepository::get::IRepository as RouteRepository;
+
+    use super::super::{domain::{Ascent, AscentData, Username}, irepository::post::IRepository};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        routes     : Arc<dyn RouteRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, routes: Arc<dyn RouteRepository>) -> Self
+        {
+            Self { repository: repo, routes }
+        }
+
+        /// The route must exist in its source, its id is stored as the source gives it.
+        pub async fn log(&self, username: Username, mut data: AscentData) -> Result<Ascent, CreateError>
+        {
+            data.route_id = match self.routes.get(data.route_id).await
+            {
+                Ok(route) => route.id,
+                Err(GetError::NonExistingId(id)) => return Err(CreateError::NonExistingReference(id)),
+                Err(GetError::MalformedId(id)) => return Err(CreateError::MalformedId(id)),
+                Err(GetError::UnknownSource(id)) => return Err(CreateError::UnknownSource(id)),
+                Err(GetError::Source(failure)) => return Err(CreateError::Source(failure)),
+                Err(GetError::BadUpstreamId(_) | GetError::InternalServerError) => return Err(CreateError::InternalServerError),
+            };
+            self.repository.create(username, data).await
+        }
+    }
+}
+
+pub mod put
+{
+    use std::sync::Arc;
+
+    use crate::errors::{GetError, UpdateError};
+    use crate::contexts::routes::irepository::get::IRepository as RouteRepository;
+
+    use super::super::{domain::{AscentData, AscentId, Username}, irepository::put::IRepository};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        routes     : Arc<dyn RouteRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, routes: Arc<dyn RouteRepository>) -> Self
+        {
+            Self { repository: repo, routes }
+        }
+
+        /// The route must exist in its source, its id is stored as the source gives it.
+        pub async fn edit(&self, username: Username, id: AscentId, mut data: AscentData) -> Result<(), UpdateError>
+        {
+            data.route_id = match self.routes.get(data.route_id).await
+            {
+                Ok(route) => route.id,
+                Err(GetError::NonExistingId(id)) => return Err(UpdateError::NonExistingReference(id)),
+                Err(GetError::MalformedId(id)) => return Err(UpdateError::MalformedId(id)),
+                Err(GetError::UnknownSource(id)) => return Err(UpdateError::UnknownSource(id)),
+                Err(GetError::Source(failure)) => return Err(UpdateError::Source(failure)),
+                Err(GetError::BadUpstreamId(_) | GetError::InternalServerError) => return Err(UpdateError::InternalServerError),
+            };
+            self.repository.update(username, id, data).await
+        }
+    }
+}
+
+pub mod delete
+{
+    use std::sync::Arc;
+
+    use crate::errors::DeleteError;
+
+    use super::super::{domain::{AscentId, Username}, irepository::delete::IRepository};
+    pub struct UseCase
+    {
+        repository: Arc<dyn IRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
+        {
+            Self { repository: repo }
+        }
+
+        pub async fn delete(&self, username: Username, id: AscentId) -> Result<(), DeleteError>
+        {
+            self.repository.delete(username, id).await
+        }
+    }
+}
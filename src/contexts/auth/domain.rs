@@ -0,0 +1,321 @@
+use std::{collections::BTreeSet, env};
+
+pub use super::super::users::domain::Username;
+pub use super::super::sources::domain::SourceId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Role
+{
+    #[default]
+    Climber,
+    Setter,
+    GymAdmin,
+    PlatformAdmin,
+}
+
+/// What a user may change: setters manage the routes of their sources,
+/// gym admins the places and routes of their sources and platform admins everything.
+/// Setters and gym admins also moderate the comments on the routes of their sources.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Access
+{
+    pub role    : Role,
+    /// Sources the role is scoped to, only meaningful for setters and gym admins.
+    pub sources : BTreeSet<SourceId>,
+}
+
+impl Access
+{
+    pub fn platform_admin() -> Self
+    {
+        Access { role: Role::PlatformAdmin, sources: BTreeSet::new() }
+    }
+
+    pub fn is_platform_admin(&self) -> bool
+    {
+        self.role == Role::PlatformAdmin
+    }
+
+    pub fn can_manage_routes(&self, source: SourceId) -> bool
+    {
+        match self.role
+        {
+            Role::Climber => false,
+            Role::Setter | Role::GymAdmin => self.sources.contains(&source),
+            Role::PlatformAdmin => true,
+        }
+    }
+
+    pub fn can_manage_places(&self, source: SourceId) -> bool
+    {
+        match self.role
+        {
+            Role::Climber | Role::Setter => false,
+            Role::GymAdmin => self.sources.contains(&source),
+            Role::PlatformAdmin => true,
+        }
+    }
+
+    pub fn can_moderate(&self, source: SourceId) -> bool
+    {
+        self.can_manage_routes(source)
+    }
+}
+
+/// Usernames given in `CLIMBHUB_PLATFORM_ADMINS` (comma separated), whose accounts are granted platform admin access at startup.
+pub fn platform_admins_from_env() -> Vec<Username>
+{
+    env::var("CLIMBHUB_PLATFORM_ADMINS").unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|username| !username.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Drawn at random when a user registers, it tells the account apart from the ones later registered with the same username.
+pub type AccountId = String;
+
+pub fn new_account_id() -> AccountId
+{
+    format!("{:032x}", rand::random::<u128>())
+}
+
+/// What a user logs in with, as stored.
+#[derive(Debug, Clone)]
+pub struct Credentials
+{
+    /// The username as registered, whatever the case it is logged in with.
+    pub username      : Username,
+    pub account       : AccountId,
+    pub password_hash : String,
+}
+
+/// Tokens given to a user who logged in.
+#[derive(Debug, Clone)]
+pub struct Session
+{
+    pub access_token  : String,
+    pub refresh_token : String,
+    /// Lifetime of the access token in seconds.
+    pub expires_in    : u64,
+}
+
+pub mod password
+{
+    use std::sync::OnceLock;
+
+    use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier, password_hash::{SaltString, rand_core::OsRng}};
+
+    use crate::errors::InvalidField;
+
+    /// In characters.
+    pub const MIN_LENGTH: usize = 8;
+
+    pub fn validate(password: &str) -> Result<(), InvalidField>
+    {
+        match password.chars().count()
+        {
+            length if length < MIN_LENGTH => Err(InvalidField::new("password", format!("must be at least {MIN_LENGTH} characters long"))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Hashes the password with Argon2 and a random salt, the result holds the parameters to verify it.
+    pub fn hash(password: &str) -> String
+    {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .expect("Argon2 failed to hash a password")
+            .to_string()
+    }
+
+    pub fn verify(password: &str, hash: &str) -> bool
+    {
+        match PasswordHash::new(hash)
+        {
+            Ok(hash) => Argon2::default().verify_password(password.as_bytes(), &hash).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    /// A hash of no user's password, verified against when logging in as an unknown user
+    /// so that it takes as long as a wrong password and does not tell which usernames exist.
+    pub fn decoy() -> &'static str
+    {
+        static DECOY: OnceLock<String> = OnceLock::new();
+        DECOY.get_or_init(|| hash("decoy"))
+    }
+}
+
+pub mod tokens
+{
+    use std::env;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+    use serde::{Deserialize, Serialize};
+
+    use super::{AccountId, Session, Username};
+
+    const ACCESS_LIFETIME  : u64 = 15 * 60;
+    const REFRESH_LIFETIME : u64 = 7 * 24 * 60 * 60;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Kind
+    {
+        Access,
+        Refresh,
+    }
+
+    /// The account a token is issued to.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Subject
+    {
+        pub username : Username,
+        pub account  : AccountId,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Claims
+    {
+        sub     : Username,
+        account : AccountId,
+        exp     : u64,
+        kind    : Kind,
+    }
+
+    /// Signs and verifies the JWTs (HS256) handed to users.
+    #[derive(Clone)]
+    pub struct Issuer
+    {
+        encoding : EncodingKey,
+        decoding : DecodingKey,
+    }
+
+    impl Issuer
+    {
+        pub fn new(secret: &[u8]) -> Self
+        {
+            Issuer {
+                encoding : EncodingKey::from_secret(secret),
+                decoding : DecodingKey::from_secret(secret),
+            }
+        }
+
+        pub fn from_env() -> Self
+        {
+            let secret = env::var("CLIMBHUB_JWT_SECRET").expect("CLIMBHUB_JWT_SECRET not set");
+            Self::new(secret.as_bytes())
+        }
+
+        pub fn issue(&self, subject: &Subject) -> Session
+        {
+            Session {
+                access_token  : self.sign(subject, Kind::Access, ACCESS_LIFETIME),
+                refresh_token : self.sign(subject, Kind::Refresh, REFRESH_LIFETIME),
+                expires_in    : ACCESS_LIFETIME,
+            }
+        }
+
+        /// The account the token was issued to, if the token is valid, unexpired and of the given kind.
+        /// Whether the account still exists is up to the caller.
+        pub fn verify(&self, token: &str, kind: Kind) -> Option<Subject>
+        {
+            let claims = decode::<Claims>(token, &self.decoding, &Validation::default()).ok()?.claims;
+            (claims.kind == kind).then_some(Subject { username: claims.sub, account: claims.account })
+        }
+
+        fn sign(&self, subject: &Subject, kind: Kind, lifetime: u64) -> String
+        {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_secs());
+            let claims = Claims { sub: subject.username.clone(), account: subject.account.clone(), exp: now + lifetime, kind };
+            encode(&Header::default(), &claims, &self.encoding).expect("Failed to sign a token")
+        }
+    }
+
+    #[cfg(test)]
+    mod tests
+    {
+        use super::*;
+
+        #[test]
+        fn test_issue_and_verify()
+        {
+            let issuer = Issuer::new(b"secret");
+            let jdoe = Subject { username: String::from("jdoe"), account: String::from("1") };
+            let session = issuer.issue(&jdoe);
+
+            assert_eq!(issuer.verify(&session.access_token, Kind::Access), Some(jdoe.clone()));
+            assert_eq!(issuer.verify(&session.refresh_token, Kind::Refresh), Some(jdoe));
+            // a token can't be used for the other purpose
+            assert!(issuer.verify(&session.refresh_token, Kind::Access).is_none());
+            // nor verified with another secret
+            assert!(Issuer::new(b"other").verify(&session.access_token, Kind::Access).is_none());
+            assert!(issuer.verify("not a token", Kind::Access).is_none());
+        }
+
+        #[test]
+        fn test_expired()
+        {
+            let issuer = Issuer::new(b"secret");
+            let claims = Claims { sub: String::from("jdoe"), account: String::from("1"), exp: 1, kind: Kind::Access };
+            let token = encode(&Header::default(), &claims, &issuer.encoding).unwrap();
+            assert!(issuer.verify(&token, Kind::Access).is_none());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::{new_account_id, password, Access, Role};
+
+    #[test]
+    fn test_access()
+    {
+        let access = |role, sources: &[u16]| Access { role, sources: sources.iter().copied().collect() };
+
+        assert!(!access(Role::Climber, &[1]).can_manage_routes(1));
+        assert!(access(Role::Setter, &[1, 2]).can_manage_routes(2));
+        assert!(!access(Role::Setter, &[1, 2]).can_manage_routes(3));
+        assert!(!access(Role::Setter, &[1]).can_manage_places(1));
+        assert!(access(Role::GymAdmin, &[1]).can_manage_places(1));
+        assert!(access(Role::GymAdmin, &[1]).can_manage_routes(1));
+        assert!(!access(Role::GymAdmin, &[1]).can_manage_places(2));
+        assert!(Access::platform_admin().can_manage_places(42));
+        assert!(Access::platform_admin().can_manage_routes(42));
+        assert!(access(Role::Setter, &[1]).can_moderate(1));
+        assert!(!access(Role::Climber, &[1]).can_moderate(1));
+    }
+
+    #[test]
+    fn test_password()
+    {
+        let hash = password::hash("correct horse");
+        assert!(hash.starts_with("$argon2"));
+        assert!(password::verify("correct horse", &hash));
+        assert!(!password::verify("wrong horse", &hash));
+        assert!(!password::verify("correct horse", "not a hash"));
+        assert!(password::decoy().starts_with("$argon2"));
+    }
+
+    #[test]
+    fn test_password_length()
+    {
+        assert!(password::validate("1234567").is_err());
+        assert!(password::validate("12345678").is_ok());
+        // characters are counted, not bytes
+        assert!(password::validate("éééé").is_err());
+    }
+
+    #[test]
+    fn test_account_id()
+    {
+        let account = new_account_id();
+        assert_eq!(account.len(), 32);
+        assert_ne!(account, new_account_id());
+    }
+}
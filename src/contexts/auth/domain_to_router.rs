@@ -0,0 +1,30 @@
+use super::{domain, router};
+
+pub fn session(s: domain::Session) -> router::Session
+{
+    router::Session {
+        access_token  : s.access_token,
+        refresh_token : s.refresh_token,
+        token_type    : String::from("Bearer"),
+        expires_in    : s.expires_in,
+    }
+}
+
+pub fn role(r: domain::Role) -> router::Role
+{
+    match r
+    {
+        domain::Role::Climber       => router::Role::Climber,
+        domain::Role::Setter        => router::Role::Setter,
+        domain::Role::GymAdmin      => router::Role::GymAdmin,
+        domain::Role::PlatformAdmin => router::Role::PlatformAdmin,
+    }
+}
+
+pub fn access(a: domain::Access) -> router::Access
+{
+    router::Access {
+        role    : role(a.role),
+        sources : a.sources.into_iter().collect(),
+    }
+}
@@ -0,0 +1,28 @@
+pub mod get
+{
+    use async_trait::async_trait;
+    use crate::errors::{AuthError, GetError};
+    use super::super::domain::{Access, Credentials, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// `None` if there is no such user, usernames are compared regardless of case.
+        async fn credentials(&self, username: Username) -> Result<Option<Credentials>, AuthError>;
+        /// Users are climbers until given another access.
+        async fn access(&self, username: Username) -> Result<Access, GetError>;
+    }
+}
+
+pub mod put
+{
+    use async_trait::async_trait;
+    use crate::errors::UpdateError;
+    use super::super::domain::{Access, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        async fn set_access(&self, username: Username, access: Access) -> Result<(), UpdateError>;
+    }
+}
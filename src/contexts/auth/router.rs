@@ -0,0 +1,255 @@
+use rocket::{Request, http::Status, request::{FromRequest, Outcome}};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    okapi::{
+        openapi3::{Object, RefOr, Response, Responses, SecurityRequirement, SecurityScheme, SecuritySchemeData},
+        schemars::{self, JsonSchema},
+    },
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::GetError;
+use crate::problems::Problem;
+
+use super::{domain::{self, tokens::{Issuer, Kind}}, use_cases::get::UseCase as AccessUseCase};
+
+pub mod get
+{
+    use rocket::{get, serde::json::Json, State, http::Status};
+    use rocket_okapi::openapi;
+
+    use crate::errors::GetError;
+    use crate::problems::Problem;
+
+    use super::super::{use_cases::get::UseCase, domain_to_router};
+    use super::{Access, AuthenticatedUser, platform_admin};
+
+    /// # Get the access of a user
+    ///
+    /// Only platform admins may see it.
+    #[openapi(tag = "Admin")]
+    #[get("/admin/users/<username>/access")]
+    pub async fn get_access(username: String, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<Json<Access>, Problem>
+    {
+        platform_admin(&user)?;
+        match use_case.access(username).await
+        {
+            Ok(access) => Ok(Json(domain_to_router::access(access))),
+            Err(GetError::NonExistingId(username)) => Err(Problem::new(Status::NotFound, format!("User `{username}` was not found.")).id(username)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+
+pub mod post
+{
+    use rocket::{post, serde::json::Json, State, http::Status};
+    use rocket_okapi::openapi;
+
+    use crate::errors::AuthError;
+    use crate::problems::Problem;
+
+    use super::super::{use_cases::post::UseCase, domain_to_router};
+    use super::{Credentials, Refresh, Session};
+
+    /// # Log in with a username and a password
+    ///
+    /// Returns a short lived access token, to give as `Authorization: Bearer <token>`,
+    /// and a refresh token to get new ones.
+    #[openapi(tag = "Auth")]
+    #[post("/auth/login", data = "<credentials>")]
+    pub async fn login(credentials: Json<Credentials>, use_case: &State<UseCase>) -> Result<Json<Session>, Problem>
+    {
+        let credentials = credentials.into_inner();
+        match use_case.login(credentials.username, credentials.password).await
+        {
+            Ok(session) => Ok(Json(domain_to_router::session(session))),
+            Err(AuthError::InvalidCredentials) => Err(Problem::new(Status::Unauthorized, String::from("Invalid username or password."))),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+
+    /// # Get new tokens with a refresh token
+    #[openapi(tag = "Auth")]
+    #[post("/auth/refresh", data = "<refresh>")]
+    pub async fn refresh(refresh: Json<Refresh>, use_case: &State<UseCase>) -> Result<Json<Session>, Problem>
+    {
+        match use_case.refresh(refresh.into_inner().refresh_token).await
+        {
+            Ok(session) => Ok(Json(domain_to_router::session(session))),
+            Err(AuthError::InvalidToken) => Err(Problem::new(Status::Unauthorized, String::from("Invalid or expired refresh token."))),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod put
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{put, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use crate::errors::UpdateError;
+    use crate::problems::Problem;
+
+    use super::super::{use_cases::put::UseCase, router_to_domain};
+    use super::{Access, AuthenticatedUser, platform_admin};
+
+    /// # Set the role of a user and the sources it is scoped to
+    ///
+    /// Only platform admins may change it.
+    /// Setters and gym admins are scoped to at least one source, climbers and platform admins to none.
+    #[openapi(tag = "Admin")]
+    #[put("/admin/users/<username>/access", data = "<access>")]
+    pub async fn update_access(username: String, access: Json<Access>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
+    {
+        platform_admin(&user)?;
+        match use_case.set_access(username, router_to_domain::access(access.into_inner())?).await
+        {
+            Ok(()) => Ok(status::NoContent),
+            Err(UpdateError::NonExistingId(username)) => Err(Problem::new(Status::NotFound, format!("No existing user `{username}`.")).id(username)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Role
+{
+    Climber,
+    Setter,
+    GymAdmin,
+    PlatformAdmin,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Access
+{
+    pub role    : Role,
+    pub sources : Vec<u16>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Credentials
+{
+    pub username : String,
+    pub password : String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Refresh
+{
+    pub refresh_token : String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Session
+{
+    pub access_token  : String,
+    pub refresh_token : String,
+    pub token_type    : String,
+    /// Lifetime of the access token in seconds.
+    pub expires_in    : u64,
+}
+
+/// Request guard of the endpoints that need a logged in user,
+/// it answers `401 Unauthorized` without a valid access token in the `Authorization: Bearer` header,
+/// the scheme being matched regardless of case.
+///
+/// The access of the user is read on each request, so that changes apply to the tokens already given.
+pub struct AuthenticatedUser
+{
+    pub username : String,
+    pub access   : domain::Access,
+}
+
+impl AuthenticatedUser
+{
+    /// Whether this is the given user (usernames being compared regardless of case) or a platform admin.
+    pub fn may_act_as(&self, username: &str) -> bool
+    {
+        self.username.to_lowercase() == username.to_lowercase() || self.access.is_platform_admin()
+    }
+
+    /// 403 unless the user may act as the given one.
+    pub fn acting_as(&self, username: &str) -> Result<(), Problem>
+    {
+        if self.may_act_as(username)
+        {
+            Ok(())
+        }
+        else
+        {
+            Err(Problem::new(Status::Forbidden, format!("Only `{username}` may do this.")))
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AuthenticatedUser
+{
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error>
+    {
+        let (Some(issuer), Some(use_case)) = (request.rocket().state::<Issuer>(), request.rocket().state::<AccessUseCase>())
+        else { return Outcome::Failure((Status::InternalServerError, ())) };
+
+        let subject = request.headers().get_one("Authorization")
+            .and_then(|header| header.trim().split_once(' '))
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("Bearer"))
+            .and_then(|(_, token)| issuer.verify(token.trim(), Kind::Access));
+        let Some(subject) = subject else { return Outcome::Failure((Status::Unauthorized, ())) };
+
+        let username = subject.username.clone();
+        match use_case.authenticate(subject).await
+        {
+            Ok(access) => Outcome::Success(AuthenticatedUser { username, access }),
+            // the account was deleted since the token was given
+            Err(GetError::NonExistingId(_)) => Outcome::Failure((Status::Unauthorized, ())),
+            Err(_) => Outcome::Failure((Status::InternalServerError, ())),
+        }
+    }
+}
+
+impl<'r> OpenApiFromRequest<'r> for AuthenticatedUser
+{
+    fn from_request_input(_gen: &mut OpenApiGenerator, _name: String, _required: bool) -> rocket_okapi::Result<RequestHeaderInput>
+    {
+        let scheme = SecurityScheme {
+            description : Some(String::from("Access token given by `/auth/login` or `/auth/refresh`.")),
+            data        : SecuritySchemeData::Http { scheme: String::from("bearer"), bearer_format: Some(String::from("JWT")) },
+            extensions  : Object::default(),
+        };
+        let mut requirement = SecurityRequirement::new();
+        requirement.insert(String::from("bearer"), Vec::new());
+        Ok(RequestHeaderInput::Security(String::from("bearer"), scheme, requirement))
+    }
+
+    fn get_responses(_gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses>
+    {
+        let mut responses = Responses::default();
+        responses.responses.insert(String::from("401"), RefOr::Object(Response {
+            description : String::from("Missing, invalid or expired access token."),
+            ..Default::default()
+        }));
+        Ok(responses)
+    }
+}
+
+/// 403 unless the authenticated user is a platform admin.
+fn platform_admin(user: &AuthenticatedUser) -> Result<(), Problem>
+{
+    if user.access.is_platform_admin()
+    {
+        Ok(())
+    }
+    else
+    {
+        Err(Problem::new(Status::Forbidden, String::from("Only platform admins may manage accesses.")))
+    }
+}
@@ -0,0 +1,47 @@
+use rocket::http::Status;
+
+use super::{domain, router};
+use crate::problems::Problem;
+
+pub fn role(r: router::Role) -> domain::Role
+{
+    match r
+    {
+        router::Role::Climber       => domain::Role::Climber,
+        router::Role::Setter        => domain::Role::Setter,
+        router::Role::GymAdmin      => domain::Role::GymAdmin,
+        router::Role::PlatformAdmin => domain::Role::PlatformAdmin,
+    }
+}
+
+/// 400 for setters and gym admins without sources, and for climbers and platform admins with sources.
+pub fn access(a: router::Access) -> Result<domain::Access, Problem>
+{
+    let role = role(a.role);
+    let scoped = matches!(role, domain::Role::Setter | domain::Role::GymAdmin);
+    if scoped == a.sources.is_empty()
+    {
+        let message = if scoped { "This role must be scoped to at least one source." } else { "This role can't be scoped to sources." };
+        return Err(Problem::new(Status::BadRequest, String::from(message)));
+    }
+    Ok(domain::Access { role, sources: a.sources.into_iter().collect() })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_access()
+    {
+        let access = |role, sources: &[u16]| router::Access { role, sources: sources.to_vec() };
+
+        let setter = super::access(access(router::Role::Setter, &[2, 1, 2])).unwrap();
+        assert_eq!(setter.role, domain::Role::Setter);
+        assert_eq!(setter.sources.into_iter().collect::<Vec<_>>(), vec![1, 2]);
+        assert!(super::access(access(router::Role::Climber, &[])).is_ok());
+        assert_eq!(super::access(access(router::Role::GymAdmin, &[])).unwrap_err().status, 400);
+        assert_eq!(super::access(access(router::Role::PlatformAdmin, &[1])).unwrap_err().status, 400);
+    }
+}
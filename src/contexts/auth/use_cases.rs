@@ -0,0 +1,126 @@
+pub mod get
+{
+    use std::sync::Arc;
+
+    use crate::errors::GetError;
+
+    use super::super::{domain::{tokens::Subject, Access, Username}, irepository::get::IRepository};
+    pub struct UseCase
+    {
+        repository: Arc<dyn IRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
+        {
+            Self { repository: repo }
+        }
+
+        pub async fn access(&self, username: Username) -> Result<Access, GetError>
+        {
+            self.repository.access(username).await
+        }
+
+        /// The access of the account a token was issued to.
+        /// `NonExistingId` once the account is deleted, even if its username was registered again since.
+        pub async fn authenticate(&self, subject: Subject) -> Result<Access, GetError>
+        {
+            match self.repository.credentials(subject.username.clone()).await
+            {
+                Ok(Some(credentials)) if credentials.account == subject.account => self.repository.access(subject.username).await,
+                Ok(_) => Err(GetError::NonExistingId(subject.username)),
+                Err(_) => Err(GetError::InternalServerError),
+            }
+        }
+    }
+}
+
+pub mod post
+{
+    use std::sync::Arc;
+
+    use crate::errors::AuthError;
+
+    use super::super::{domain::{password, tokens, Session, Username}, irepository::get::IRepository};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        issuer     : tokens::Issuer,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, issuer: tokens::Issuer) -> Self
+        {
+            Self { repository: repo, issuer }
+        }
+
+        /// Unknown users and wrong passwords are not told apart.
+        /// Tokens are given to the username as registered, whatever the case it is logged in with.
+        pub async fn login(&self, username: Username, password: String) -> Result<Session, AuthError>
+        {
+            let credentials = self.repository.credentials(username).await?;
+            let hash = credentials.as_ref().map_or(password::decoy(), |credentials| &credentials.password_hash).to_owned();
+            // Argon2 is slow on purpose, it would hold up the async workers
+            let verified = tokio::task::spawn_blocking(move || password::verify(&password, &hash)).await
+                .map_err(|_| AuthError::InternalServerError)?;
+            match credentials
+            {
+                Some(credentials) if verified => Ok(self.issuer.issue(&tokens::Subject { username: credentials.username, account: credentials.account })),
+                _ => Err(AuthError::InvalidCredentials),
+            }
+        }
+
+        /// Trades a refresh token for a new session, as long as the account it was issued to still exists.
+        pub async fn refresh(&self, refresh_token: String) -> Result<Session, AuthError>
+        {
+            let subject = self.issuer.verify(&refresh_token, tokens::Kind::Refresh).ok_or(AuthError::InvalidToken)?;
+            match self.repository.credentials(subject.username.clone()).await?
+            {
+                Some(credentials) if credentials.account == subject.account => Ok(self.issuer.issue(&subject)),
+                _ => Err(AuthError::InvalidToken),
+            }
+        }
+    }
+}
+
+pub mod put
+{
+    use std::sync::Arc;
+
+    use crate::errors::UpdateError;
+
+    use super::super::{domain::{Access, Username}, irepository::put::IRepository};
+    pub struct UseCase
+    {
+        repository: Arc<dyn IRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
+        {
+            Self { repository: repo }
+        }
+
+        pub async fn set_access(&self, username: Username, access: Access) -> Result<(), UpdateError>
+        {
+            self.repository.set_access(username, access).await
+        }
+
+        /// Stores the platform admin access of the registered users among `usernames`.
+        /// Returns the usernames that are not registered, which are left alone: registering them later does not make an admin.
+        pub async fn grant_platform_admin(&self, usernames: Vec<Username>) -> Result<Vec<Username>, UpdateError>
+        {
+            let mut unregistered = Vec::new();
+            for username in usernames
+            {
+                match self.repository.set_access(username, Access::platform_admin()).await
+                {
+                    Ok(()) => (),
+                    Err(UpdateError::NonExistingId(username)) => unregistered.push(username),
+                    Err(error) => return Err(error),
+                }
+            }
+            Ok(unregistered)
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::mem;
+
+use chrono::{DateTime, Utc};
+
+pub use super::super::users::domain::Username;
+pub use super::super::routes::domain::RouteId;
+
+pub type CommentId = u64;
+pub type Instant = DateTime<Utc>;
+
+/// A comment on a route, or a reply to such a comment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment
+{
+    pub id       : CommentId,
+    pub route_id : RouteId,
+    /// The comment this one replies to, which is never a reply itself.
+    pub reply_to : Option<CommentId>,
+    pub author   : Username,
+    pub content  : String,
+    pub date     : Instant,
+    /// Previous contents, oldest first.
+    pub history  : Vec<Revision>,
+    pub edited   : Option<Instant>,
+    /// Deleted comments are kept so that their replies stay in their thread.
+    pub deleted  : Option<Instant>,
+    /// Hidden by a moderator, hidden comments are withheld like deleted ones until they are restored.
+    pub hidden   : bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Revision
+{
+    pub content : String,
+    /// When this content was written.
+    pub date    : Instant,
+}
+
+/// A comment to create, its id is given by the repository.
+#[derive(Debug, Clone)]
+pub struct NewComment
+{
+    pub route_id : RouteId,
+    pub reply_to : Option<CommentId>,
+    pub author   : Username,
+    pub content  : String,
+    pub date     : Instant,
+}
+
+/// A comment on a route with its replies, oldest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thread
+{
+    pub comment : Comment,
+    pub replies : Vec<Comment>,
+}
+
+impl Comment
+{
+    pub fn new(id: CommentId, new: NewComment) -> Self
+    {
+        Comment {
+            id,
+            route_id : new.route_id,
+            reply_to : new.reply_to,
+            author   : new.author,
+            content  : new.content,
+            date     : new.date,
+            history  : Vec::new(),
+            edited   : None,
+            deleted  : None,
+            hidden   : false,
+        }
+    }
+
+    /// The previous content goes to the history.
+    pub fn edit(&mut self, content: String, date: Instant)
+    {
+        let written = self.edited.unwrap_or(self.date);
+        let previous = mem::replace(&mut self.content, content);
+        self.history.push(Revision { content: previous, date: written });
+        self.edited = Some(date);
+    }
+
+    pub fn delete(&mut self, date: Instant)
+    {
+        self.deleted.get_or_insert(date);
+    }
+
+    pub fn is_deleted(&self) -> bool
+    {
+        self.deleted.is_some()
+    }
+
+    /// Whether the comment is neither deleted nor hidden.
+    pub fn is_shown(&self) -> bool
+    {
+        !self.is_deleted() && !self.hidden
+    }
+}
+
+/// Groups the comments of a route by thread, oldest first.
+///
+/// Deleted or hidden replies are left out, as are deleted or hidden comments without replies left.
+pub fn threads(mut comments: Vec<Comment>) -> Vec<Thread>
+{
+    comments.sort_by_key(|comment| (comment.date, comment.id));
+    let (comments, replies): (Vec<Comment>, Vec<Comment>) = comments.into_iter()
+        .filter(|comment| comment.reply_to.is_none() || comment.is_shown())
+        .partition(|comment| comment.reply_to.is_none());
+
+    comments.into_iter()
+        .map(|comment| {
+            let replies = replies.iter().filter(|reply| reply.reply_to == Some(comment.id)).cloned().collect();
+            Thread { comment, replies }
+        })
+        .filter(|thread| thread.comment.is_shown() || !thread.replies.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(minute: u32) -> Instant
+    {
+        Utc.with_ymd_and_hms(2023, 5, 1, 12, minute, 0).unwrap()
+    }
+
+    fn comment(id: CommentId, reply_to: Option<CommentId>, minute: u32) -> Comment
+    {
+        Comment::new(id, NewComment {
+            route_id : String::from("0001-00000001"),
+            reply_to,
+            author   : String::from("jdoe"),
+            content  : format!("comment {id}"),
+            date     : at(minute),
+        })
+    }
+
+    #[test]
+    fn test_edit()
+    {
+        let mut comment = comment(1, None, 0);
+        comment.edit(String::from("second"), at(5));
+        comment.edit(String::from("third"), at(10));
+
+        assert_eq!(comment.content, "third");
+        assert_eq!(comment.edited, Some(at(10)));
+        assert_eq!(comment.history, vec![
+            Revision { content: String::from("comment 1"), date: at(0) },
+            Revision { content: String::from("second"), date: at(5) },
+        ]);
+    }
+
+    #[test]
+    fn test_threads()
+    {
+        let mut deleted_with_replies = comment(3, None, 1);
+        deleted_with_replies.delete(at(20));
+        let mut deleted_alone = comment(5, None, 3);
+        deleted_alone.delete(at(20));
+        let mut deleted_reply = comment(6, Some(1), 4);
+        deleted_reply.delete(at(20));
+        let mut hidden_reply = comment(8, Some(1), 7);
+        hidden_reply.hidden = true;
+        let mut hidden_alone = comment(9, None, 8);
+        hidden_alone.hidden = true;
+
+        let comments = vec![
+            hidden_reply,
+            hidden_alone,
+            comment(4, Some(1), 5),
+            comment(2, Some(1), 2),
+            deleted_with_replies,
+            comment(7, Some(3), 6),
+            comment(1, None, 0),
+            deleted_alone,
+            deleted_reply,
+        ];
+
+        let threads: Vec<(CommentId, Vec<CommentId>)> = threads(comments).into_iter()
+            .map(|thread| (thread.comment.id, thread.replies.iter().map(|reply| reply.id).collect()))
+            .collect();
+        assert_eq!(threads, vec![(1, vec![2, 4]), (3, vec![7])]);
+    }
+}
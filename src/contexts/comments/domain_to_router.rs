@@ -0,0 +1,35 @@
+use super::{domain, router};
+use crate::typeutil::routers::DateTime;
+
+/// The content and history of deleted or hidden comments are left out.
+pub fn comment(c: domain::Comment) -> router::Comment
+{
+    let shown = c.is_shown();
+    router::Comment {
+        id       : c.id,
+        username : c.author,
+        content  : shown.then_some(c.content),
+        date     : DateTime(c.date),
+        edited   : c.edited.map(DateTime),
+        history  : if shown { c.history.into_iter().map(revision).collect() } else { Vec::new() },
+        deleted  : c.deleted.is_some(),
+        hidden   : c.hidden,
+        reply_to : c.reply_to,
+    }
+}
+
+fn revision(r: domain::Revision) -> router::Revision
+{
+    router::Revision {
+        content : r.content,
+        date    : DateTime(r.date),
+    }
+}
+
+pub fn thread(t: domain::Thread) -> router::Thread
+{
+    router::Thread {
+        comment : comment(t.comment),
+        replies : t.replies.into_iter().map(comment).collect(),
+    }
+}
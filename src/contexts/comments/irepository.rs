@@ -0,0 +1,60 @@
+pub mod get
+{
+    use async_trait::async_trait;
+    use crate::errors::{GetAllError, GetError};
+    use super::super::domain::{Comment, CommentId, RouteId};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// Every comment of the route, deleted ones included.
+        async fn get_all(&self, route_id: RouteId) -> Result<Vec<Comment>, GetAllError>;
+        async fn get(&self, id: CommentId) -> Result<Comment, GetError>;
+        /// The comments with these ids at once, by id, the ones that don't exist being left out.
+        async fn get_many(&self, ids: Vec<CommentId>) -> Result<Vec<Comment>, GetAllError>;
+    }
+}
+
+pub mod post
+{
+    use async_trait::async_trait;
+    use crate::errors::CreateError;
+    use super::super::domain::{Comment, NewComment};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        async fn create(&self, comment: NewComment) -> Result<Comment, CreateError>;
+    }
+}
+
+pub mod put
+{
+    use async_trait::async_trait;
+    use crate::errors::UpdateError;
+    use super::super::domain::Comment;
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        async fn update(&self, comment: Comment) -> Result<(), UpdateError>;
+    }
+}
+
+pub mod delete
+{
+    use async_trait::async_trait;
+    use crate::errors::DeleteError;
+    use super::super::domain::{CommentId, Instant, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// Marks the comment as deleted, it is kept for its replies.
+        async fn delete(&self, id: CommentId, date: Instant) -> Result<(), DeleteError>;
+        /// Marks every comment of the author as deleted, there may be none.
+        async fn forget(&self, author: Username, date: Instant) -> Result<(), DeleteError>;
+        /// Removes the comment for good, along with its replies.
+        async fn purge(&self, id: CommentId) -> Result<(), DeleteError>;
+    }
+}
@@ -0,0 +1,193 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::typeutil::routers::DateTime;
+
+pub mod get
+{
+    use rocket::{get, serde::json::Json, State, http::Status};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::sources::{router::Paginated, router_to_domain::window};
+    use crate::errors::{GetAllError, GetError};
+    use crate::problems::Problem;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::get::UseCase, domain_to_router, router_to_domain};
+    use super::{Comment, Thread};
+
+    /// # Get the comments on a route
+    ///
+    /// Returns the comments with their replies, oldest first.
+    /// `limit` comments (100 by default) are returned, starting at `offset`
+    /// or where the previous page ended when given its `cursor`.
+    /// The following page is linked in the `Link` header.
+    #[openapi(tag = "Comment")]
+    #[get("/comments/<route_id>?<limit>&<offset>&<cursor>")]
+    pub async fn get_comments(route_id: Id, limit: Option<usize>, offset: Option<usize>, cursor: Option<String>, use_case: &State<UseCase>) -> Result<Paginated<Thread>, Problem>
+    {
+        let window = window(limit, offset, cursor, "date")?;
+        match use_case.threads(router_to_domain::route_id(route_id)?, window).await
+        {
+            Ok((threads, next)) => Ok(Paginated::new(threads.into_iter().map(domain_to_router::thread).collect(), next, "date")),
+            Err(GetAllError::InternalServerError) => Err(Problem::internal()),
+        }
+    }
+
+    /// # Get a comment with its edit history
+    #[openapi(tag = "Comment")]
+    #[get("/comments/<route_id>/<id>")]
+    pub async fn get_comment(route_id: Id, id: u64, use_case: &State<UseCase>) -> Result<Json<Comment>, Problem>
+    {
+        match use_case.get(router_to_domain::route_id(route_id)?, id).await
+        {
+            Ok(comment) => Ok(Json(domain_to_router::comment(comment))),
+            Err(GetError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("Comment `{id}` was not found.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod post
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{post, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::CreateError;
+    use crate::problems::Problem;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::post::UseCase, domain_to_router, router_to_domain};
+    use super::{Comment, NewComment};
+
+    /// # Comment on a route
+    ///
+    /// Returns the comment with its id. Comments reply to another one with `reply_to`,
+    /// replying to a reply answers the comment it replies to.
+    #[openapi(tag = "Comment")]
+    #[post("/comments/<route_id>", data = "<comment>")]
+    pub async fn post_comment(route_id: Id, comment: Json<NewComment>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::Created<Json<Comment>>, Problem>
+    {
+        let comment = comment.into_inner();
+        match use_case.comment(user.username, router_to_domain::route_id(route_id)?, router_to_domain::content(comment.content)?, comment.reply_to).await
+        {
+            Ok(comment) => Ok(status::Created::new(format!("/comments/{}/{}", comment.route_id, comment.id)).body(Json(domain_to_router::comment(comment)))),
+            Err(CreateError::NonExistingReference(id)) => Err(Problem::new(Status::NotFound, format!("No existing route or comment with id `{id}`.")).id(id)),
+            Err(CreateError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(CreateError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the route with id `{id}`.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod put
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{put, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::UpdateError;
+    use crate::problems::Problem;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::put::UseCase, router_to_domain};
+    use super::Content;
+
+    /// # Edit a comment
+    ///
+    /// Authors can only edit their own comments, the previous content is kept in the history.
+    #[openapi(tag = "Comment")]
+    #[put("/comments/<route_id>/<id>", data = "<content>")]
+    pub async fn edit_comment(route_id: Id, id: u64, content: Json<Content>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
+    {
+        match use_case.edit(user.username, router_to_domain::route_id(route_id)?, id, router_to_domain::content(content.into_inner().content)?).await
+        {
+            Ok(()) => Ok(status::NoContent),
+            Err(UpdateError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No existing comment `{id}`.")).id(id)),
+            Err(UpdateError::NotOwner(id)) => Err(Problem::new(Status::Forbidden, format!("Comment `{id}` was written by another user.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod delete
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{delete, State};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::DeleteError;
+    use crate::problems::Problem;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::delete::UseCase, router_to_domain};
+
+    /// # Delete a comment
+    ///
+    /// Comments are deleted by their author or by platform admins, the replies stay in the thread.
+    #[openapi(tag = "Comment")]
+    #[delete("/comments/<route_id>/<id>")]
+    pub async fn delete_comment(route_id: Id, id: u64, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
+    {
+        match use_case.delete(user.username, &user.access, router_to_domain::route_id(route_id)?, id).await
+        {
+            Ok(()) => Ok(status::NoContent),
+            Err(DeleteError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No existing comment `{id}`.")).id(id)),
+            Err(DeleteError::NotOwner(id)) => Err(Problem::new(Status::Forbidden, format!("Comment `{id}` was written by another user.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct NewComment
+{
+    pub content  : String,
+    /// Id of the comment this one replies to.
+    pub reply_to : Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Content
+{
+    pub content : String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Comment
+{
+    pub id       : u64,
+    pub username : String,
+    /// Absent once the comment is deleted or hidden.
+    pub content  : Option<String>,
+    pub date     : DateTime,
+    pub edited   : Option<DateTime>,
+    /// Previous contents, oldest first.
+    pub history  : Vec<Revision>,
+    pub deleted  : bool,
+    /// Hidden by a moderator.
+    pub hidden   : bool,
+    pub reply_to : Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Revision
+{
+    pub content : String,
+    pub date    : DateTime,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Thread
+{
+    #[serde(flatten)]
+    pub comment : Comment,
+    pub replies : Vec<Comment>,
+}
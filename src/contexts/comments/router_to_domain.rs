@@ -0,0 +1,17 @@
+use rocket::http::Status;
+
+use super::domain;
+use crate::typeutil::{routers::Id, ids::ParseIdError};
+use crate::problems::Problem;
+
+pub fn route_id(id: Id) -> Result<domain::RouteId, ParseIdError> { id.0.map(|id| id.to_string()) }
+
+/// 400 for a blank comment.
+pub fn content(content: String) -> Result<String, Problem>
+{
+    match content.trim()
+    {
+        "" => Err(Problem::new(Status::BadRequest, String::from("A comment can't be blank."))),
+        _ => Ok(content),
+    }
+}
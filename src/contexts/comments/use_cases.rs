@@ -0,0 +1,197 @@
+pub mod get
+{
+    use std::sync::Arc;
+
+    use crate::errors::{GetAllError, GetError};
+    use crate::contexts::sources::domain::Window;
+
+    use super::super::{domain::{self, Comment, CommentId, RouteId, Thread}, irepository::get::IRepository};
+    pub struct UseCase
+    {
+        repository: Arc<dyn IRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
+        {
+            Self { repository: repo }
+        }
+
+        /// The threads in the window, oldest first, along with the window of the following page.
+        pub async fn threads(&self, route_id: RouteId, window: Window) -> Result<(Vec<Thread>, Option<Window>), GetAllError>
+        {
+            let comments = self.repository.get_all(route_id).await?;
+            Ok(window.cut(domain::threads(comments)))
+        }
+
+        /// Deleted or hidden comments are not found.
+        pub async fn get(&self, route_id: RouteId, id: CommentId) -> Result<Comment, GetError>
+        {
+            match self.repository.get(id).await
+            {
+                Ok(comment) if comment.route_id == route_id && comment.is_shown() => Ok(comment),
+                Ok(_) => Err(GetError::NonExistingId(id.to_string())),
+                Err(error) => Err(error),
+            }
+        }
+    }
+}
+
+pub mod post
+{
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use crate::errors::{CreateError, GetError};
+    use crate::contexts::routes::irepository::get::IRepository as RouteRepository;
+    use crate::contexts::moderation::{domain::WordFilter, irepository::post::IRepository as ReportRepository};
+
+    use super::super::{domain::{Comment, CommentId, NewComment, RouteId, Username}, irepository::{get, post::IRepository}};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        comments   : Arc<dyn get::IRepository>,
+        routes     : Arc<dyn RouteRepository>,
+        reports    : Arc<dyn ReportRepository>,
+        filter     : WordFilter,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, comments: Arc<dyn get::IRepository>, routes: Arc<dyn RouteRepository>, reports: Arc<dyn ReportRepository>, filter: WordFilter) -> Self
+        {
+            Self { repository: repo, comments, routes, reports, filter }
+        }
+
+        /// The route must exist in its source.
+        /// There is a single level of replies: replying to a reply answers the comment it replies to.
+        /// Comments with filtered words are reported to the moderators,
+        /// a report that can't be saved is logged without failing the comment.
+        pub async fn comment(&self, author: Username, route_id: RouteId, content: String, reply_to: Option<CommentId>) -> Result<Comment, CreateError>
+        {
+            let route_id = match self.routes.get(route_id).await
+            {
+                Ok(route) => route.id,
+                Err(GetError::NonExistingId(id)) => return Err(CreateError::NonExistingReference(id)),
+                Err(GetError::MalformedId(id)) => return Err(CreateError::MalformedId(id)),
+                Err(GetError::UnknownSource(id)) => return Err(CreateError::UnknownSource(id)),
+                Err(GetError::Source(failure)) => return Err(CreateError::Source(failure)),
+                Err(GetError::BadUpstreamId(_) | GetError::InternalServerError) => return Err(CreateError::InternalServerError),
+            };
+            let reply_to = match reply_to
+            {
+                None => None,
+                Some(id) => match self.comments.get(id).await
+                {
+                    Ok(parent) if parent.route_id == route_id && parent.is_shown() => Some(parent.reply_to.unwrap_or(parent.id)),
+                    Ok(_) | Err(GetError::NonExistingId(_)) => return Err(CreateError::NonExistingReference(id.to_string())),
+                    Err(_) => return Err(CreateError::InternalServerError),
+                },
+            };
+            let comment = self.repository.create(NewComment { route_id, reply_to, author, content, date: Utc::now() }).await?;
+            if let Some(report) = self.filter.report(&comment)
+            {
+                if let Err(error) = self.reports.report(report).await
+                {
+                    eprintln!("Report of comment {} failed: {:?}", comment.id, error);
+                }
+            }
+            Ok(comment)
+        }
+    }
+}
+
+pub mod put
+{
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use crate::errors::{GetError, UpdateError};
+    use crate::contexts::moderation::{domain::WordFilter, irepository::post::IRepository as ReportRepository};
+
+    use super::super::{domain::{CommentId, RouteId, Username}, irepository::{get, put::IRepository}};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        comments   : Arc<dyn get::IRepository>,
+        reports    : Arc<dyn ReportRepository>,
+        filter     : WordFilter,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, comments: Arc<dyn get::IRepository>, reports: Arc<dyn ReportRepository>, filter: WordFilter) -> Self
+        {
+            Self { repository: repo, comments, reports, filter }
+        }
+
+        /// Only the author may edit a comment, the previous content is kept in its history.
+        /// Hidden comments can't be edited until a moderator restores them,
+        /// comments with filtered words are reported to the moderators as when they are written.
+        /// A report that can't be saved is logged without failing the edit.
+        pub async fn edit(&self, username: Username, route_id: RouteId, id: CommentId, content: String) -> Result<(), UpdateError>
+        {
+            let mut comment = match self.comments.get(id).await
+            {
+                Ok(comment) if comment.route_id == route_id && comment.is_shown() => comment,
+                Ok(_) | Err(GetError::NonExistingId(_)) => return Err(UpdateError::NonExistingId(id.to_string())),
+                Err(_) => return Err(UpdateError::InternalServerError),
+            };
+            if comment.author.to_lowercase() != username.to_lowercase()
+            {
+                return Err(UpdateError::NotOwner(id.to_string()));
+            }
+            comment.edit(content, Utc::now());
+            let report = self.filter.report(&comment);
+            self.repository.update(comment).await?;
+            if let Some(report) = report
+            {
+                if let Err(error) = self.reports.report(report).await
+                {
+                    eprintln!("Report of comment {} failed: {:?}", id, error);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+pub mod delete
+{
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use crate::errors::{DeleteError, GetError};
+    use crate::contexts::auth::domain::Access;
+
+    use super::super::{domain::{CommentId, RouteId, Username}, irepository::{get, delete::IRepository}};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        comments   : Arc<dyn get::IRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, comments: Arc<dyn get::IRepository>) -> Self
+        {
+            Self { repository: repo, comments }
+        }
+
+        /// Comments are deleted by their author or by platform admins.
+        pub async fn delete(&self, username: Username, access: &Access, route_id: RouteId, id: CommentId) -> Result<(), DeleteError>
+        {
+            let comment = match self.comments.get(id).await
+            {
+                Ok(comment) if comment.route_id == route_id && !comment.is_deleted() => comment,
+                Ok(_) | Err(GetError::NonExistingId(_)) => return Err(DeleteError::NonExistingId(id.to_string())),
+                Err(_) => return Err(DeleteError::InternalServerError),
+            };
+            if comment.author.to_lowercase() != username.to_lowercase() && !access.is_platform_admin()
+            {
+                return Err(DeleteError::NotOwner(id.to_string()));
+            }
+            self.repository.delete(id, Utc::now()).await
+        }
+    }
+}
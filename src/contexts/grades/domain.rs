@@ -0,0 +1,89 @@
+pub use super::super::users::domain::Username;
+pub use super::super::routes::domain::{Grade, GradeSystem, RouteId};
+
+/// The grade a user suggests for a route, users suggest a single grade per route.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vote
+{
+    pub username : Username,
+    pub grade    : Grade,
+}
+
+/// How many users suggested a grade.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Count
+{
+    pub grade : Grade,
+    pub votes : usize,
+}
+
+/// The grades suggested for a route, from the easiest to the hardest, and the grade they agree on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Consensus
+{
+    pub grade : Option<Grade>,
+    pub votes : Vec<Count>,
+}
+
+/// Votes are compared by difficulty whatever their grading system, and counted as the closest grade in `system`.
+/// The consensus is the median vote (the easier one when there are two), there is none without readable votes.
+pub fn consensus(votes: &[Vote], system: GradeSystem) -> Consensus
+{
+    let mut difficulties: Vec<_> = votes.iter().filter_map(|vote| vote.grade.difficulty()).collect();
+    difficulties.sort();
+
+    let grade = difficulties.get(difficulties.len().saturating_sub(1) / 2).map(|&difficulty| Grade::rated(system, difficulty));
+    let mut counts: Vec<Count> = Vec::new();
+    for difficulty in difficulties
+    {
+        let grade = Grade::rated(system, difficulty);
+        match counts.last_mut()
+        {
+            Some(count) if count.grade.text == grade.text => count.votes += 1,
+            _ => counts.push(Count { grade, votes: 1 }),
+        }
+    }
+    Consensus { grade, votes: counts }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn votes(grades: &[&str]) -> Vec<Vote>
+    {
+        grades.iter().enumerate()
+            .map(|(i, grade)| Vote { username: format!("user{i}"), grade: Grade::parse(grade) })
+            .collect()
+    }
+
+    fn counts(consensus: &Consensus) -> Vec<(&str, usize)>
+    {
+        consensus.votes.iter().map(|count| (count.grade.text.as_str(), count.votes)).collect()
+    }
+
+    #[test]
+    fn test_consensus()
+    {
+        let consensus = consensus(&votes(&["6b", "5.11a", "6a+", "VII", "7a"]), GradeSystem::French);
+        assert_eq!(consensus.grade.as_ref().map(|grade| grade.text.as_str()), Some("6b+"));
+        assert_eq!(counts(&consensus), vec![("6a+", 1), ("6b", 1), ("6b+", 2), ("7a", 1)]);
+    }
+
+    #[test]
+    fn test_consensus_in_other_system()
+    {
+        // 7a+ and 7b are both closest to V5
+        let consensus = consensus(&votes(&["7b", "7a+", "6a"]), GradeSystem::VScale);
+        assert_eq!(consensus.grade.as_ref().map(|grade| grade.text.as_str()), Some("V5"));
+        assert_eq!(counts(&consensus), vec![("V2", 1), ("V5", 2)]);
+    }
+
+    #[test]
+    fn test_consensus_without_votes()
+    {
+        let consensus = consensus(&votes(&["yellow"]), GradeSystem::French);
+        assert_eq!(consensus, Consensus { grade: None, votes: Vec::new() });
+    }
+}
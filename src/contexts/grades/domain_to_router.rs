@@ -0,0 +1,17 @@
+use super::{domain, router};
+
+pub fn consensus(c: domain::Consensus) -> router::Consensus
+{
+    router::Consensus {
+        grade : c.grade.map(|grade| grade.text),
+        votes : c.votes.into_iter().map(count).collect(),
+    }
+}
+
+fn count(c: domain::Count) -> router::Count
+{
+    router::Count {
+        grade : c.grade.text,
+        votes : c.votes,
+    }
+}
@@ -0,0 +1,46 @@
+pub mod get
+{
+    use std::collections::HashMap;
+
+    use async_trait::async_trait;
+    use crate::errors::GetAllError;
+    use super::super::domain::{RouteId, Vote};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        async fn get_all(&self, route_id: RouteId) -> Result<Vec<Vote>, GetAllError>;
+        /// The votes on each of the routes at once, routes without votes are left out.
+        async fn votes_by_route(&self, route_ids: Vec<RouteId>) -> Result<HashMap<RouteId, Vec<Vote>>, GetAllError>;
+    }
+}
+
+pub mod put
+{
+    use async_trait::async_trait;
+    use crate::errors::UpdateError;
+    use super::super::domain::{RouteId, Vote};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// Replaces the vote the user may already have on the route, usernames are compared regardless of case.
+        async fn save(&self, route_id: RouteId, vote: Vote) -> Result<(), UpdateError>;
+    }
+}
+
+pub mod delete
+{
+    use async_trait::async_trait;
+    use crate::errors::DeleteError;
+    use super::super::domain::{RouteId, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// `NonExistingId` with the route id when the user has no vote on the route.
+        async fn delete(&self, route_id: RouteId, username: Username) -> Result<(), DeleteError>;
+        /// Removes every vote of the user, there may be none.
+        async fn forget(&self, username: Username) -> Result<(), DeleteError>;
+    }
+}
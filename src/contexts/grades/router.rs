@@ -0,0 +1,124 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+pub mod get
+{
+    use rocket::{get, serde::json::Json, State, http::Status};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::routes::router::GradeSystem;
+    use crate::errors::GetError;
+    use crate::problems::Problem;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::get::UseCase, domain_to_router, router_to_domain};
+    use super::Consensus;
+
+    /// # Get the grades suggested for a route
+    ///
+    /// Returns how many users suggested each grade and the consensus grade, the median of the suggestions.
+    /// Suggestions are compared whatever their grading system and given in `grade_system`,
+    /// in the grading system of the route by default.
+    #[openapi(tag = "Grade")]
+    #[get("/grades/<route_id>?<grade_system>")]
+    pub async fn get_grades(route_id: Id, grade_system: Option<GradeSystem>, use_case: &State<UseCase>) -> Result<Json<Consensus>, Problem>
+    {
+        match use_case.consensus(router_to_domain::route_id(route_id)?, grade_system.map(router_to_domain::grade_system)).await
+        {
+            Ok(consensus) => Ok(Json(domain_to_router::consensus(consensus))),
+            Err(GetError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("Route with id `{id}` was not found.")).id(id)),
+            Err(GetError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(GetError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the route with id `{id}`.")).id(id)),
+            Err(GetError::BadUpstreamId(id)) => Err(Problem::new(Status::BadGateway, format!("The source answered with the invalid id `{id}`.")).id(id)),
+            Err(GetError::Source(failure)) => Err(Problem::from(failure)),
+            Err(GetError::InternalServerError) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod put
+{
+    use rocket::http::Status;
+    use rocket::{put, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::contexts::routes::router::GradeSystem;
+    use crate::errors::UpdateError;
+    use crate::problems::Problem;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::put::UseCase, domain_to_router, router_to_domain};
+    use super::{Consensus, Suggestion};
+
+    /// # Suggest a grade for a route
+    ///
+    /// Replaces the grade the user suggested before, if any, and returns the grades suggested for the route as `GET` does.
+    /// The grade can be given in any grading system the core reads.
+    #[openapi(tag = "Grade")]
+    #[put("/grades/<route_id>?<grade_system>", data = "<suggestion>")]
+    pub async fn suggest_grade(route_id: Id, grade_system: Option<GradeSystem>, suggestion: Json<Suggestion>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<Json<Consensus>, Problem>
+    {
+        let grade = router_to_domain::grade(suggestion.into_inner().grade)?;
+        match use_case.suggest(user.username, router_to_domain::route_id(route_id)?, grade, grade_system.map(router_to_domain::grade_system)).await
+        {
+            Ok(consensus) => Ok(Json(domain_to_router::consensus(consensus))),
+            Err(UpdateError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No existing route with id `{id}`.")).id(id)),
+            Err(UpdateError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(UpdateError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the route with id `{id}`.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod delete
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{delete, State};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::DeleteError;
+    use crate::problems::Problem;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::delete::UseCase, router_to_domain};
+
+    /// # Withdraw the grade the user suggested for a route
+    #[openapi(tag = "Grade")]
+    #[delete("/grades/<route_id>")]
+    pub async fn withdraw_grade(route_id: Id, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
+    {
+        match use_case.withdraw(user.username, router_to_domain::route_id(route_id)?).await
+        {
+            Ok(()) => Ok(status::NoContent),
+            Err(DeleteError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("You suggested no grade for the route `{id}`.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Suggestion
+{
+    /// In any grading system, ex: `6a+`, `5.10d`, `V3`.
+    pub grade : String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Consensus
+{
+    /// Absent until a user suggests a grade.
+    pub grade : Option<String>,
+    /// From the easiest grade to the hardest.
+    pub votes : Vec<Count>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[schemars(rename = "GradeCount")]
+pub struct Count
+{
+    pub grade : String,
+    pub votes : usize,
+}
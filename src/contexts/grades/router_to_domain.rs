@@ -0,0 +1,20 @@
+use rocket::http::Status;
+
+use super::domain;
+use crate::typeutil::{routers::Id, ids::ParseIdError};
+use crate::problems::Problem;
+
+pub use crate::contexts::routes::router_to_domain::grade_system;
+
+pub fn route_id(id: Id) -> Result<domain::RouteId, ParseIdError> { id.0.map(|id| id.to_string()) }
+
+/// 400 for a grade that can't be read, since it could not be compared with the others.
+pub fn grade(text: String) -> Result<domain::Grade, Problem>
+{
+    let grade = domain::Grade::parse(&text);
+    match grade.difficulty()
+    {
+        Some(_) => Ok(grade),
+        None => Err(Problem::new(Status::BadRequest, format!("`{text}` is not a grade the core can read."))),
+    }
+}
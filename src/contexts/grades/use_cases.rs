@@ -0,0 +1,105 @@
+pub mod get
+{
+    use std::sync::Arc;
+
+    use crate::errors::{GetAllError, GetError};
+    use crate::contexts::routes::irepository::get::IRepository as RouteRepository;
+
+    use super::super::{domain::{self, Consensus, GradeSystem, RouteId}, irepository::get::IRepository};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        routes     : Arc<dyn RouteRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, routes: Arc<dyn RouteRepository>) -> Self
+        {
+            Self { repository: repo, routes }
+        }
+
+        /// Votes are counted in `system`, in the grading system of the route by default (French when its grade can't be read).
+        pub async fn consensus(&self, route_id: RouteId, system: Option<GradeSystem>) -> Result<Consensus, GetError>
+        {
+            let route = self.routes.get(route_id).await?;
+            let system = system.or_else(|| route.data.grade.system()).unwrap_or(GradeSystem::French);
+            match self.repository.get_all(route.id).await
+            {
+                Ok(votes) => Ok(domain::consensus(&votes, system)),
+                Err(GetAllError::InternalServerError) => Err(GetError::InternalServerError),
+            }
+        }
+    }
+}
+
+pub mod put
+{
+    use std::sync::Arc;
+
+    use crate::errors::{GetAllError, GetError, UpdateError};
+    use crate::contexts::routes::irepository::get::IRepository as RouteRepository;
+
+    use super::super::{domain::{self, Consensus, Grade, GradeSystem, RouteId, Username, Vote}, irepository::{get, put::IRepository}};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        votes      : Arc<dyn get::IRepository>,
+        routes     : Arc<dyn RouteRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, votes: Arc<dyn get::IRepository>, routes: Arc<dyn RouteRepository>) -> Self
+        {
+            Self { repository: repo, votes, routes }
+        }
+
+        /// The route must exist in its source, its id is stored as the source gives it.
+        /// Returns the consensus with the new vote, counted as in `get`.
+        pub async fn suggest(&self, username: Username, route_id: RouteId, grade: Grade, system: Option<GradeSystem>) -> Result<Consensus, UpdateError>
+        {
+            let route = match self.routes.get(route_id).await
+            {
+                Ok(route) => route,
+                Err(GetError::NonExistingId(id)) => return Err(UpdateError::NonExistingId(id)),
+                Err(GetError::MalformedId(id)) => return Err(UpdateError::MalformedId(id)),
+                Err(GetError::UnknownSource(id)) => return Err(UpdateError::UnknownSource(id)),
+                Err(GetError::Source(failure)) => return Err(UpdateError::Source(failure)),
+                Err(GetError::BadUpstreamId(_) | GetError::InternalServerError) => return Err(UpdateError::InternalServerError),
+            };
+            self.repository.save(route.id.clone(), Vote { username, grade }).await?;
+
+            let system = system.or_else(|| route.data.grade.system()).unwrap_or(GradeSystem::French);
+            match self.votes.get_all(route.id).await
+            {
+                Ok(votes) => Ok(domain::consensus(&votes, system)),
+                Err(GetAllError::InternalServerError) => Err(UpdateError::InternalServerError),
+            }
+        }
+    }
+}
+
+pub mod delete
+{
+    use std::sync::Arc;
+
+    use crate::errors::DeleteError;
+
+    use super::super::{domain::{RouteId, Username}, irepository::delete::IRepository};
+    pub struct UseCase
+    {
+        repository: Arc<dyn IRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
+        {
+            Self { repository: repo }
+        }
+
+        /// Routes are not checked, so that votes on a route that no longer exists can be withdrawn.
+        pub async fn withdraw(&self, username: Username, route_id: RouteId) -> Result<(), DeleteError>
+        {
+            self.repository.delete(route_id, username).await
+        }
+    }
+}
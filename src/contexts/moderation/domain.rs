@@ -0,0 +1,120 @@
+use std::env;
+
+pub use super::super::users::domain::Username;
+pub use super::super::sources::domain::SourceId;
+pub use super::super::comments::domain::{Comment, CommentId, Instant, RouteId};
+use crate::typeutil::ids::RelativeId;
+
+/// Why a comment should be looked at by a moderator, a user reports a comment once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report
+{
+    pub comment_id : CommentId,
+    /// Absent for the reports of the word filter.
+    pub reporter   : Option<Username>,
+    pub reason     : String,
+    pub date       : Instant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action
+{
+    Hide,
+    Restore,
+    /// Removes the comment for good, along with its replies.
+    Delete,
+}
+
+/// An action of a moderator, as kept in the audit trail.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry
+{
+    pub comment_id : CommentId,
+    pub route_id   : RouteId,
+    /// The author of the comment.
+    pub author     : Username,
+    pub moderator  : Username,
+    pub action     : Action,
+    pub reason     : Option<String>,
+    pub date       : Instant,
+}
+
+/// A reported comment waiting for a moderator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case
+{
+    pub comment : Comment,
+    /// Oldest first.
+    pub reports : Vec<Report>,
+}
+
+/// The source of the route a comment is on, none for ids that can't be read.
+pub fn source(route_id: &str) -> Option<SourceId>
+{
+    route_id.parse().ok().map(|RelativeId { source_id, .. }| source_id)
+}
+
+/// Words that get a comment reported as soon as it is written, whatever their case.
+#[derive(Debug, Clone, Default)]
+pub struct WordFilter
+{
+    words: Vec<String>,
+}
+
+impl WordFilter
+{
+    pub fn new(words: impl IntoIterator<Item = String>) -> Self
+    {
+        WordFilter { words: words.into_iter().map(|word| word.trim().to_lowercase()).filter(|word| !word.is_empty()).collect() }
+    }
+
+    /// Words given in `CLIMBHUB_FLAGGED_WORDS` (comma separated), none when it is not set.
+    pub fn from_env() -> Self
+    {
+        WordFilter::new(env::var("CLIMBHUB_FLAGGED_WORDS").unwrap_or_default().split(',').map(str::to_owned))
+    }
+
+    /// The filtered words the content contains, as whole words.
+    pub fn matches(&self, content: &str) -> Vec<&str>
+    {
+        let content: Vec<String> = content.split(|c: char| !c.is_alphanumeric()).map(str::to_lowercase).collect();
+        self.words.iter()
+            .filter(|word| content.contains(word))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// The report of the filter on a comment, if it contains filtered words.
+    pub fn report(&self, comment: &Comment) -> Option<Report>
+    {
+        let words = self.matches(&comment.content);
+        (!words.is_empty()).then(|| Report {
+            comment_id : comment.id,
+            reporter   : None,
+            reason     : format!("Contains {}.", words.iter().map(|word| format!("`{word}`")).collect::<Vec<_>>().join(", ")),
+            date       : comment.edited.unwrap_or(comment.date),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_matches()
+    {
+        let filter = WordFilter::new([String::from("Spam"), String::from(" scam "), String::new()]);
+        assert_eq!(filter.matches("Buy now, SPAM! It's no scam."), vec!["spam", "scam"]);
+        assert!(filter.matches("Spammers would say so").is_empty());
+        assert!(WordFilter::default().matches("spam").is_empty());
+    }
+
+    #[test]
+    fn test_source()
+    {
+        assert_eq!(source("0002-0000000F"), Some(2));
+        assert_eq!(source("garbage"), None);
+    }
+}
@@ -0,0 +1,57 @@
+use super::{domain, router};
+use crate::typeutil::routers::DateTime;
+
+pub fn case(c: domain::Case) -> router::Case
+{
+    router::Case {
+        comment : comment(c.comment),
+        reports : c.reports.into_iter().map(report).collect(),
+    }
+}
+
+fn comment(c: domain::Comment) -> router::Comment
+{
+    router::Comment {
+        id       : c.id,
+        route_id : c.route_id,
+        username : c.author,
+        content  : c.content,
+        date     : DateTime(c.date),
+        edited   : c.edited.map(DateTime),
+        deleted  : c.deleted.is_some(),
+        hidden   : c.hidden,
+        reply_to : c.reply_to,
+    }
+}
+
+fn report(r: domain::Report) -> router::Report
+{
+    router::Report {
+        username : r.reporter,
+        reason   : r.reason,
+        date     : DateTime(r.date),
+    }
+}
+
+pub fn entry(e: domain::Entry) -> router::Entry
+{
+    router::Entry {
+        comment_id : e.comment_id,
+        route_id   : e.route_id,
+        author     : e.author,
+        moderator  : e.moderator,
+        action     : action(e.action),
+        reason     : e.reason,
+        date       : DateTime(e.date),
+    }
+}
+
+fn action(a: domain::Action) -> router::Action
+{
+    match a
+    {
+        domain::Action::Hide    => router::Action::Hide,
+        domain::Action::Restore => router::Action::Restore,
+        domain::Action::Delete  => router::Action::Delete,
+    }
+}
@@ -0,0 +1,46 @@
+pub mod get
+{
+    use async_trait::async_trait;
+    use crate::errors::GetAllError;
+    use super::super::domain::{Entry, Report};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// Reports that were not dealt with yet, oldest first.
+        async fn reports(&self) -> Result<Vec<Report>, GetAllError>;
+        /// Every action of the moderators, oldest first.
+        async fn journal(&self) -> Result<Vec<Entry>, GetAllError>;
+    }
+}
+
+pub mod post
+{
+    use async_trait::async_trait;
+    use crate::errors::CreateError;
+    use super::super::domain::{Entry, Report};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// Replaces the report the reporter may already have made on the comment, usernames are compared regardless of case.
+        async fn report(&self, report: Report) -> Result<(), CreateError>;
+        async fn record(&self, entry: Entry) -> Result<(), CreateError>;
+    }
+}
+
+pub mod delete
+{
+    use async_trait::async_trait;
+    use crate::errors::DeleteError;
+    use super::super::domain::{CommentId, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// Removes the reports on the comment, there may be none.
+        async fn dismiss(&self, comment_id: CommentId) -> Result<(), DeleteError>;
+        /// Removes the reports filed by the user, there may be none. The audit trail is kept.
+        async fn forget(&self, reporter: Username) -> Result<(), DeleteError>;
+    }
+}
@@ -0,0 +1,183 @@
+use rocket::http::Status;
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use crate::problems::Problem;
+use serde::{Deserialize, Serialize};
+
+use crate::contexts::auth::router::AuthenticatedUser;
+use crate::typeutil::routers::DateTime;
+
+pub mod get
+{
+    use rocket::{get, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::GetAllError;
+    use crate::problems::Problem;
+
+    use super::super::{use_cases::get::UseCase, domain_to_router};
+    use super::{moderator, Case, Entry};
+
+    /// # Get the moderation queue of a source
+    ///
+    /// Returns the reported comments on the routes of the source with their reports, the one reported first comes first.
+    /// Only the setters and gym admins of the source (and platform admins) see it.
+    #[openapi(tag = "Moderation")]
+    #[get("/moderation/<source>")]
+    pub async fn get_queue(source: u16, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<Json<Vec<Case>>, Problem>
+    {
+        moderator(&user, source)?;
+        match use_case.queue(source).await
+        {
+            Ok(cases) => Ok(Json(cases.into_iter().map(domain_to_router::case).collect())),
+            Err(GetAllError::InternalServerError) => Err(Problem::internal()),
+        }
+    }
+
+    /// # Get the audit trail of a source
+    ///
+    /// Returns what the moderators did to the comments on the routes of the source, latest first.
+    #[openapi(tag = "Moderation")]
+    #[get("/moderation/<source>/audit")]
+    pub async fn get_audit(source: u16, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<Json<Vec<Entry>>, Problem>
+    {
+        moderator(&user, source)?;
+        match use_case.journal(source).await
+        {
+            Ok(journal) => Ok(Json(journal.into_iter().map(domain_to_router::entry).collect())),
+            Err(GetAllError::InternalServerError) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod post
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{post, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::{CreateError, UpdateError};
+    use crate::problems::Problem;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::{post::UseCase, put::UseCase as ModerationUseCase}, domain_to_router, router_to_domain};
+    use super::{Decision, Entry, NewReport};
+
+    /// # Report a comment to the moderators
+    ///
+    /// Users report a comment once, reporting it again replaces the previous report.
+    #[openapi(tag = "Moderation")]
+    #[post("/comments/<route_id>/<id>/reports", data = "<report>")]
+    pub async fn report_comment(route_id: Id, id: u64, report: Json<NewReport>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
+    {
+        match use_case.report(user.username, router_to_domain::route_id(route_id)?, id, router_to_domain::reason(report.into_inner().reason)?).await
+        {
+            Ok(()) => Ok(status::NoContent),
+            Err(CreateError::NonExistingReference(id)) => Err(Problem::new(Status::NotFound, format!("No existing comment `{id}`.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+
+    /// # Hide, restore or delete a comment
+    ///
+    /// Hidden comments are withheld like deleted ones until they are restored, deleting a comment removes it for good along with its replies.
+    /// Any action closes the reports on the comment and is recorded in the audit trail of the source, the entry is returned.
+    /// Only the setters and gym admins of the source of the route (and platform admins) moderate its comments.
+    #[openapi(tag = "Moderation")]
+    #[post("/moderation/comments/<id>", data = "<decision>")]
+    pub async fn moderate_comment(id: u64, decision: Json<Decision>, user: AuthenticatedUser, use_case: &State<ModerationUseCase>) -> Result<Json<Entry>, Problem>
+    {
+        let decision = decision.into_inner();
+        match use_case.moderate(user.username, &user.access, id, router_to_domain::action(decision.action), decision.reason).await
+        {
+            Ok(entry) => Ok(Json(domain_to_router::entry(entry))),
+            Err(UpdateError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No existing comment `{id}`.")).id(id)),
+            Err(UpdateError::Forbidden(source)) => Err(Problem::new(Status::Forbidden, format!("You may not moderate the source `{source}`."))),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+/// 403 unless the authenticated user moderates the source.
+fn moderator(user: &AuthenticatedUser, source: u16) -> Result<(), Problem>
+{
+    if user.access.can_moderate(source)
+    {
+        Ok(())
+    }
+    else
+    {
+        Err(Problem::new(Status::Forbidden, format!("You may not moderate the source `{source}`.")))
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct NewReport
+{
+    pub reason : String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Decision
+{
+    pub action : Action,
+    pub reason : Option<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+#[schemars(rename = "ModerationAction")]
+pub enum Action
+{
+    Hide,
+    Restore,
+    Delete,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Case
+{
+    pub comment : Comment,
+    /// Oldest first.
+    pub reports : Vec<Report>,
+}
+
+/// A comment as moderators see it, with its content whether it is deleted or hidden.
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[schemars(rename = "ModeratedComment")]
+pub struct Comment
+{
+    pub id       : u64,
+    pub route_id : String,
+    pub username : String,
+    pub content  : String,
+    pub date     : DateTime,
+    pub edited   : Option<DateTime>,
+    pub deleted  : bool,
+    pub hidden   : bool,
+    pub reply_to : Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Report
+{
+    /// Absent for the reports of the word filter.
+    pub username : Option<String>,
+    pub reason   : String,
+    pub date     : DateTime,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Entry
+{
+    pub comment_id : u64,
+    pub route_id   : String,
+    /// The author of the comment.
+    pub author     : String,
+    pub moderator  : String,
+    pub action     : Action,
+    pub reason     : Option<String>,
+    pub date       : DateTime,
+}
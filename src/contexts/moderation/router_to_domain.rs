@@ -0,0 +1,27 @@
+use rocket::http::Status;
+
+use super::{domain, router};
+use crate::typeutil::{routers::Id, ids::ParseIdError};
+use crate::problems::Problem;
+
+pub fn route_id(id: Id) -> Result<domain::RouteId, ParseIdError> { id.0.map(|id| id.to_string()) }
+
+/// 400 for a blank reason.
+pub fn reason(reason: String) -> Result<String, Problem>
+{
+    match reason.trim()
+    {
+        "" => Err(Problem::new(Status::BadRequest, String::from("A report needs a reason."))),
+        _ => Ok(reason),
+    }
+}
+
+pub fn action(a: router::Action) -> domain::Action
+{
+    match a
+    {
+        router::Action::Hide    => domain::Action::Hide,
+        router::Action::Restore => domain::Action::Restore,
+        router::Action::Delete  => domain::Action::Delete,
+    }
+}
@@ -0,0 +1,180 @@
+pub mod get
+{
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use crate::errors::GetAllError;
+    use crate::contexts::comments::{domain::Comment, irepository::get::IRepository as CommentRepository};
+
+    use super::super::{domain::{self, Case, CommentId, Entry, SourceId}, irepository::get::IRepository};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        comments   : Arc<dyn CommentRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, comments: Arc<dyn CommentRepository>) -> Self
+        {
+            Self { repository: repo, comments }
+        }
+
+        /// The reported comments on the routes of the source, the one reported first comes first.
+        /// Comments that are no longer shown are left out: their author deleted them or they are already hidden.
+        pub async fn queue(&self, source: SourceId) -> Result<Vec<Case>, GetAllError>
+        {
+            let reports = self.repository.reports().await?;
+            let mut ids: Vec<CommentId> = reports.iter().map(|report| report.comment_id).collect();
+            ids.sort_unstable();
+            ids.dedup();
+            // comments removed along with the comment they replied to are not found
+            let comments: HashMap<CommentId, Comment> = self.comments.get_many(ids).await?.into_iter()
+                .filter(|comment| comment.is_shown() && domain::source(&comment.route_id) == Some(source))
+                .map(|comment| (comment.id, comment))
+                .collect();
+
+            let mut cases: Vec<Case> = Vec::new();
+            for report in reports
+            {
+                if let Some(case) = cases.iter_mut().find(|case| case.comment.id == report.comment_id)
+                {
+                    case.reports.push(report);
+                }
+                else if let Some(comment) = comments.get(&report.comment_id)
+                {
+                    cases.push(Case { comment: comment.clone(), reports: vec![report] });
+                }
+            }
+            Ok(cases)
+        }
+
+        /// The actions of the moderators on the comments of the source, latest first.
+        pub async fn journal(&self, source: SourceId) -> Result<Vec<Entry>, GetAllError>
+        {
+            let mut journal = self.repository.journal().await?;
+            journal.retain(|entry| domain::source(&entry.route_id) == Some(source));
+            journal.reverse();
+            Ok(journal)
+        }
+    }
+}
+
+pub mod post
+{
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use crate::errors::{CreateError, GetError};
+    use crate::contexts::comments::irepository::get::IRepository as CommentRepository;
+
+    use super::super::{domain::{CommentId, Report, RouteId, Username}, irepository::post::IRepository};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        comments   : Arc<dyn CommentRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, comments: Arc<dyn CommentRepository>) -> Self
+        {
+            Self { repository: repo, comments }
+        }
+
+        /// Only comments that are shown can be reported, reporting a comment again replaces the previous report.
+        pub async fn report(&self, username: Username, route_id: RouteId, id: CommentId, reason: String) -> Result<(), CreateError>
+        {
+            match self.comments.get(id).await
+            {
+                Ok(comment) if comment.route_id == route_id && comment.is_shown() => (),
+                Ok(_) | Err(GetError::NonExistingId(_)) => return Err(CreateError::NonExistingReference(id.to_string())),
+                Err(_) => return Err(CreateError::InternalServerError),
+            }
+            self.repository.report(Report { comment_id: id, reporter: Some(username), reason, date: Utc::now() }).await
+        }
+    }
+}
+
+pub mod put
+{
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use crate::errors::{DeleteError, GetError, UpdateError};
+    use crate::contexts::auth::domain::Access;
+    use crate::contexts::comments::irepository::{
+        get::IRepository    as CommentRepository,
+        put::IRepository    as CommentUpdateRepository,
+        delete::IRepository as CommentDeleteRepository,
+    };
+
+    use super::super::{domain::{self, Action, CommentId, Entry, Username}, irepository::{post::IRepository, delete}};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        reports    : Arc<dyn delete::IRepository>,
+        comments   : Arc<dyn CommentRepository>,
+        updates    : Arc<dyn CommentUpdateRepository>,
+        deletions  : Arc<dyn CommentDeleteRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(
+            repo      : Arc<dyn IRepository>,
+            reports   : Arc<dyn delete::IRepository>,
+            comments  : Arc<dyn CommentRepository>,
+            updates   : Arc<dyn CommentUpdateRepository>,
+            deletions : Arc<dyn CommentDeleteRepository>,
+        ) -> Self
+        {
+            Self { repository: repo, reports, comments, updates, deletions }
+        }
+
+        /// Staff of the source of the route moderate the comment. Any action closes the reports on the comment,
+        /// and is recorded in the audit trail.
+        pub async fn moderate(&self, moderator: Username, access: &Access, id: CommentId, action: Action, reason: Option<String>) -> Result<Entry, UpdateError>
+        {
+            let mut comment = match self.comments.get(id).await
+            {
+                Ok(comment) => comment,
+                Err(GetError::NonExistingId(_)) => return Err(UpdateError::NonExistingId(id.to_string())),
+                Err(_) => return Err(UpdateError::InternalServerError),
+            };
+            let Some(source) = domain::source(&comment.route_id) else { return Err(UpdateError::MalformedId(comment.route_id)) };
+            if !access.can_moderate(source)
+            {
+                return Err(UpdateError::Forbidden(source));
+            }
+
+            let entry = Entry {
+                comment_id : id,
+                route_id   : comment.route_id.clone(),
+                author     : comment.author.clone(),
+                moderator,
+                action,
+                reason,
+                date       : Utc::now(),
+            };
+            match action
+            {
+                Action::Hide | Action::Restore =>
+                {
+                    comment.hidden = action == Action::Hide;
+                    self.updates.update(comment).await?;
+                },
+                Action::Delete => match self.deletions.purge(id).await
+                {
+                    Ok(()) => (),
+                    Err(DeleteError::NonExistingId(id)) => return Err(UpdateError::NonExistingId(id)),
+                    Err(_) => return Err(UpdateError::InternalServerError),
+                },
+            }
+            if self.reports.dismiss(id).await.is_err() || self.repository.record(entry.clone()).await.is_err()
+            {
+                return Err(UpdateError::InternalServerError);
+            }
+            Ok(entry)
+        }
+    }
+}
@@ -1,11 +1,14 @@
-#[derive(Debug)]
+use crate::errors::InvalidField;
+
+#[derive(Debug, Clone)]
 pub struct Place
 {
     pub id   : PlaceId,
     pub data : PlaceData,
 }
 pub type PlaceId = String;
-#[derive(Debug)]
+pub use super::super::sources::domain::SourceId;
+#[derive(Debug, Clone)]
 pub struct PlaceData
 {
     pub name        : String,
@@ -16,12 +19,229 @@ pub struct PlaceData
     pub country     : String,
 }
 
+impl PlaceData
+{
+    /// Lists every rule the data breaks.
+    /// The postcode is only checked in the countries whose format is known, ex: `99999` in France.
+    pub fn validate(&self) -> Result<(), Vec<InvalidField>>
+    {
+        let mut invalid = Vec::new();
+        if self.name.trim().is_empty()
+        {
+            invalid.push(InvalidField::new("name", "must not be blank"));
+        }
+        if self.city.trim().is_empty()
+        {
+            invalid.push(InvalidField::new("city", "must not be blank"));
+        }
+        let formats = postcode_formats(&self.country);
+        if !formats.is_empty() && !formats.iter().any(|format| is_postcode(self.postcode.trim(), format))
+        {
+            let formats = formats.iter().map(|format| format!("`{format}`")).collect::<Vec<_>>().join(" or ");
+            invalid.push(InvalidField::new("postcode", format!("must be of the form {formats} in {}", self.country.trim())));
+        }
+        if invalid.is_empty() { Ok(()) } else { Err(invalid) }
+    }
+}
+
+/// Formats of the postcodes of a country, named in English or in its own language.
+/// `9` stands for a digit and `A` for a letter, other characters stand for themselves.
+fn postcode_formats(country: &str) -> &'static [&'static str]
+{
+    match get::normalize(country).as_str()
+    {
+        "france" | "germany" | "deutschland" | "spain" | "espana" | "italy" | "italia" => &["99999"],
+        "united states" | "united states of america" | "usa" => &["99999", "99999-9999"],
+        "belgium" | "belgique" | "belgie" | "switzerland" | "suisse" | "schweiz" | "svizzera" => &["9999"],
+        "netherlands" | "nederland" => &["9999 AA"],
+        "canada" => &["A9A 9A9"],
+        _ => &[],
+    }
+}
+
+fn is_postcode(postcode: &str, format: &str) -> bool
+{
+    postcode.chars().count() == format.len() && postcode.chars().zip(format.chars()).all(|(c, f)| match f
+    {
+        '9' => c.is_ascii_digit(),
+        'A' => c.is_ascii_alphabetic(),
+        _ => c == f,
+    })
+}
+
 pub mod get
 {
-    #[derive(Debug)]
+    use std::cmp::Ordering;
+
+    use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+    use super::{Place, PlaceData};
+
+    #[derive(Debug, Clone)]
     pub struct Filters
     {
         pub country : Option<String>,
         pub city    : Option<String>,
     }
-}
\ No newline at end of file
+
+    impl Filters
+    {
+        /// Whether the place matches every given filter.
+        /// Text is compared regardless of case, accents and word separators.
+        pub fn matches(&self, place: &PlaceData) -> bool
+        {
+            let matches = |filter: &Option<String>, value: &str| match filter
+            {
+                Some(filter) => normalize(filter) == normalize(value),
+                None => true,
+            };
+
+            matches(&self.country, &place.country) && matches(&self.city, &place.city)
+        }
+
+        /// Whether the sources find the same places as the core, which compares text its own way.
+        pub fn is_upstream(&self) -> bool
+        {
+            self.country.is_none() && self.city.is_none()
+        }
+    }
+
+    /// Order of the places, ties are broken by id so that the order is total.
+    #[derive(Debug, Clone, Copy, PartialEq, Default)]
+    pub enum Sort
+    {
+        #[default]
+        Id,
+        Name,
+        City,
+    }
+
+    impl Sort
+    {
+        pub fn compare(&self, a: &Place, b: &Place) -> Ordering
+        {
+            let by_key = match self
+            {
+                Sort::Id   => Ordering::Equal,
+                Sort::Name => normalize(&a.data.name).cmp(&normalize(&b.data.name)),
+                Sort::City => normalize(&a.data.city).cmp(&normalize(&b.data.city)),
+            };
+            by_key.then_with(|| a.id.cmp(&b.id))
+        }
+    }
+
+    /// Lowercases the text, strips its accents and joins its words with a single space.
+    ///
+    /// Ex: "Gif-sur-Yvette" -> "gif sur yvette"
+    pub(super) fn normalize(text: &str) -> String
+    {
+        text.nfd()
+            .filter(|c| !is_combining_mark(*c))
+            .flat_map(char::to_lowercase)
+            .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[cfg(test)]
+    mod tests
+    {
+        use super::*;
+
+        fn place(city: &str, country: &str) -> PlaceData
+        {
+            named("COUM", city, country)
+        }
+
+        fn named(name: &str, city: &str, country: &str) -> PlaceData
+        {
+            PlaceData {
+                name        : String::from(name),
+                description : String::new(),
+                address     : String::new(),
+                postcode    : String::new(),
+                city        : String::from(city),
+                country     : String::from(country),
+            }
+        }
+
+        #[test]
+        fn test_normalize()
+        {
+            assert_eq!(normalize("Gif-sur-Yvette"), "gif sur yvette");
+            assert_eq!(normalize("  gif sur  yvette "), "gif sur yvette");
+            assert_eq!(normalize("Île-de-France"), "ile de france");
+            assert_eq!(normalize("L'Haÿ-les-Roses"), "l hay les roses");
+        }
+
+        #[test]
+        fn test_matches()
+        {
+            let filters = Filters { country: Some(String::from("france")), city: Some(String::from("gif sur yvette")) };
+            assert!(filters.matches(&place("Gif-sur-Yvette", "France")));
+            assert!(!filters.matches(&place("Bures-sur-Yvette", "France")));
+            assert!(!filters.matches(&place("Gif-sur-Yvette", "Belgium")));
+
+            let filters = Filters { country: Some(String::from("Belgique")), city: None };
+            assert!(filters.matches(&place("Liège", "Belgique")));
+            assert!(!filters.matches(&place("Paris", "France")));
+
+            let filters = Filters { country: None, city: None };
+            assert!(filters.matches(&place("Paris", "France")));
+        }
+
+        #[test]
+        fn test_sort()
+        {
+            let a = Place { id: String::from("0002-00000001"), data: named("Bloc Session", "Évry", "France") };
+            let b = Place { id: String::from("0001-00000003"), data: named("arkose", "Paris", "France") };
+            let c = Place { id: String::from("0001-00000002"), data: named("Arkose", "Évry", "France") };
+
+            assert_eq!(Sort::Id.compare(&a, &b), Ordering::Greater);
+            assert_eq!(Sort::Name.compare(&a, &b), Ordering::Greater);
+            // same name, the id breaks the tie
+            assert_eq!(Sort::Name.compare(&b, &c), Ordering::Greater);
+            assert_eq!(Sort::City.compare(&a, &b), Ordering::Less);
+            assert_eq!(Sort::City.compare(&a, &c), Ordering::Greater);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn place(postcode: &str, country: &str) -> PlaceData
+    {
+        PlaceData {
+            name        : String::from("COUM"),
+            description : String::new(),
+            address     : String::from("3 Rue Joliot Curie"),
+            postcode    : String::from(postcode),
+            city        : String::from("Gif-sur-Yvette"),
+            country     : String::from(country),
+        }
+    }
+
+    #[test]
+    fn test_validate()
+    {
+        assert_eq!(place("91190", "France").validate(), Ok(()));
+        assert_eq!(place("1012 AB", "Nederland").validate(), Ok(()));
+        assert_eq!(place("H2X 1Y4", "Canada").validate(), Ok(()));
+        assert_eq!(place("12345-6789", "USA").validate(), Ok(()));
+        // unknown formats are not checked
+        assert_eq!(place("", "Atlantis").validate(), Ok(()));
+
+        assert_eq!(place("9119", "France").validate(), Err(vec![InvalidField::new("postcode", "must be of the form `99999` in France")]));
+        assert_eq!(place("1000", "Belgique").validate(), Ok(()));
+        assert!(place("1000 B", "Belgique").validate().is_err());
+
+        let blank = PlaceData { name: String::new(), city: String::from(" "), ..place("91190", "France") };
+        let fields: Vec<&str> = blank.validate().unwrap_err().iter().map(|invalid| invalid.field).collect();
+        assert_eq!(fields, ["name", "city"]);
+    }
+}
@@ -2,12 +2,14 @@ pub mod get
 {
     use async_trait::async_trait;
     use crate::errors::{GetAllError, GetError};
-    use super::super::domain::{get::Filters, Place, PlaceId};
+    use crate::contexts::sources::domain::{Aggregate, Window};
+    use super::super::domain::{get::{Filters, Sort}, Place, PlaceId};
 
     #[async_trait]
     pub trait IRepository : Send + Sync
     {
-        async fn get_all(&self, filters: Filters) -> Result<Vec<Place>, GetAllError>;
+        /// The items of the window must be among the returned ones, sources may return more.
+        async fn get_all(&self, filters: Filters, sort: Sort, window: Window) -> Result<Aggregate<Place>, GetAllError>;
         async fn get(&self, id: PlaceId) -> Result<Place, GetError>;
     }
 }
@@ -16,12 +18,12 @@ pub mod post
 {
     use async_trait::async_trait;
     use crate::errors::CreateError;
-    use super::super::domain::{PlaceData, Place};
+    use super::super::domain::{PlaceData, Place, SourceId};
 
     #[async_trait]
     pub trait IRepository : Send + Sync
     {
-        async fn create(&self, data: PlaceData) -> Result<Place, CreateError>;
+        async fn create(&self, source_id: SourceId, data: PlaceData) -> Result<Place, CreateError>;
     }
 }
 
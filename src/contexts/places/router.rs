@@ -4,43 +4,65 @@ use serde::{Deserialize, Serialize};
 
 pub mod get
 {
-    use rocket::{get, serde::json::Json, State, FromForm, response::status::Custom, http::Status};
+    use rocket::{get, serde::json::Json, State, FromForm, FromFormField, http::Status};
     use rocket_okapi::{
         openapi,
         okapi::schemars::{self, JsonSchema},
     };
 
     use super::super::{use_cases::get::UseCase, domain_to_router, router_to_domain};
-    use super::{Place, PlaceId};
+    use super::Place;
+    use crate::typeutil::routers::Id;
 
     use crate::errors::{GetAllError, GetError};
+    use crate::problems::Problem;
+    use crate::contexts::sources::{router::Listing, router_to_domain::window};
 
     /// # Get the place that has the given id
     ///
     /// Returns the place that has the given id.
     #[openapi(tag = "Place")]
     #[get("/places/<id>")]
-    pub async fn get_place(id: PlaceId, use_case: &State<UseCase>) -> Result<Json<Place>, Custom<String>>
+    pub async fn get_place(id: Id, use_case: &State<UseCase>) -> Result<Json<Place>, Problem>
     {
-        match use_case.get(router_to_domain::place_id(id)).await
+        match use_case.get(router_to_domain::place_id(id)?).await
         {
             Ok(place) => Ok(Json(domain_to_router::place(place))),
-            Err(GetError::NonExistingId(id)) => Err(Custom(Status::NotFound, format!("Place with id `{id}` was not found."))),
-            Err(GetError::InternalServerError) => Err(Custom(Status::InternalServerError, String::from("Internal Server Error"))),
+            Err(GetError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("Place with id `{id}` was not found.")).id(id)),
+            Err(GetError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(GetError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the place with id `{id}`.")).id(id)),
+            Err(GetError::BadUpstreamId(id)) => Err(Problem::new(Status::BadGateway, format!("The source answered with the invalid id `{id}`.")).id(id)),
+            Err(GetError::Source(failure)) => Err(Problem::from(failure)),
+            Err(GetError::InternalServerError) => Err(Problem::internal()),
         }
     }
 
     /// # Get places that match the given filters
     ///
-    /// Returns all places that match the given filters.
+    /// Returns a page of the places that match the given filters, sorted by `sort` (by id by default).
+    /// Pages hold `limit` places (100 by default, 1000 at most) starting at `offset`,
+    /// or where the previous page ended when given its `cursor`.
+    /// The following page is linked in the `Link` header.
+    /// The status of each source is given in the `X-Source-Status` headers,
+    /// with `strict=true` the request fails with `502 Bad Gateway` if any source failed.
     #[openapi(tag = "Place")]
-    #[get("/places?<filters..>")]
-    pub async fn get_all_places(filters: Filters, use_case: &State<UseCase>) -> Result<Json<Vec<Place>>, Custom<String>>
+    #[get("/places?<strict>&<sort>&<limit>&<offset>&<cursor>&<filters..>")]
+    pub async fn get_all_places(
+        strict   : Option<bool>,
+        sort     : Option<Sort>,
+        limit    : Option<usize>,
+        offset   : Option<usize>,
+        cursor   : Option<String>,
+        filters  : Filters,
+        use_case : &State<UseCase>,
+    ) -> Result<Listing<Place>, Problem>
     {
-        match use_case.get_all(router_to_domain::get::filters(filters)).await
+        let sort_name = sort.map_or("id", Sort::as_str);
+        let window = window(limit, offset, cursor, sort_name)?;
+        match use_case.get_all(router_to_domain::get::filters(filters), router_to_domain::get::sort(sort), window).await
         {
-            Ok(places) => Ok(Json(places.into_iter().map(domain_to_router::place).collect())),
-            Err(GetAllError::InternalServerError) => Err(Custom(Status::InternalServerError, String::from("Internal Server Error"))),
+            Ok(places) => Listing::from_page(places, domain_to_router::place, sort_name).strict(strict.unwrap_or(false)),
+            Err(GetAllError::InternalServerError) => Err(Problem::internal()),
         } 
     }
 
@@ -51,32 +73,62 @@ pub mod get
         pub city    : Option<String>,
     }
 
+    #[derive(FromFormField, JsonSchema, Debug, Clone, Copy)]
+    #[serde(rename_all = "snake_case")]
+    #[schemars(rename = "PlaceSort")]
+    pub enum Sort
+    {
+        Name,
+        City,
+    }
+
+    impl Sort
+    {
+        pub fn as_str(self) -> &'static str
+        {
+            match self
+            {
+                Sort::Name => "name",
+                Sort::City => "city",
+            }
+        }
+    }
+
 }
 
 pub mod post
 {
     use rocket::http::Status;
-    use rocket::response::status::Custom;
     use rocket::{post, serde::json::Json, State};
     use rocket_okapi::openapi;
 
     use crate::errors::CreateError;
+    use crate::problems::Problem;
+    use crate::contexts::auth::router::AuthenticatedUser;
 
     use super::super::{use_cases::post::UseCase, domain_to_router, router_to_domain};
 
-    use super::{PlaceData, Place};
+    use super::{PlaceData, Place, SourceId};
 
     /// # Create a new place with the given data
     ///
-    /// Returns the newly created place with an associated id
+    /// Returns the newly created place with an associated id, the place is created in the given source.
     #[openapi(tag = "Place")]
-    #[post("/places", data = "<route_data>")]
-    pub async fn create_place(route_data: Json<PlaceData>, use_case: &State<UseCase>) -> Result<Json<Place>, Custom<String>>
+    #[post("/places?<source>", data = "<place_data>")]
+    pub async fn create_place(source: SourceId, place_data: Json<PlaceData>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<Json<Place>, Problem>
     {
-        match use_case.create(router_to_domain::place_data(route_data.into_inner())).await
+        match use_case.create(&user.access, router_to_domain::source_id(source), router_to_domain::place_data(place_data.into_inner())).await
         {
             Ok(place) => Ok(Json(domain_to_router::place(place))),
-            Err(CreateError::InternalServerError) => Err(Custom(Status::InternalServerError, String::from("Internal Server Error"))),
+            Err(CreateError::AlreadyExists(id)) => Err(Problem::new(Status::Conflict, format!("A place with id `{id}` already exists.")).id(id)),
+            Err(CreateError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(CreateError::Forbidden(source)) => Err(Problem::new(Status::Forbidden, format!("You may not change the source `{source}`."))),
+            Err(CreateError::NonExistingReference(id)) => Err(Problem::new(Status::NotFound, format!("`{id}` does not exist.")).id(id)),
+            Err(CreateError::UnknownSource(source)) => Err(Problem::new(Status::NotFound, format!("No source with id `{source}`.")).id(source)),
+            Err(CreateError::BadUpstreamId(id)) => Err(Problem::new(Status::BadGateway, format!("The source answered with the invalid id `{id}`.")).id(id)),
+            Err(CreateError::Invalid(fields)) => Err(Problem::invalid(fields)),
+            Err(CreateError::Source(failure)) => Err(Problem::from(failure)),
+            Err(CreateError::InternalServerError) => Err(Problem::internal()),
         }
     }
 }
@@ -84,27 +136,37 @@ pub mod post
 pub mod put
 {
     use rocket::http::Status;
-    use rocket::response::status::{self, Custom};
+    use rocket::response::status;
     use rocket::{put, serde::json::Json, State};
     use rocket_okapi::openapi;
 
     use crate::errors::UpdateError;
+    use crate::problems::Problem;
+    use crate::contexts::auth::router::AuthenticatedUser;
 
     use super::super::{use_cases::put::UseCase, router_to_domain};
 
-    use super::{PlaceData, PlaceId};
+    use super::PlaceData;
+    use crate::typeutil::routers::Id;
 
     /// # Update an existing place
     #[openapi(tag = "Place")]
-    #[put("/places/<id>", data = "<route_data>")]
-    pub async fn update_place(id: PlaceId, route_data: Json<PlaceData>, use_case: &State<UseCase>) -> Result<status::NoContent, Custom<String>>
+    #[put("/places/<id>", data = "<place_data>")]
+    pub async fn update_place(id: Id, place_data: Json<PlaceData>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
     {
         
-        match use_case.update(router_to_domain::place_id(id), router_to_domain::place_data(route_data.into_inner())).await
+        match use_case.update(&user.access, router_to_domain::place_id(id)?, router_to_domain::place_data(place_data.into_inner())).await
         {
             Ok(()) => Ok(status::NoContent),
-            Err(UpdateError::NonExistingId(id)) => Err(Custom(Status::NotFound, format!("No existing place with id `{id}`."))),
-            Err(UpdateError::InternalServerError) => Err(Custom(Status::InternalServerError, String::from("Internal Server Error"))),
+            Err(UpdateError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No existing place with id `{id}`.")).id(id)),
+            Err(UpdateError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(UpdateError::Forbidden(source)) => Err(Problem::new(Status::Forbidden, format!("You may not change the source `{source}`."))),
+            Err(UpdateError::NotOwner(id)) => Err(Problem::new(Status::Forbidden, format!("`{id}` belongs to another user.")).id(id)),
+            Err(UpdateError::NonExistingReference(id)) => Err(Problem::new(Status::NotFound, format!("`{id}` does not exist.")).id(id)),
+            Err(UpdateError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the place with id `{id}`.")).id(id)),
+            Err(UpdateError::Invalid(fields)) => Err(Problem::invalid(fields)),
+            Err(UpdateError::Source(failure)) => Err(Problem::from(failure)),
+            Err(UpdateError::InternalServerError) => Err(Problem::internal()),
         }
     }
 }
@@ -112,32 +174,40 @@ pub mod put
 pub mod delete
 {
     use rocket::http::Status;
-    use rocket::response::status::{self, Custom};
+    use rocket::response::status;
     use rocket::{delete, State};
     use rocket_okapi::openapi;
 
     use crate::errors::DeleteError;
+    use crate::problems::Problem;
+    use crate::contexts::auth::router::AuthenticatedUser;
 
     use super::super::{use_cases::delete::UseCase, router_to_domain};
 
-    use super::PlaceId;
+    use crate::typeutil::routers::Id;
 
     /// # Delete an existing place
     #[openapi(tag = "Place")]
     #[delete("/places/<id>")]
-    pub async fn delete_place(id: PlaceId, use_case: &State<UseCase>) -> Result<status::NoContent, Custom<String>>
+    pub async fn delete_place(id: Id, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
     {
         
-        match use_case.delete(router_to_domain::place_id(id)).await
+        match use_case.delete(&user.access, router_to_domain::place_id(id)?).await
         {
             Ok(()) => Ok(status::NoContent),
-            Err(DeleteError::NonExistingId(id)) => Err(Custom(Status::NotFound, format!("No existing place with id `{id}`."))),
-            Err(DeleteError::InternalServerError) => Err(Custom(Status::InternalServerError, String::from("Internal Server Error"))),
+            Err(DeleteError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No existing place with id `{id}`.")).id(id)),
+            Err(DeleteError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(DeleteError::Forbidden(source)) => Err(Problem::new(Status::Forbidden, format!("You may not change the source `{source}`."))),
+            Err(DeleteError::NotOwner(id)) => Err(Problem::new(Status::Forbidden, format!("`{id}` belongs to another user.")).id(id)),
+            Err(DeleteError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the place with id `{id}`.")).id(id)),
+            Err(DeleteError::Source(failure)) => Err(Problem::from(failure)),
+            Err(DeleteError::InternalServerError) => Err(Problem::internal()),
         }
     }
 }
 
 pub type PlaceId = String;
+pub type SourceId = u16;
 
 #[derive(FromForm, JsonSchema, Debug)]
 pub struct Filters
@@ -1,6 +1,8 @@
 use super::{router, domain};
+use crate::typeutil::{routers::Id, ids::ParseIdError};
 
-pub fn place_id(id: router::PlaceId) -> domain::PlaceId { id }
+pub fn place_id(id: Id) -> Result<domain::PlaceId, ParseIdError> { id.0.map(|id| id.to_string()) }
+pub fn source_id(id: router::SourceId) -> domain::SourceId { id }
 pub fn place_data(pd: router::PlaceData) -> domain::PlaceData
 {
     domain::PlaceData { 
@@ -27,4 +29,14 @@ pub mod get
             city    : f.city,
         }
     }
+
+    pub fn sort(s: Option<router::Sort>) -> domain::Sort
+    {
+        match s
+        {
+            None                     => domain::Sort::Id,
+            Some(router::Sort::Name) => domain::Sort::Name,
+            Some(router::Sort::City) => domain::Sort::City,
+        }
+    }
 }
\ No newline at end of file
@@ -1,24 +1,29 @@
 pub mod get
 {
+    use std::sync::Arc;
+
     use crate::errors::{GetAllError, GetError};
+    use crate::contexts::sources::domain::{Page, Window};
 
-    use super::super::{domain::{Place, PlaceId, get::Filters}, irepository::get::IRepository};
+    use super::super::{domain::{Place, PlaceId, get::{Filters, Sort}}, irepository::get::IRepository};
     pub struct UseCase
     {
-        repository: Box<dyn IRepository>,
+        repository: Arc<dyn IRepository>,
     }
-    unsafe impl Send for UseCase {}
-    unsafe impl Sync for UseCase {}
     impl UseCase
     {
-        pub fn new(repo: Box<dyn IRepository>) -> Self
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
         {
             Self { repository: repo }
         }
         
-        pub async fn get_all(&self, filters: Filters) -> Result<Vec<Place>, GetAllError>
+        /// Filters are applied on the aggregated places, the sources being asked for every place
+        /// since they would not match text as the core does. The places are then sorted and cut to the window.
+        pub async fn get_all(&self, filters: Filters, sort: Sort, window: Window) -> Result<Page<Place>, GetAllError>
         {
-            self.repository.get_all(filters).await
+            let mut places = self.repository.get_all(filters.clone(), sort, window).await?;
+            places.items.retain(|place| filters.matches(&place.data));
+            Ok(places.page(window, |a, b| sort.compare(a, b)))
         }    
 
         pub async fn get(&self, id: PlaceId) -> Result<Place, GetError>
@@ -31,49 +36,63 @@ pub mod get
 
 pub mod post
 {
+    use std::sync::Arc;
+
     use crate::errors::CreateError;
+    use crate::contexts::auth::domain::Access;
 
-    use super::super::{domain::{Place, PlaceData}, irepository::post::IRepository};
+    use super::super::{domain::{Place, PlaceData, SourceId}, irepository::post::IRepository};
     pub struct UseCase
     {
-        repository: Box<dyn IRepository>,
+        repository: Arc<dyn IRepository>,
     }
-    unsafe impl Send for UseCase {}
-    unsafe impl Sync for UseCase {}
     impl UseCase
     {
-        pub fn new(repo: Box<dyn IRepository>) -> Self
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
         {
             Self { repository: repo }
         }
 
-        pub async fn create(&self, data: PlaceData) -> Result<Place, CreateError>
+        pub async fn create(&self, access: &Access, source_id: SourceId, data: PlaceData) -> Result<Place, CreateError>
         {
-            self.repository.create(data).await
+            if !access.can_manage_places(source_id)
+            {
+                return Err(CreateError::Forbidden(source_id));
+            }
+            data.validate().map_err(CreateError::Invalid)?;
+            self.repository.create(source_id, data).await
         }     
     }
 }
 
 pub mod put
 {
+    use std::sync::Arc;
+
     use crate::errors::UpdateError;
+    use crate::contexts::auth::domain::Access;
+    use crate::typeutil::ids::RelativeId;
 
     use super::super::{domain::{PlaceData, PlaceId}, irepository::put::IRepository};
     pub struct UseCase
     {
-        repository: Box<dyn IRepository>,
+        repository: Arc<dyn IRepository>,
     }
-    unsafe impl Send for UseCase {}
-    unsafe impl Sync for UseCase {}
     impl UseCase
     {
-        pub fn new(repo: Box<dyn IRepository>) -> Self
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
         {
             Self { repository: repo }
         }
 
-        pub async fn update(&self, id: PlaceId, data: PlaceData) -> Result<(), UpdateError>
+        pub async fn update(&self, access: &Access, id: PlaceId, data: PlaceData) -> Result<(), UpdateError>
         {
+            let Ok(RelativeId { source_id, .. }) = id.parse() else { return Err(UpdateError::MalformedId(id)) };
+            if !access.can_manage_places(source_id)
+            {
+                return Err(UpdateError::Forbidden(source_id));
+            }
+            data.validate().map_err(UpdateError::Invalid)?;
             self.repository.update(id, data).await
         }     
     }
@@ -81,24 +100,31 @@ pub mod put
 
 pub mod delete
 {
+    use std::sync::Arc;
+
     use crate::errors::DeleteError;
+    use crate::contexts::auth::domain::Access;
+    use crate::typeutil::ids::RelativeId;
 
     use super::super::{domain::PlaceId, irepository::delete::IRepository};
     pub struct UseCase
     {
-        repository: Box<dyn IRepository>,
+        repository: Arc<dyn IRepository>,
     }
-    unsafe impl Send for UseCase {}
-    unsafe impl Sync for UseCase {}
     impl UseCase
     {
-        pub fn new(repo: Box<dyn IRepository>) -> Self
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
         {
             Self { repository: repo }
         }
 
-        pub async fn delete(&self, id: PlaceId) -> Result<(), DeleteError>
+        pub async fn delete(&self, access: &Access, id: PlaceId) -> Result<(), DeleteError>
         {
+            let Ok(RelativeId { source_id, .. }) = id.parse() else { return Err(DeleteError::MalformedId(id)) };
+            if !access.can_manage_places(source_id)
+            {
+                return Err(DeleteError::Forbidden(source_id));
+            }
             self.repository.delete(id).await
         }     
     }
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+pub use super::super::users::domain::Username;
+pub use super::super::routes::domain::RouteId;
+use super::super::ascents::domain::Ascent;
+
+/// What users mark routes with, sends come from their ascents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flag
+{
+    Liked,
+    Project,
+}
+
+/// Flags a user set on a route, a route without any flag set has no marks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marks
+{
+    pub route_id : RouteId,
+    pub liked    : bool,
+    pub project  : bool,
+}
+
+impl Marks
+{
+    pub fn new(route_id: RouteId) -> Self
+    {
+        Marks { route_id, liked: false, project: false }
+    }
+
+    pub fn set(&mut self, flag: Flag)
+    {
+        match flag
+        {
+            Flag::Liked   => self.liked = true,
+            Flag::Project => self.project = true,
+        }
+    }
+
+    pub fn clear(&mut self, flag: Flag)
+    {
+        match flag
+        {
+            Flag::Liked   => self.liked = false,
+            Flag::Project => self.project = false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        !(self.liked || self.project)
+    }
+}
+
+/// Progress of a user on a route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress
+{
+    pub route_id : RouteId,
+    pub sent     : bool,
+    pub flashed  : bool,
+    pub liked    : bool,
+    pub project  : bool,
+}
+
+impl Progress
+{
+    pub fn new(route_id: RouteId) -> Self
+    {
+        Progress { route_id, sent: false, flashed: false, liked: false, project: false }
+    }
+}
+
+/// The progress of a user on every route it marked or climbed, ordered by route id.
+///
+/// A route is sent by any ascent but a top rope one, and flashed by an onsight or a flash.
+pub fn progress(marks: Vec<Marks>, ascents: &[Ascent]) -> Vec<Progress>
+{
+    let mut progress: BTreeMap<RouteId, Progress> = BTreeMap::new();
+    for marks in marks
+    {
+        let entry = progress.entry(marks.route_id.clone()).or_insert_with(|| Progress::new(marks.route_id));
+        entry.liked = marks.liked;
+        entry.project = marks.project;
+    }
+    for ascent in ascents
+    {
+        let route_id = &ascent.data.route_id;
+        let entry = progress.entry(route_id.clone()).or_insert_with(|| Progress::new(route_id.clone()));
+        entry.sent |= ascent.data.style.is_send();
+        entry.flashed |= ascent.data.style.is_flash();
+    }
+    progress.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::contexts::ascents::domain::{AscentData, Date, Style};
+
+    fn ascent(route_id: &str, style: Style) -> Ascent
+    {
+        Ascent {
+            id   : 1,
+            data : AscentData {
+                route_id : String::from(route_id),
+                date     : Date::from_ymd_opt(2023, 5, 1).unwrap(),
+                style,
+                attempts : 1,
+                grade    : None,
+                rating   : None,
+                notes    : String::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_marks()
+    {
+        let mut marks = Marks::new(String::from("0001-0000000F"));
+        marks.set(Flag::Liked);
+        marks.set(Flag::Project);
+        marks.clear(Flag::Project);
+        assert!(marks.liked && !marks.project);
+        marks.clear(Flag::Liked);
+        assert!(marks.is_empty());
+    }
+
+    #[test]
+    fn test_progress()
+    {
+        let mut marks = Marks::new(String::from("0001-00000002"));
+        marks.set(Flag::Project);
+        let ascents = [
+            ascent("0001-00000001", Style::Redpoint),
+            ascent("0001-00000001", Style::Flash),
+            ascent("0001-00000002", Style::Toprope),
+            ascent("0001-00000003", Style::Repeat),
+        ];
+
+        let progress = progress(vec![marks], &ascents);
+        let flags: Vec<(&str, bool, bool, bool)> = progress.iter().map(|p| (p.route_id.as_str(), p.sent, p.flashed, p.project)).collect();
+        assert_eq!(flags, vec![
+            ("0001-00000001", true, true, false),
+            ("0001-00000002", false, false, true),
+            ("0001-00000003", true, false, false),
+        ]);
+    }
+}
@@ -0,0 +1,12 @@
+use super::{domain, router};
+
+pub fn progress(p: domain::Progress) -> router::Progress
+{
+    router::Progress {
+        route_id : p.route_id,
+        sent     : p.sent,
+        flashed  : p.flashed,
+        liked    : p.liked,
+        project  : p.project,
+    }
+}
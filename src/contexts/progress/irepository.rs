@@ -0,0 +1,42 @@
+pub mod get
+{
+    use async_trait::async_trait;
+    use crate::errors::GetAllError;
+    use super::super::domain::{Marks, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// Usernames are compared regardless of case.
+        async fn get_all(&self, username: Username) -> Result<Vec<Marks>, GetAllError>;
+    }
+}
+
+pub mod put
+{
+    use async_trait::async_trait;
+    use crate::errors::UpdateError;
+    use super::super::domain::{Flag, Marks, RouteId, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// Sets the flag of the route to `value` in a single step, leaving the other flag as it is even if it changes meanwhile.
+        /// Returns the resulting marks, which are removed once empty.
+        async fn mark(&self, username: Username, route_id: RouteId, flag: Flag, value: bool) -> Result<Marks, UpdateError>;
+    }
+}
+
+pub mod delete
+{
+    use async_trait::async_trait;
+    use crate::errors::DeleteError;
+    use super::super::domain::Username;
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// Removes every mark of the user, there may be none.
+        async fn forget(&self, username: Username) -> Result<(), DeleteError>;
+    }
+}
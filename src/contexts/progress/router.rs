@@ -0,0 +1,125 @@
+use rocket::request::FromParam;
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+pub mod get
+{
+    use rocket::{get, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use super::super::{use_cases::get::UseCase, domain_to_router};
+    use super::Progress;
+
+    use crate::errors::GetAllError;
+    use crate::problems::Problem;
+
+    /// # Get the progress of a user
+    ///
+    /// Returns the routes the user has liked, projects or climbed, `sent` and `flashed` being given by the user's ascents.
+    #[openapi(tag = "Progress")]
+    #[get("/progress/<username>")]
+    pub async fn get_progress(username: String, use_case: &State<UseCase>) -> Result<Json<Vec<Progress>>, Problem>
+    {
+        match use_case.get_all(username).await
+        {
+            Ok(progress) => Ok(Json(progress.into_iter().map(domain_to_router::progress).collect())),
+            Err(GetAllError::InternalServerError) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod put
+{
+    use rocket::http::Status;
+    use rocket::{put, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use crate::errors::UpdateError;
+    use crate::problems::Problem;
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::put::UseCase, domain_to_router, router_to_domain};
+    use super::{Flag, Progress};
+
+    /// # Set a flag of a user's progress on a route
+    ///
+    /// Returns the progress on the route. Users can only change their own progress.
+    #[openapi(tag = "Progress")]
+    #[put("/progress/<username>/<route_id>/<flag>")]
+    pub async fn set_progress(username: String, route_id: Id, flag: Flag, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<Json<Progress>, Problem>
+    {
+        user.acting_as(&username)?;
+        match use_case.set(username, router_to_domain::route_id(route_id)?, router_to_domain::flag(flag)).await
+        {
+            Ok(progress) => Ok(Json(domain_to_router::progress(progress))),
+            Err(UpdateError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No existing route with id `{id}`.")).id(id)),
+            Err(UpdateError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(UpdateError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the route with id `{id}`.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod delete
+{
+    use rocket::{delete, serde::json::Json, State};
+    use rocket_okapi::openapi;
+    use crate::problems::Problem;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::put::UseCase, domain_to_router, router_to_domain};
+    use super::{Flag, Progress};
+
+    /// # Clear a flag of a user's progress on a route
+    ///
+    /// Returns the progress on the route. Users can only change their own progress.
+    #[openapi(tag = "Progress")]
+    #[delete("/progress/<username>/<route_id>/<flag>")]
+    pub async fn clear_progress(username: String, route_id: Id, flag: Flag, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<Json<Progress>, Problem>
+    {
+        user.acting_as(&username)?;
+        match use_case.clear(username, router_to_domain::route_id(route_id)?, router_to_domain::flag(flag)).await
+        {
+            Ok(progress) => Ok(Json(domain_to_router::progress(progress))),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Progress
+{
+    pub route_id : String,
+    pub sent     : bool,
+    pub flashed  : bool,
+    pub liked    : bool,
+    pub project  : bool,
+}
+
+/// Path parameter naming a flag users set themselves, other values are not matched.
+///
+/// Sends are logged as ascents.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Flag
+{
+    Liked,
+    Project,
+}
+
+impl<'a> FromParam<'a> for Flag
+{
+    type Error = &'a str;
+    fn from_param(param: &'a str) -> Result<Self, Self::Error>
+    {
+        match param
+        {
+            "liked"   => Ok(Flag::Liked),
+            "project" => Ok(Flag::Project),
+            _ => Err(param),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use super::{domain, router};
+use crate::typeutil::{routers::Id, ids::ParseIdError};
+
+pub fn route_id(id: Id) -> Result<domain::RouteId, ParseIdError> { id.0.map(|id| id.to_string()) }
+
+pub fn flag(f: router::Flag) -> domain::Flag
+{
+    match f
+    {
+        router::Flag::Liked   => domain::Flag::Liked,
+        router::Flag::Project => domain::Flag::Project,
+    }
+}
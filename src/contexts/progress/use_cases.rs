@@ -0,0 +1,90 @@
+pub mod get
+{
+    use std::sync::Arc;
+
+    use crate::errors::GetAllError;
+    use crate::contexts::ascents::irepository::get::IRepository as AscentRepository;
+
+    use super::super::{domain::{self, Progress, Username}, irepository::get::IRepository};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        ascents    : Arc<dyn AscentRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, ascents: Arc<dyn AscentRepository>) -> Self
+        {
+            Self { repository: repo, ascents }
+        }
+
+        pub async fn get_all(&self, username: Username) -> Result<Vec<Progress>, GetAllError>
+        {
+            let marks = self.repository.get_all(username.clone()).await?;
+            let ascents = self.ascents.get_all(username).await?;
+            Ok(domain::progress(marks, &ascents))
+        }
+    }
+}
+
+pub mod put
+{
+    use std::sync::Arc;
+
+    use crate::errors::{GetAllError, GetError, UpdateError};
+    use crate::contexts::routes::irepository::get::IRepository as RouteRepository;
+    use crate::contexts::ascents::irepository::get::IRepository as AscentRepository;
+
+    use crate::typeutil::ids::RelativeId;
+
+    use super::super::{domain::{self, Flag, Progress, RouteId, Username}, irepository::put::IRepository};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        routes     : Arc<dyn RouteRepository>,
+        ascents    : Arc<dyn AscentRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, routes: Arc<dyn RouteRepository>, ascents: Arc<dyn AscentRepository>) -> Self
+        {
+            Self { repository: repo, routes, ascents }
+        }
+
+        /// The route must exist in its source, its id is stored as the source gives it.
+        pub async fn set(&self, username: Username, route_id: RouteId, flag: Flag) -> Result<Progress, UpdateError>
+        {
+            let route_id = match self.routes.get(route_id).await
+            {
+                Ok(route) => route.id,
+                Err(GetError::NonExistingId(id)) => return Err(UpdateError::NonExistingId(id)),
+                Err(GetError::MalformedId(id)) => return Err(UpdateError::MalformedId(id)),
+                Err(GetError::UnknownSource(id)) => return Err(UpdateError::UnknownSource(id)),
+                Err(GetError::Source(failure)) => return Err(UpdateError::Source(failure)),
+                Err(GetError::BadUpstreamId(_) | GetError::InternalServerError) => return Err(UpdateError::InternalServerError),
+            };
+            self.update(username, route_id, flag, true).await
+        }
+
+        /// Routes are not checked, so that the marks on a route that no longer exists can be cleared.
+        /// Their id is written as sources give it instead, to match the one stored when the flag was set.
+        pub async fn clear(&self, username: Username, route_id: RouteId, flag: Flag) -> Result<Progress, UpdateError>
+        {
+            let Ok(id) = route_id.parse::<RelativeId>() else { return Err(UpdateError::MalformedId(route_id)) };
+            self.update(username, id.to_string(), flag, false).await
+        }
+
+        /// Changes a flag of the route and gives the resulting progress on it.
+        async fn update(&self, username: Username, route_id: RouteId, flag: Flag, value: bool) -> Result<Progress, UpdateError>
+        {
+            let marks = self.repository.mark(username.clone(), route_id.clone(), flag, value).await?;
+
+            let ascents: Vec<_> = match self.ascents.get_all(username).await
+            {
+                Ok(ascents) => ascents.into_iter().filter(|ascent| ascent.data.route_id == route_id).collect(),
+                Err(GetAllError::InternalServerError) => return Err(UpdateError::InternalServerError),
+            };
+            Ok(domain::progress(vec![marks], &ascents).pop().unwrap_or_else(|| Progress::new(route_id)))
+        }
+    }
+}
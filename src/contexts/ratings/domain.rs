@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+pub use super::super::users::domain::Username;
+pub use super::super::routes::domain::RouteId;
+
+/// How many stars a user gives a route, users rate a route once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rating
+{
+    pub username : Username,
+    pub stars    : u8,
+}
+
+impl Rating
+{
+    pub const MIN_STARS : u8 = 1;
+    pub const MAX_STARS : u8 = 5;
+}
+
+/// The average of the ratings of a route.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Score
+{
+    pub average : f32,
+    pub count   : usize,
+}
+
+/// None without ratings.
+pub fn score(ratings: &[Rating]) -> Option<Score>
+{
+    let count = ratings.len();
+    let total: u32 = ratings.iter().map(|rating| u32::from(rating.stars)).sum();
+    (count > 0).then(|| Score { average: total as f32 / count as f32, count })
+}
+
+/// The score of each route that has ratings.
+pub fn scores(ratings: impl IntoIterator<Item = (RouteId, Rating)>) -> HashMap<RouteId, Score>
+{
+    let mut by_route: HashMap<RouteId, Vec<Rating>> = HashMap::new();
+    for (route_id, rating) in ratings
+    {
+        by_route.entry(route_id).or_default().push(rating);
+    }
+    by_route.into_iter()
+        .filter_map(|(route_id, ratings)| score(&ratings).map(|score| (route_id, score)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_score()
+    {
+        let rating = |stars| Rating { username: String::from("jdoe"), stars };
+        assert_eq!(score(&[rating(4), rating(5), rating(2)]), Some(Score { average: 11.0 / 3.0, count: 3 }));
+        assert_eq!(score(&[]), None);
+    }
+}
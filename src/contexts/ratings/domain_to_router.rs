@@ -0,0 +1,10 @@
+use super::{domain, router};
+
+/// A route without ratings has no average.
+pub fn score(s: Option<domain::Score>) -> router::Score
+{
+    router::Score {
+        average : s.map(|score| score.average),
+        count   : s.map_or(0, |score| score.count),
+    }
+}
@@ -0,0 +1,48 @@
+pub mod get
+{
+    use std::collections::HashMap;
+
+    use async_trait::async_trait;
+    use crate::errors::GetAllError;
+    use super::super::domain::{Rating, RouteId, Score};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        async fn get_all(&self, route_id: RouteId) -> Result<Vec<Rating>, GetAllError>;
+        /// The score of every rated route.
+        async fn scores(&self) -> Result<HashMap<RouteId, Score>, GetAllError>;
+        /// The scores of the routes at once, routes without ratings are left out.
+        async fn scores_by_route(&self, route_ids: Vec<RouteId>) -> Result<HashMap<RouteId, Score>, GetAllError>;
+    }
+}
+
+pub mod put
+{
+    use async_trait::async_trait;
+    use crate::errors::UpdateError;
+    use super::super::domain::{Rating, RouteId};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// Replaces the rating the user may already have given the route, usernames are compared regardless of case.
+        async fn save(&self, route_id: RouteId, rating: Rating) -> Result<(), UpdateError>;
+    }
+}
+
+pub mod delete
+{
+    use async_trait::async_trait;
+    use crate::errors::DeleteError;
+    use super::super::domain::{RouteId, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// `NonExistingId` with the route id when the user did not rate the route.
+        async fn delete(&self, route_id: RouteId, username: Username) -> Result<(), DeleteError>;
+        /// Removes every rating of the user, there may be none.
+        async fn forget(&self, username: Username) -> Result<(), DeleteError>;
+    }
+}
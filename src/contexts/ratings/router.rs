@@ -0,0 +1,110 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+pub mod get
+{
+    use rocket::{get, serde::json::Json, State, http::Status};
+    use rocket_okapi::openapi;
+
+    use crate::errors::GetError;
+    use crate::problems::Problem;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::get::UseCase, domain_to_router, router_to_domain};
+    use super::Score;
+
+    /// # Get the score of a route
+    ///
+    /// Returns the average of the stars users gave the route and how many users rated it.
+    #[openapi(tag = "Rating")]
+    #[get("/ratings/<route_id>")]
+    pub async fn get_score(route_id: Id, use_case: &State<UseCase>) -> Result<Json<Score>, Problem>
+    {
+        match use_case.score(router_to_domain::route_id(route_id)?).await
+        {
+            Ok(score) => Ok(Json(domain_to_router::score(score))),
+            Err(GetError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("Route with id `{id}` was not found.")).id(id)),
+            Err(GetError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(GetError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the route with id `{id}`.")).id(id)),
+            Err(GetError::BadUpstreamId(id)) => Err(Problem::new(Status::BadGateway, format!("The source answered with the invalid id `{id}`.")).id(id)),
+            Err(GetError::Source(failure)) => Err(Problem::from(failure)),
+            Err(GetError::InternalServerError) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod put
+{
+    use rocket::http::Status;
+    use rocket::{put, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::UpdateError;
+    use crate::problems::Problem;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::put::UseCase, domain_to_router, router_to_domain};
+    use super::{Rating, Score};
+
+    /// # Rate a route
+    ///
+    /// Replaces the rating the user gave before, if any, and returns the score of the route.
+    #[openapi(tag = "Rating")]
+    #[put("/ratings/<route_id>", data = "<rating>")]
+    pub async fn rate_route(route_id: Id, rating: Json<Rating>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<Json<Score>, Problem>
+    {
+        let stars = router_to_domain::stars(rating.into_inner().stars)?;
+        match use_case.rate(user.username, router_to_domain::route_id(route_id)?, stars).await
+        {
+            Ok(score) => Ok(Json(domain_to_router::score(Some(score)))),
+            Err(UpdateError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No existing route with id `{id}`.")).id(id)),
+            Err(UpdateError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(UpdateError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the route with id `{id}`.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod delete
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{delete, State};
+    use rocket_okapi::openapi;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::DeleteError;
+    use crate::problems::Problem;
+    use crate::typeutil::routers::Id;
+
+    use super::super::{use_cases::delete::UseCase, router_to_domain};
+
+    /// # Withdraw the rating the user gave a route
+    #[openapi(tag = "Rating")]
+    #[delete("/ratings/<route_id>")]
+    pub async fn withdraw_rating(route_id: Id, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
+    {
+        match use_case.withdraw(user.username, router_to_domain::route_id(route_id)?).await
+        {
+            Ok(()) => Ok(status::NoContent),
+            Err(DeleteError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("You did not rate the route `{id}`.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Rating
+{
+    /// From 1 to 5.
+    pub stars : u8,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Score
+{
+    /// Absent until a user rates the route.
+    pub average : Option<f32>,
+    pub count   : usize,
+}
@@ -0,0 +1,17 @@
+use rocket::http::Status;
+
+use super::domain::{self, Rating};
+use crate::typeutil::{routers::Id, ids::ParseIdError};
+use crate::problems::Problem;
+
+pub fn route_id(id: Id) -> Result<domain::RouteId, ParseIdError> { id.0.map(|id| id.to_string()) }
+
+/// 400 for a rating out of range.
+pub fn stars(stars: u8) -> Result<u8, Problem>
+{
+    match stars
+    {
+        Rating::MIN_STARS..=Rating::MAX_STARS => Ok(stars),
+        _ => Err(Problem::new(Status::BadRequest, format!("A rating goes from {} to {} stars.", Rating::MIN_STARS, Rating::MAX_STARS))),
+    }
+}
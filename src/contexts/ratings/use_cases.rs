@@ -0,0 +1,103 @@
+pub mod get
+{
+    use std::sync::Arc;
+
+    use crate::errors::{GetAllError, GetError};
+    use crate::contexts::routes::irepository::get::IRepository as RouteRepository;
+
+    use super::super::{domain::{self, RouteId, Score}, irepository::get::IRepository};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        routes     : Arc<dyn RouteRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, routes: Arc<dyn RouteRepository>) -> Self
+        {
+            Self { repository: repo, routes }
+        }
+
+        /// The route must exist in its source, there is no score until a user rates it.
+        pub async fn score(&self, route_id: RouteId) -> Result<Option<Score>, GetError>
+        {
+            let route = self.routes.get(route_id).await?;
+            match self.repository.get_all(route.id).await
+            {
+                Ok(ratings) => Ok(domain::score(&ratings)),
+                Err(GetAllError::InternalServerError) => Err(GetError::InternalServerError),
+            }
+        }
+    }
+}
+
+pub mod put
+{
+    use std::sync::Arc;
+
+    use crate::errors::{GetAllError, GetError, UpdateError};
+    use crate::contexts::routes::irepository::get::IRepository as RouteRepository;
+
+    use super::super::{domain::{self, Rating, RouteId, Score, Username}, irepository::{get, put::IRepository}};
+    pub struct UseCase
+    {
+        repository : Arc<dyn IRepository>,
+        ratings    : Arc<dyn get::IRepository>,
+        routes     : Arc<dyn RouteRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, ratings: Arc<dyn get::IRepository>, routes: Arc<dyn RouteRepository>) -> Self
+        {
+            Self { repository: repo, ratings, routes }
+        }
+
+        /// The route must exist in its source, its id is stored as the source gives it.
+        /// Returns the score of the route with the new rating.
+        pub async fn rate(&self, username: Username, route_id: RouteId, stars: u8) -> Result<Score, UpdateError>
+        {
+            let route_id = match self.routes.get(route_id).await
+            {
+                Ok(route) => route.id,
+                Err(GetError::NonExistingId(id)) => return Err(UpdateError::NonExistingId(id)),
+                Err(GetError::MalformedId(id)) => return Err(UpdateError::MalformedId(id)),
+                Err(GetError::UnknownSource(id)) => return Err(UpdateError::UnknownSource(id)),
+                Err(GetError::Source(failure)) => return Err(UpdateError::Source(failure)),
+                Err(GetError::BadUpstreamId(_) | GetError::InternalServerError) => return Err(UpdateError::InternalServerError),
+            };
+            self.repository.save(route_id.clone(), Rating { username, stars }).await?;
+
+            match self.ratings.get_all(route_id).await
+            {
+                Ok(ratings) => domain::score(&ratings).ok_or(UpdateError::InternalServerError),
+                Err(GetAllError::InternalServerError) => Err(UpdateError::InternalServerError),
+            }
+        }
+    }
+}
+
+pub mod delete
+{
+    use std::sync::Arc;
+
+    use crate::errors::DeleteError;
+
+    use super::super::{domain::{RouteId, Username}, irepository::delete::IRepository};
+    pub struct UseCase
+    {
+        repository: Arc<dyn IRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
+        {
+            Self { repository: repo }
+        }
+
+        /// Routes are not checked, so that ratings of a route that no longer exists can be withdrawn.
+        pub async fn withdraw(&self, username: Username, route_id: RouteId) -> Result<(), DeleteError>
+        {
+            self.repository.delete(route_id, username).await
+        }
+    }
+}
@@ -1,18 +1,25 @@
 use std::collections::HashMap;
 
+use crate::errors::InvalidField;
+use crate::typeutil::ids::RelativeId;
+
 use super::super::places::domain::PlaceId;
+pub use super::super::ratings::domain::Score;
+
+pub mod grade;
+pub use grade::{Grade, GradeSystem};
 
 pub type RouteProperties = HashMap<String, String>;
 pub type RouteId = String;
 pub type Date = chrono::NaiveDate;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RouteData
 {
     pub place_id     : PlaceId,
     pub name         : String,
     pub description  : String,
-    pub grade        : String,
+    pub grade        : Grade,
     pub color        : String,
     pub sector       : String,
     pub opening_date : Date,
@@ -22,7 +29,7 @@ pub struct RouteData
     pub properties   : RouteProperties,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Rules
 {
     pub sitstart        : bool,
@@ -30,21 +37,256 @@ pub struct Rules
     pub edges_allowed   : bool,
 }
 
+impl RouteData
+{
+    pub const MAX_TAGS       : usize = 20;
+    pub const MAX_TAG_LENGTH : usize = 32;
 
-#[derive(Debug)]
+    /// Lists every rule the data breaks, these hold whatever the source of the route.
+    pub fn validate(&self) -> Result<(), Vec<InvalidField>>
+    {
+        let mut invalid = Vec::new();
+        if self.place_id.parse::<RelativeId>().is_err()
+        {
+            invalid.push(InvalidField::new("place_id", "must be an id of the form `FFFF-FFFFFFFF`"));
+        }
+        if self.name.trim().is_empty()
+        {
+            invalid.push(InvalidField::new("name", "must not be blank"));
+        }
+        if matches!(self.closing_date, Some(closing_date) if closing_date < self.opening_date)
+        {
+            invalid.push(InvalidField::new("closing_date", "must not be before the opening date"));
+        }
+        if self.tags.len() > Self::MAX_TAGS
+        {
+            invalid.push(InvalidField::new("tags", format!("must not hold more than {} tags", Self::MAX_TAGS)));
+        }
+        if self.tags.iter().any(|tag| tag.trim().is_empty())
+        {
+            invalid.push(InvalidField::new("tags", "must not hold blank tags"));
+        }
+        if self.tags.iter().any(|tag| tag.chars().count() > Self::MAX_TAG_LENGTH)
+        {
+            invalid.push(InvalidField::new("tags", format!("must not hold tags longer than {} characters", Self::MAX_TAG_LENGTH)));
+        }
+        if invalid.is_empty() { Ok(()) } else { Err(invalid) }
+    }
+}
+
+
+#[derive(Debug, Clone)]
 pub struct Route
 {
-    pub id   : RouteId,
-    pub data : RouteData,
+    pub id              : RouteId,
+    pub data            : RouteData,
+    /// The grade users agree on, sources know nothing about it.
+    pub community_grade : Option<Grade>,
+    /// The stars users give it, sources know nothing about it either.
+    pub rating          : Option<Score>,
 }
 
 pub mod get
 {
+    use std::cmp::Ordering;
+
+    use super::{Grade, Route};
+
+    #[derive(Debug, Clone)]
     pub struct Filters
     {
-        pub min_grade  : Option<String>,
-        pub max_grade  : Option<String>,
+        pub min_grade  : Option<Grade>,
+        pub max_grade  : Option<Grade>,
+        /// Lowest average rating, sources know nothing about it.
+        pub min_rating : Option<f32>,
         pub tags       : Vec<String>,
         pub properties : super::RouteProperties,
     }
+
+    impl Filters
+    {
+        /// Whether the grade of the route lies within the grade bounds and its average rating reaches `min_rating`.
+        /// Routes whose grade can't be read never match a grade bound, routes without ratings never match `min_rating`.
+        pub fn matches(&self, route: &Route) -> bool
+        {
+            let within = |bound: &Option<Grade>, within: fn(Ordering) -> bool| match bound
+            {
+                Some(bound) => matches!(route.data.grade.difficulty().zip(bound.difficulty()), Some((grade, bound)) if within(grade.cmp(&bound))),
+                None => true,
+            };
+            let rated = match self.min_rating
+            {
+                Some(min_rating) => matches!(route.rating, Some(score) if score.average >= min_rating),
+                None => true,
+            };
+            within(&self.min_grade, Ordering::is_ge) && within(&self.max_grade, Ordering::is_le) && rated
+        }
+
+        /// Whether the sources find the same routes as the core, which checks them afterwards anyway.
+        /// Grades are compared on the scale of the core and ratings are unknown to the sources.
+        pub fn is_upstream(&self) -> bool
+        {
+            self.min_grade.is_none() && self.max_grade.is_none() && self.min_rating.is_none()
+        }
+    }
+
+    /// Order of the routes, ties are broken by id so that the order is total.
+    #[derive(Debug, Clone, Copy, PartialEq, Default)]
+    pub enum Sort
+    {
+        #[default]
+        Id,
+        Name,
+        Grade,
+        OpeningDate,
+        /// Best rated first, routes without ratings last.
+        Rating,
+    }
+
+    impl Sort
+    {
+        pub fn compare(&self, a: &Route, b: &Route) -> Ordering
+        {
+            let by_key = match self
+            {
+                Sort::Id          => Ordering::Equal,
+                Sort::Name        => a.data.name.to_lowercase().cmp(&b.data.name.to_lowercase()),
+                Sort::Grade       => a.data.grade.compare(&b.data.grade),
+                Sort::OpeningDate => a.data.opening_date.cmp(&b.data.opening_date),
+                Sort::Rating      => match (a.rating, b.rating)
+                {
+                    (Some(a), Some(b)) => b.average.total_cmp(&a.average),
+                    (Some(_), None)    => Ordering::Less,
+                    (None, Some(_))    => Ordering::Greater,
+                    (None, None)       => Ordering::Equal,
+                },
+            };
+            by_key.then_with(|| a.id.cmp(&b.id))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests
+    {
+        use super::*;
+        use super::super::{Date, RouteData, RouteProperties, Rules, Score};
+
+        fn rated(id: &str, grade: &str, average: Option<f32>) -> Route
+        {
+            Route {
+                id              : String::from(id),
+                data            : data(grade),
+                community_grade : None,
+                rating          : average.map(|average| Score { average, count: 1 }),
+            }
+        }
+
+        fn route(grade: &str) -> Route
+        {
+            rated("0001-00000001", grade, None)
+        }
+
+        fn data(grade: &str) -> RouteData
+        {
+            RouteData {
+                place_id     : String::from("0001-00000001"),
+                name         : String::from("Route 1"),
+                description  : String::new(),
+                grade        : Grade::parse(grade),
+                color        : String::new(),
+                sector       : String::new(),
+                opening_date : Date::from_ymd_opt(2021, 1, 1).unwrap(),
+                closing_date : None,
+                rules        : Rules { sitstart: false, modules_allowed: false, edges_allowed: false },
+                tags         : Vec::new(),
+                properties   : RouteProperties::new(),
+            }
+        }
+
+        fn filters(min_grade: Option<&str>, max_grade: Option<&str>) -> Filters
+        {
+            Filters {
+                min_grade  : min_grade.map(Grade::parse),
+                max_grade  : max_grade.map(Grade::parse),
+                min_rating : None,
+                tags       : Vec::new(),
+                properties : RouteProperties::new(),
+            }
+        }
+
+        #[test]
+        fn test_matches()
+        {
+            let bounded = filters(Some("6a"), Some("7a"));
+            assert!(bounded.matches(&route("6a")));
+            assert!(bounded.matches(&route("7a")));
+            assert!(bounded.matches(&route("5.11a")));
+            assert!(!bounded.matches(&route("5c")));
+            assert!(!bounded.matches(&route("V8")));
+            assert!(!bounded.matches(&route("yellow")));
+
+            assert!(filters(None, None).matches(&route("yellow")));
+
+            let rating = Filters { min_rating: Some(3.5), ..filters(None, None) };
+            assert!(rating.matches(&rated("0001-00000001", "6a", Some(3.5))));
+            assert!(!rating.matches(&rated("0001-00000001", "6a", Some(3.4))));
+            assert!(!rating.matches(&route("6a")));
+        }
+
+        #[test]
+        fn test_sort_by_rating()
+        {
+            let mut routes = [
+                rated("0001-00000001", "6a", None),
+                rated("0001-00000002", "6a", Some(3.0)),
+                rated("0001-00000003", "6a", Some(4.5)),
+                rated("0001-00000004", "6a", Some(3.0)),
+            ];
+            routes.sort_by(|a, b| Sort::Rating.compare(a, b));
+            let ids: Vec<&str> = routes.iter().map(|route| route.id.as_str()).collect();
+            assert_eq!(ids, vec!["0001-00000003", "0001-00000002", "0001-00000004", "0001-00000001"]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn data() -> RouteData
+    {
+        RouteData {
+            place_id     : String::from("0001-00000001"),
+            name         : String::from("Rocky Road"),
+            description  : String::new(),
+            grade        : Grade::parse("5c"),
+            color        : String::new(),
+            sector       : String::new(),
+            opening_date : Date::from_ymd_opt(2021, 1, 1).unwrap(),
+            closing_date : Date::from_ymd_opt(2021, 1, 1),
+            rules        : Rules { sitstart: false, modules_allowed: false, edges_allowed: false },
+            tags         : vec![String::from("challenging")],
+            properties   : RouteProperties::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate()
+    {
+        assert_eq!(data().validate(), Ok(()));
+
+        let invalid = RouteData {
+            place_id     : String::from("1"),
+            name         : String::from("  "),
+            closing_date : Date::from_ymd_opt(2020, 12, 31),
+            tags         : vec![String::new(), "x".repeat(RouteData::MAX_TAG_LENGTH + 1)],
+            ..data()
+        };
+        let fields: Vec<&str> = invalid.validate().unwrap_err().iter().map(|invalid| invalid.field).collect();
+        assert_eq!(fields, ["place_id", "name", "closing_date", "tags", "tags"]);
+
+        let tagged = RouteData { tags: vec![String::from("tag"); RouteData::MAX_TAGS + 1], ..data() };
+        assert_eq!(tagged.validate(), Err(vec![InvalidField::new("tags", "must not hold more than 20 tags")]));
+    }
 }
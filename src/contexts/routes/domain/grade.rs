@@ -0,0 +1,289 @@
+use std::cmp::Ordering;
+
+/// Grading systems the core can read and render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GradeSystem
+{
+    /// French sport grades, ex: `6a+`
+    French,
+    /// Fontainebleau boulder grades, ex: `7A`
+    Fontainebleau,
+    /// Hueco boulder grades, ex: `V5`
+    VScale,
+    /// Yosemite Decimal System, ex: `5.11c`
+    Yds,
+    /// UIAA grades in roman or arabic numerals, ex: `VII+` or `7+`
+    Uiaa,
+    /// British adjectival grades, with an optional technical grade, ex: `E1 5b`
+    British,
+}
+
+/// Difficulty on the common scale, which follows the French sport grades.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Difficulty(u8);
+
+/// A grade as given by a source, along with its difficulty when it could be read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grade
+{
+    pub text   : String,
+    pub rating : Option<Rating>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating
+{
+    pub system     : GradeSystem,
+    pub difficulty : Difficulty,
+}
+
+// Equivalences with French grades, from the usual conversion charts.
+// Boulder grades are matched with the sport grades of a similar effort.
+const FONTAINEBLEAU: &[(&str, &str)] = &[
+    ("3", "4a"), ("4", "4c"), ("4+", "5a"), ("5", "5b"), ("5+", "5c"),
+    ("6A", "6b+"), ("6A+", "6c"), ("6B", "6c+"), ("6B+", "7a"), ("6C", "7a+"), ("6C+", "7b"),
+    ("7A", "7b+"), ("7A+", "7c"), ("7B", "7c+"), ("7B+", "8a"), ("7C", "8a+"), ("7C+", "8b"),
+    ("8A", "8b+"), ("8A+", "8c"), ("8B", "8c+"), ("8B+", "9a"), ("8C", "9a+"), ("8C+", "9b"),
+    ("9A", "9b+"),
+];
+const V_SCALE: &[(&str, &str)] = &[
+    ("VB", "4a"), ("V0", "4c"), ("V1", "5b"), ("V2", "5c"), ("V3", "6b+"), ("V4", "6c+"),
+    ("V5", "7a+"), ("V6", "7b+"), ("V7", "7c"), ("V8", "7c+"), ("V9", "8a+"), ("V10", "8b"),
+    ("V11", "8b+"), ("V12", "8c"), ("V13", "8c+"), ("V14", "9a"), ("V15", "9a+"), ("V16", "9b"),
+    ("V17", "9b+"),
+];
+const YDS: &[(&str, &str)] = &[
+    ("5.4", "3a"), ("5.5", "4a"), ("5.6", "4b"), ("5.7", "4c"), ("5.8", "5a"), ("5.9", "5b"),
+    ("5.10a", "6a"), ("5.10b", "6a+"), ("5.10c", "6b"), ("5.10d", "6b+"),
+    ("5.11a", "6b+"), ("5.11b", "6c"), ("5.11c", "6c+"), ("5.11d", "7a"),
+    ("5.12a", "7a+"), ("5.12b", "7b"), ("5.12c", "7b+"), ("5.12d", "7c"),
+    ("5.13a", "7c+"), ("5.13b", "8a"), ("5.13c", "8a+"), ("5.13d", "8b"),
+    ("5.14a", "8b+"), ("5.14b", "8c"), ("5.14c", "8c+"), ("5.14d", "9a"),
+    ("5.15a", "9a+"), ("5.15b", "9b"), ("5.15c", "9b+"), ("5.15d", "9c"),
+];
+const UIAA: &[(&str, &str)] = &[
+    ("III", "3a"), ("III+", "3b"), ("IV-", "3c"), ("IV", "4a"), ("IV+", "4b"), ("V-", "4c"),
+    ("V", "5a"), ("V+", "5b"), ("VI-", "5c"), ("VI", "6a"), ("VI+", "6a+"), ("VII-", "6b"),
+    ("VII", "6b+"), ("VII+", "6c"), ("VIII-", "7a"), ("VIII", "7a+"), ("VIII+", "7b+"), ("IX-", "7c+"),
+    ("IX", "8a"), ("IX+", "8a+"), ("X-", "8b+"), ("X", "8c"), ("X+", "8c+"), ("XI-", "9a+"),
+    ("XI", "9b"), ("XI+", "9b+"), ("XII-", "9c"),
+];
+const BRITISH: &[(&str, &str)] = &[
+    ("M", "3a"), ("D", "3b"), ("VD", "4a"), ("S", "4b"), ("HS", "4c"), ("VS", "5a"), ("HVS", "5b"),
+    ("E1", "6a"), ("E2", "6a+"), ("E3", "6b+"), ("E4", "7a"), ("E5", "7a+"), ("E6", "7b+"),
+    ("E7", "7c+"), ("E8", "8a+"), ("E9", "8b+"), ("E10", "8c+"), ("E11", "9a+"),
+];
+const ROMAN: [&str; 12] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X", "XI", "XII"];
+
+impl Grade
+{
+    /// Reads the grade, guessing its system from its notation.
+    ///
+    /// Notations shared by several systems are read as:
+    /// - a bare number (`5`) as a French grade
+    /// - a number with a sign (`5+`) as an UIAA grade
+    /// - a lowercase letter (`6a`) as a French grade, an uppercase one (`6A`) as a Fontainebleau grade
+    pub fn parse(text: &str) -> Grade
+    {
+        Grade { text: text.to_owned(), rating: read(text.trim()) }
+    }
+
+    /// The closest grade to the difficulty in the given system.
+    pub fn rated(system: GradeSystem, difficulty: Difficulty) -> Grade
+    {
+        Grade { text: label(system, difficulty), rating: Some(Rating { system, difficulty }) }
+    }
+
+    pub fn difficulty(&self) -> Option<Difficulty>
+    {
+        self.rating.map(|rating| rating.difficulty)
+    }
+
+    pub fn system(&self) -> Option<GradeSystem>
+    {
+        self.rating.map(|rating| rating.system)
+    }
+
+    /// The closest grade in the given system, the grade as given when it could not be read.
+    pub fn render(&self, system: GradeSystem) -> String
+    {
+        match self.rating
+        {
+            Some(rating) if rating.system == system => self.text.clone(),
+            Some(rating) => label(system, rating.difficulty),
+            None => self.text.clone(),
+        }
+    }
+
+    /// Orders grades by difficulty, grades that could not be read come last.
+    pub fn compare(&self, other: &Grade) -> Ordering
+    {
+        match (self.difficulty(), other.difficulty())
+        {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None)    => Ordering::Less,
+            (None, Some(_))    => Ordering::Greater,
+            (None, None)       => Ordering::Equal,
+        }
+        .then_with(|| self.text.cmp(&other.text))
+    }
+}
+
+fn read(text: &str) -> Option<Rating>
+{
+    let rating = |system, difficulty| Some(Rating { system, difficulty });
+
+    if let Some(difficulty) = french(text)
+    {
+        return rating(GradeSystem::French, difficulty);
+    }
+    if text.starts_with("5.")
+    {
+        // `5.10` stands for `5.10a`
+        let yds = lookup(YDS, text).or_else(|| lookup(YDS, &format!("{text}a")))?;
+        return rating(GradeSystem::Yds, yds);
+    }
+    let upper = text.to_uppercase();
+    if let Some(difficulty) = lookup(V_SCALE, &upper)
+    {
+        return rating(GradeSystem::VScale, difficulty);
+    }
+    if let Some(difficulty) = lookup(UIAA, &upper).or_else(|| lookup(UIAA, &arabic_to_roman(text)?))
+    {
+        return rating(GradeSystem::Uiaa, difficulty);
+    }
+    // the technical grade of a British grade is ignored
+    if let Some(difficulty) = upper.split_whitespace().next().and_then(|adjectival| lookup(BRITISH, adjectival))
+    {
+        return rating(GradeSystem::British, difficulty);
+    }
+    if text.chars().any(|c| c.is_ascii_uppercase())
+    {
+        return rating(GradeSystem::Fontainebleau, lookup(FONTAINEBLEAU, text)?);
+    }
+    None
+}
+
+/// Ex: `6a+`, `5c`, `4`, there is no `+` below the 6th degree
+fn french(text: &str) -> Option<Difficulty>
+{
+    let mut chars = text.chars();
+    let number = chars.next()?.to_digit(10).filter(|number| (1..=9).contains(number))? as u8;
+    let (letter, plus) = match (chars.next(), chars.next(), chars.next())
+    {
+        (None, None, None)                        => (0, 0),
+        (Some(letter @ 'a'..='c'), None, None)      => (letter as u8 - b'a', 0),
+        (Some(letter @ 'a'..='c'), Some('+'), None) if number >= 6 => (letter as u8 - b'a', 1),
+        _ => return None,
+    };
+    Some(Difficulty(number * 6 + letter * 2 + plus))
+}
+
+/// Ex: `7+` -> `VII+`
+fn arabic_to_roman(text: &str) -> Option<String>
+{
+    let sign_at = text.find(['+', '-']).unwrap_or(text.len());
+    let number: usize = text[..sign_at].parse().ok()?;
+    let roman = ROMAN.get(number.checked_sub(1)?)?;
+    Some(format!("{roman}{}", &text[sign_at..]))
+}
+
+fn lookup(table: &[(&str, &str)], text: &str) -> Option<Difficulty>
+{
+    table.iter()
+        .find(|(grade, _)| *grade == text)
+        .and_then(|(_, french_grade)| french(french_grade))
+}
+
+fn label(system: GradeSystem, difficulty: Difficulty) -> String
+{
+    let table = match system
+    {
+        GradeSystem::French        => return french_label(difficulty),
+        GradeSystem::Fontainebleau => FONTAINEBLEAU,
+        GradeSystem::VScale        => V_SCALE,
+        GradeSystem::Yds           => YDS,
+        GradeSystem::Uiaa          => UIAA,
+        GradeSystem::British       => BRITISH,
+    };
+    // the closest grade, the easiest one on ties
+    let distance = |grade: &&(&str, &str)| french(grade.1).map_or(u8::MAX, |other| other.0.abs_diff(difficulty.0));
+    table.iter().min_by_key(distance).map(|(grade, _)| grade.to_string()).unwrap_or_default()
+}
+
+fn french_label(Difficulty(difficulty): Difficulty) -> String
+{
+    let number = difficulty / 6;
+    let letter = (b'a' + difficulty % 6 / 2) as char;
+    // there is no `+` below the 6th degree
+    let plus = if number >= 6 && difficulty % 2 == 1 { "+" } else { "" };
+    format!("{number}{letter}{plus}")
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn system(text: &str) -> Option<GradeSystem>
+    {
+        Grade::parse(text).system()
+    }
+
+    #[test]
+    fn test_parse()
+    {
+        assert_eq!(system("6a+"), Some(GradeSystem::French));
+        assert_eq!(system("4"), Some(GradeSystem::French));
+        assert_eq!(system("7A"), Some(GradeSystem::Fontainebleau));
+        assert_eq!(system("v5"), Some(GradeSystem::VScale));
+        assert_eq!(system("5.11c"), Some(GradeSystem::Yds));
+        assert_eq!(system("5.10"), Some(GradeSystem::Yds));
+        assert_eq!(system("VII+"), Some(GradeSystem::Uiaa));
+        assert_eq!(system("7+"), Some(GradeSystem::Uiaa));
+        assert_eq!(system("V"), Some(GradeSystem::Uiaa));
+        assert_eq!(system("E1 5b"), Some(GradeSystem::British));
+        assert_eq!(system("HVS"), Some(GradeSystem::British));
+        assert_eq!(system("yellow"), None);
+        assert_eq!(system("6d"), None);
+        assert_eq!(system("5c+"), None);
+        assert_eq!(system("5.16a"), None);
+    }
+
+    #[test]
+    fn test_compare()
+    {
+        let grade = Grade::parse;
+        assert_eq!(grade("6a").compare(&grade("6a+")), Ordering::Less);
+        assert_eq!(grade("6c+").compare(&grade("6c")), Ordering::Greater);
+        // the same difficulty in other systems
+        assert_eq!(grade("5.12a").difficulty(), grade("7a+").difficulty());
+        assert_eq!(grade("VIII-").difficulty(), grade("7a").difficulty());
+        assert_eq!(grade("V6").difficulty(), grade("7A").difficulty());
+        assert_eq!(grade("E4 6a").compare(&grade("6b")), Ordering::Greater);
+        // grades that can't be read come last
+        assert_eq!(grade("yellow").compare(&grade("9c")), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_render()
+    {
+        assert_eq!(Grade::parse("5.12a").render(GradeSystem::French), "7a+");
+        assert_eq!(Grade::parse("7a+").render(GradeSystem::Yds), "5.12a");
+        assert_eq!(Grade::parse("6b+").render(GradeSystem::Yds), "5.10d");
+        assert_eq!(Grade::parse("7A").render(GradeSystem::VScale), "V6");
+        assert_eq!(Grade::parse("V").render(GradeSystem::French), "5a");
+        assert_eq!(Grade::parse("7+").render(GradeSystem::Uiaa), "7+");
+        assert_eq!(Grade::parse("6c+").render(GradeSystem::British), "E4");
+        assert_eq!(Grade::parse("yellow").render(GradeSystem::French), "yellow");
+    }
+
+    #[test]
+    fn test_rated()
+    {
+        let difficulty = Grade::parse("7a+").difficulty().unwrap();
+        assert_eq!(Grade::rated(GradeSystem::Yds, difficulty).text, "5.12a");
+        assert_eq!(Grade::rated(GradeSystem::French, difficulty), Grade::parse("7a+"));
+        assert_eq!(Grade::rated(GradeSystem::VScale, difficulty).difficulty(), Some(difficulty));
+    }
+}
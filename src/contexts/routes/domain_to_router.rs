@@ -1,21 +1,34 @@
 use super::{domain, router};
 
-pub fn route(r: domain::Route) -> router::Route
+/// The grades are rendered in the given system if any, as given by the source otherwise.
+pub fn route(r: domain::Route, grade_system: Option<domain::GradeSystem>) -> router::Route
 {
+    let render = |grade: &domain::Grade| grade_system.map_or_else(|| grade.text.clone(), |system| grade.render(system));
     router::Route
     {
-        id           : route_id(r.id),
-        place_id     : r.data.place_id,
-        name         : r.data.name,
-        description  : r.data.description,
-        grade        : r.data.grade,
-        color        : r.data.color,
-        sector       : r.data.sector,
-        rules        : rules(r.data.rules),
-        opening_date : date(r.data.opening_date),
-        closing_date : r.data.closing_date.map(date),
-        tags         : r.data.tags,
-        properties   : r.data.properties,
+        id              : route_id(r.id),
+        place_id        : r.data.place_id,
+        name            : r.data.name,
+        description     : r.data.description,
+        grade           : render(&r.data.grade),
+        community_grade : r.community_grade.as_ref().map(render),
+        rating          : r.rating.map(score),
+        color           : r.data.color,
+        sector          : r.data.sector,
+        rules           : rules(r.data.rules),
+        opening_date    : date(r.data.opening_date),
+        closing_date    : r.data.closing_date.map(date),
+        tags            : r.data.tags,
+        properties      : r.data.properties,
+    }
+}
+
+fn score(s: domain::Score) -> router::Score
+{
+    router::Score
+    {
+        average : s.average,
+        count   : s.count,
     }
 }
 
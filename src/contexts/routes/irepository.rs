@@ -2,12 +2,14 @@ pub mod get
 {
     use async_trait::async_trait;
     use crate::errors::{GetAllError, GetError};
-    use super::super::domain::{get::Filters, Route, RouteId};
+    use crate::contexts::sources::domain::{Aggregate, Window};
+    use super::super::domain::{get::{Filters, Sort}, Route, RouteId};
 
     #[async_trait]
     pub trait IRepository : Send + Sync
     {
-        async fn get_all(&self, filters: Filters) -> Result<Vec<Route>, GetAllError>;
+        /// The items of the window must be among the returned ones, sources may return more.
+        async fn get_all(&self, filters: Filters, sort: Sort, window: Window) -> Result<Aggregate<Route>, GetAllError>;
         async fn get(&self, id: RouteId) -> Result<Route, GetError>;
     }
 }
@@ -34,6 +36,7 @@ pub mod put
     #[async_trait]
     pub trait IRepository : Send + Sync
     {
+        /// The place of `data` is one of the source of the route.
         async fn update(&self, id: RouteId, data: RouteData) -> Result<(), UpdateError>;
     }
 }
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use rocket::FromForm;
+use rocket::{FromForm, FromFormField};
 use rocket_okapi::okapi::schemars::{self, JsonSchema};
 use serde::{Deserialize, Serialize};
 
@@ -8,45 +8,74 @@ use crate::typeutil::routers::Date;
 
 pub mod get
 {
-    use rocket::{get, serde::json::Json, State, FromForm, response::status::Custom, http::Status};
+    use rocket::{get, serde::json::Json, State, FromForm, FromFormField, http::Status};
     use rocket_okapi::{
         openapi,
         okapi::schemars::{self, JsonSchema},
     };
 
     use super::super::{use_cases::get::UseCase, domain_to_router, router_to_domain};
-    use super::{Route, RouteId};
+    use super::{Route, GradeSystem};
+    use crate::typeutil::routers::Id;
 
     use std::collections::HashMap;
 
     use crate::errors::{GetAllError, GetError};
+    use crate::problems::Problem;
+    use crate::contexts::sources::{router::Listing, router_to_domain::window};
 
     /// # Get the route that has the given id
     ///
-    /// Returns the route that has the given id.
+    /// Returns the route that has the given id, its grade is given in `grade_system` if any.
     #[openapi(tag = "Route")]
-    #[get("/routes/<id>")]
-    pub async fn get_route(id: RouteId, use_case: &State<UseCase>) -> Result<Json<Route>, Custom<String>>
+    #[get("/routes/<id>?<grade_system>")]
+    pub async fn get_route(id: Id, grade_system: Option<GradeSystem>, use_case: &State<UseCase>) -> Result<Json<Route>, Problem>
     {
-        match use_case.get(router_to_domain::route_id(id)).await
+        let grade_system = grade_system.map(router_to_domain::grade_system);
+        match use_case.get(router_to_domain::route_id(id)?).await
         {
-            Ok(route) => Ok(Json(domain_to_router::route(route))),
-            Err(GetError::NonExistingId(id)) => Err(Custom(Status::NotFound, format!("Route with id `{id}` was not found."))),
-            Err(GetError::InternalServerError) => Err(Custom(Status::InternalServerError, String::from("Internal Server Error"))),
+            Ok(route) => Ok(Json(domain_to_router::route(route, grade_system))),
+            Err(GetError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("Route with id `{id}` was not found.")).id(id)),
+            Err(GetError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(GetError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the route with id `{id}`.")).id(id)),
+            Err(GetError::BadUpstreamId(id)) => Err(Problem::new(Status::BadGateway, format!("The source answered with the invalid id `{id}`.")).id(id)),
+            Err(GetError::Source(failure)) => Err(Problem::from(failure)),
+            Err(GetError::InternalServerError) => Err(Problem::internal()),
         }
     }
 
     /// # Get routes that match the given filters
     ///
-    /// Returns all routes that match the given filters.
+    /// Returns a page of the routes that match the given filters, sorted by `sort` (by id by default, best rated first by `rating`).
+    /// Routes are kept within `min_grade` and `max_grade` whatever their grading system,
+    /// and their grades are given in `grade_system` if any.
+    /// Routes rated at least `min_rating` stars on average are kept, unrated ones are left out.
+    /// Pages hold `limit` routes (100 by default, 1000 at most) starting at `offset`,
+    /// or where the previous page ended when given its `cursor`.
+    /// The following page is linked in the `Link` header.
+    /// The status of each source is given in the `X-Source-Status` headers,
+    /// with `strict=true` the request fails with `502 Bad Gateway` if any source failed.
+    #[allow(clippy::too_many_arguments)]
     #[openapi(tag = "Route")]
-    #[get("/routes?<filters..>")]
-    pub async fn get_all_routes(filters: Filters, use_case: &State<UseCase>) -> Result<Json<Vec<Route>>, Custom<String>>
+    #[get("/routes?<strict>&<sort>&<limit>&<offset>&<cursor>&<grade_system>&<filters..>")]
+    pub async fn get_all_routes(
+        strict       : Option<bool>,
+        sort         : Option<Sort>,
+        limit        : Option<usize>,
+        offset       : Option<usize>,
+        cursor       : Option<String>,
+        grade_system : Option<GradeSystem>,
+        filters      : Filters,
+        use_case     : &State<UseCase>,
+    ) -> Result<Listing<Route>, Problem>
     {
-        match use_case.get_all(router_to_domain::get::filters(filters)).await
+        let sort_name = sort.map_or("id", Sort::as_str);
+        let window = window(limit, offset, cursor, sort_name)?;
+        let grade_system = grade_system.map(router_to_domain::grade_system);
+        match use_case.get_all(router_to_domain::get::filters(filters)?, router_to_domain::get::sort(sort), window).await
         {
-            Ok(routes) => Ok(Json(routes.into_iter().map(domain_to_router::route).collect())),
-            Err(GetAllError::InternalServerError) => Err(Custom(Status::InternalServerError, String::from("Internal Server Error"))),
+            Ok(routes) => Listing::from_page(routes, |route| domain_to_router::route(route, grade_system), sort_name).strict(strict.unwrap_or(false)),
+            Err(GetAllError::InternalServerError) => Err(Problem::internal()),
         } 
     }
 
@@ -55,20 +84,48 @@ pub mod get
     {
         pub min_grade  : Option<String>,
         pub max_grade  : Option<String>,
+        pub min_rating : Option<f32>,
         pub tags       : Vec<String>,
         pub properties : HashMap<String, String>,
     }
 
+    #[derive(FromFormField, JsonSchema, Debug, Clone, Copy)]
+    #[serde(rename_all = "snake_case")]
+    #[schemars(rename = "RouteSort")]
+    pub enum Sort
+    {
+        Name,
+        Grade,
+        #[field(value = "opening_date")]
+        OpeningDate,
+        Rating,
+    }
+
+    impl Sort
+    {
+        pub fn as_str(self) -> &'static str
+        {
+            match self
+            {
+                Sort::Name        => "name",
+                Sort::Grade       => "grade",
+                Sort::OpeningDate => "opening_date",
+                Sort::Rating      => "rating",
+            }
+        }
+    }
+
 }
 
 pub mod post
 {
     use rocket::http::Status;
-    use rocket::response::status::Custom;
     use rocket::{post, serde::json::Json, State};
     use rocket_okapi::openapi;
 
     use crate::errors::CreateError;
+    use crate::problems::Problem;
+    use crate::contexts::auth::router::AuthenticatedUser;
 
     use super::super::{use_cases::post::UseCase, domain_to_router, router_to_domain};
 
@@ -79,12 +136,20 @@ pub mod post
     /// Returns the newly created route with an associated id
     #[openapi(tag = "Route")]
     #[post("/routes", data = "<route_data>")]
-    pub async fn create_route(route_data: Json<RouteData>, use_case: &State<UseCase>) -> Result<Json<Route>, Custom<String>>
+    pub async fn create_route(route_data: Json<RouteData>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<Json<Route>, Problem>
     {
-        match use_case.create(router_to_domain::route_data(route_data.into_inner())).await
+        match use_case.create(&user.access, router_to_domain::route_data(route_data.into_inner())).await
         {
-            Ok(route) => Ok(Json(domain_to_router::route(route))),
-            Err(CreateError::InternalServerError) => Err(Custom(Status::InternalServerError, String::from("Internal Server Error"))),
+            Ok(route) => Ok(Json(domain_to_router::route(route, None))),
+            Err(CreateError::AlreadyExists(id)) => Err(Problem::new(Status::Conflict, format!("A route with id `{id}` already exists.")).id(id)),
+            Err(CreateError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid place id.")).id(id)),
+            Err(CreateError::Forbidden(source)) => Err(Problem::new(Status::Forbidden, format!("You may not change the source `{source}`."))),
+            Err(CreateError::NonExistingReference(id)) => Err(Problem::new(Status::NotFound, format!("No existing place with id `{id}`.")).id(id)),
+            Err(CreateError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the place with id `{id}`.")).id(id)),
+            Err(CreateError::BadUpstreamId(id)) => Err(Problem::new(Status::BadGateway, format!("The source answered with the invalid id `{id}`.")).id(id)),
+            Err(CreateError::Invalid(fields)) => Err(Problem::invalid(fields)),
+            Err(CreateError::Source(failure)) => Err(Problem::from(failure)),
+            Err(CreateError::InternalServerError) => Err(Problem::internal()),
         }
     }
 }
@@ -92,27 +157,37 @@ pub mod post
 pub mod put
 {
     use rocket::http::Status;
-    use rocket::response::status::{self, Custom};
+    use rocket::response::status;
     use rocket::{put, serde::json::Json, State};
     use rocket_okapi::openapi;
 
     use crate::errors::UpdateError;
+    use crate::problems::Problem;
+    use crate::contexts::auth::router::AuthenticatedUser;
 
     use super::super::{use_cases::put::UseCase, router_to_domain};
 
-    use super::{RouteData, RouteId};
+    use super::RouteData;
+    use crate::typeutil::routers::Id;
 
     /// # Update an existing route
     #[openapi(tag = "Route")]
     #[put("/routes/<id>", data = "<route_data>")]
-    pub async fn update_route(id: RouteId, route_data: Json<RouteData>, use_case: &State<UseCase>) -> Result<status::NoContent, Custom<String>>
+    pub async fn update_route(id: Id, route_data: Json<RouteData>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
     {
         
-        match use_case.update(router_to_domain::route_id(id), router_to_domain::route_data(route_data.into_inner())).await
+        match use_case.update(&user.access, router_to_domain::route_id(id)?, router_to_domain::route_data(route_data.into_inner())).await
         {
             Ok(()) => Ok(status::NoContent),
-            Err(UpdateError::NonExistingId(id)) => Err(Custom(Status::NotFound, format!("No existing route with id `{id}`."))),
-            Err(UpdateError::InternalServerError) => Err(Custom(Status::InternalServerError, String::from("Internal Server Error"))),
+            Err(UpdateError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No existing route with id `{id}`.")).id(id)),
+            Err(UpdateError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(UpdateError::Forbidden(source)) => Err(Problem::new(Status::Forbidden, format!("You may not change the source `{source}`."))),
+            Err(UpdateError::NotOwner(id)) => Err(Problem::new(Status::Forbidden, format!("`{id}` belongs to another user.")).id(id)),
+            Err(UpdateError::NonExistingReference(id)) => Err(Problem::new(Status::NotFound, format!("No existing place with id `{id}`.")).id(id)),
+            Err(UpdateError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the route with id `{id}`.")).id(id)),
+            Err(UpdateError::Invalid(fields)) => Err(Problem::invalid(fields)),
+            Err(UpdateError::Source(failure)) => Err(Problem::from(failure)),
+            Err(UpdateError::InternalServerError) => Err(Problem::internal()),
         }
     }
 }
@@ -120,27 +195,34 @@ pub mod put
 pub mod delete
 {
     use rocket::http::Status;
-    use rocket::response::status::{self, Custom};
+    use rocket::response::status;
     use rocket::{delete, State};
     use rocket_okapi::openapi;
 
     use crate::errors::DeleteError;
+    use crate::problems::Problem;
+    use crate::contexts::auth::router::AuthenticatedUser;
 
     use super::super::{use_cases::delete::UseCase, router_to_domain};
 
-    use super::RouteId;
+    use crate::typeutil::routers::Id;
 
     /// # Delete an existing route
     #[openapi(tag = "Route")]
     #[delete("/routes/<id>")]
-    pub async fn delete_route(id: RouteId, use_case: &State<UseCase>) -> Result<status::NoContent, Custom<String>>
+    pub async fn delete_route(id: Id, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
     {
         
-        match use_case.delete(router_to_domain::route_id(id)).await
+        match use_case.delete(&user.access, router_to_domain::route_id(id)?).await
         {
             Ok(()) => Ok(status::NoContent),
-            Err(DeleteError::NonExistingId(id)) => Err(Custom(Status::NotFound, format!("No existing route with id `{id}`."))),
-            Err(DeleteError::InternalServerError) => Err(Custom(Status::InternalServerError, String::from("Internal Server Error"))),
+            Err(DeleteError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No existing route with id `{id}`.")).id(id)),
+            Err(DeleteError::MalformedId(id)) => Err(Problem::new(Status::BadRequest, format!("`{id}` is not a valid id.")).id(id)),
+            Err(DeleteError::Forbidden(source)) => Err(Problem::new(Status::Forbidden, format!("You may not change the source `{source}`."))),
+            Err(DeleteError::NotOwner(id)) => Err(Problem::new(Status::Forbidden, format!("`{id}` belongs to another user.")).id(id)),
+            Err(DeleteError::UnknownSource(id)) => Err(Problem::new(Status::NotFound, format!("No source owns the route with id `{id}`.")).id(id)),
+            Err(DeleteError::Source(failure)) => Err(Problem::from(failure)),
+            Err(DeleteError::InternalServerError) => Err(Problem::internal()),
         }
     }
 }
@@ -150,22 +232,48 @@ pub mod delete
 #[serde(rename_all = "camelCase")]
 pub struct Route
 {
-    pub id           : RouteId,
-    pub place_id     : PlaceId,
-    pub name         : String,
-    pub description  : String,
-    pub grade        : String,
-    pub color        : String,
-    pub sector       : String,
-    pub opening_date : Date,
-    pub closing_date : Option<Date>,
-    pub rules        : Rules,
-    pub tags         : Vec<String>,
-    pub properties   : HashMap<String, String>,
+    pub id              : RouteId,
+    pub place_id        : PlaceId,
+    pub name            : String,
+    pub description     : String,
+    pub grade           : String,
+    /// The median of the grades users suggested, absent until a user suggests one.
+    pub community_grade : Option<String>,
+    /// Absent until a user rates the route.
+    pub rating          : Option<Score>,
+    pub color           : String,
+    pub sector          : String,
+    pub opening_date    : Date,
+    pub closing_date    : Option<Date>,
+    pub rules           : Rules,
+    pub tags            : Vec<String>,
+    pub properties      : HashMap<String, String>,
 }
 pub type RouteId = String;
 pub type PlaceId = String;
 
+/// The average of the stars users gave a route and how many users rated it.
+#[derive(FromForm, Serialize, Deserialize, JsonSchema, Debug)]
+#[schemars(rename = "RouteScore")]
+pub struct Score
+{
+    pub average : f32,
+    pub count   : usize,
+}
+
+#[derive(FromFormField, JsonSchema, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum GradeSystem
+{
+    French,
+    Fontainebleau,
+    #[field(value = "v_scale")]
+    VScale,
+    Yds,
+    Uiaa,
+    British,
+}
+
 #[derive(FromForm, Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct RouteData
@@ -1,13 +1,14 @@
 use super::{router, domain};
+use crate::typeutil::{routers::Id, ids::ParseIdError};
 
-pub fn route_id(id: router::RouteId) -> domain::RouteId { id }
+pub fn route_id(id: Id) -> Result<domain::RouteId, ParseIdError> { id.0.map(|id| id.to_string()) }
 pub fn route_data(rd: router::RouteData) -> domain::RouteData
 {
     domain::RouteData {
         place_id    : rd.place_id,
         name        : rd.name,
         description : rd.description,
-        grade       : rd.grade,
+        grade       : domain::Grade::parse(&rd.grade),
         color       : rd.color,
         sector      : rd.sector,
         rules       : rules(rd.rules),
@@ -20,20 +21,64 @@ pub fn route_data(rd: router::RouteData) -> domain::RouteData
 
 fn date(d: crate::typeutil::routers::Date) -> domain::Date { *d }
 
+pub fn grade_system(s: router::GradeSystem) -> domain::GradeSystem
+{
+    match s
+    {
+        router::GradeSystem::French        => domain::GradeSystem::French,
+        router::GradeSystem::Fontainebleau => domain::GradeSystem::Fontainebleau,
+        router::GradeSystem::VScale        => domain::GradeSystem::VScale,
+        router::GradeSystem::Yds           => domain::GradeSystem::Yds,
+        router::GradeSystem::Uiaa          => domain::GradeSystem::Uiaa,
+        router::GradeSystem::British       => domain::GradeSystem::British,
+    }
+}
+
 pub mod get
 {
+    use rocket::http::Status;
+    use crate::problems::Problem;
+
     use super::{
         router::get as router, 
         domain::get as domain,
+        domain::Grade,
     };
-    pub fn filters(f: router::Filters) -> domain::Filters
+
+    /// Grade bounds must be readable since the core compares them itself, `min_rating` goes from 1 to 5.
+    pub fn filters(f: router::Filters) -> Result<domain::Filters, Problem>
     {
-        domain::Filters 
+        let grade = |grade: Option<String>| match grade.map(|grade| Grade::parse(&grade))
         {
-            min_grade  : f.min_grade,
-            max_grade  : f.max_grade,
+            Some(grade) if grade.rating.is_none() => Err(Problem::new(Status::BadRequest, format!("`{}` is not a known grade.", grade.text))),
+            grade => Ok(grade),
+        };
+
+        let min_rating = match f.min_rating
+        {
+            Some(min_rating) if !(1.0..=5.0).contains(&min_rating) => return Err(Problem::new(Status::BadRequest, format!("`{min_rating}` is not a rating from 1 to 5."))),
+            min_rating => min_rating,
+        };
+
+        Ok(domain::Filters 
+        {
+            min_grade  : grade(f.min_grade)?,
+            max_grade  : grade(f.max_grade)?,
+            min_rating,
             tags       : f.tags,
             properties : f.properties,
+        })
+    }
+
+    pub fn sort(s: Option<router::Sort>) -> domain::Sort
+    {
+        match s
+        {
+            None                            => domain::Sort::Id,
+            Some(router::Sort::Name)        => domain::Sort::Name,
+            Some(router::Sort::Grade)       => domain::Sort::Grade,
+            Some(router::Sort::OpeningDate) => domain::Sort::OpeningDate,
+            Some(router::Sort::Rating)      => domain::Sort::Rating,
         }
     }
 }
@@ -1,54 +1,120 @@
 pub mod get
 {
+    use std::sync::Arc;
+
     use crate::errors::{GetAllError, GetError};
+    use crate::contexts::sources::domain::{Page, Window};
+    use crate::contexts::grades::{domain::{consensus, Vote}, irepository::get::IRepository as GradeRepository};
+    use crate::contexts::ratings::{domain::score, irepository::get::IRepository as RatingRepository};
 
-    use super::super::{domain::{Route, RouteId, get::Filters}, irepository::get::IRepository};
+    use super::super::{domain::{GradeSystem, Route, RouteId, get::{Filters, Sort}}, irepository::get::IRepository};
     pub struct UseCase
     {
-        repository: Box<dyn IRepository>,
+        repository : Arc<dyn IRepository>,
+        grades     : Arc<dyn GradeRepository>,
+        ratings    : Arc<dyn RatingRepository>,
     }
-    unsafe impl Send for UseCase {}
-    unsafe impl Sync for UseCase {}
     impl UseCase
     {
-        pub fn new(repo: Box<dyn IRepository>) -> Self
+        pub fn new(repo: Arc<dyn IRepository>, grades: Arc<dyn GradeRepository>, ratings: Arc<dyn RatingRepository>) -> Self
         {
-            Self { repository: repo }
+            Self { repository: repo, grades, ratings }
         }
         
-        pub async fn get_all(&self, filters: Filters) -> Result<Vec<Route>, GetAllError>
+        /// Filters are forwarded to the sources, then grade bounds and `min_rating` are enforced on the aggregated routes
+        /// since sources may ignore them, use other grading systems or know nothing about ratings.
+        /// The routes are then sorted and cut to the window.
+        pub async fn get_all(&self, filters: Filters, sort: Sort, window: Window) -> Result<Page<Route>, GetAllError>
         {
-            self.repository.get_all(filters).await
+            let mut routes = self.repository.get_all(filters.clone(), sort, window).await?;
+            // every score is needed to filter or sort by rating, otherwise only the ones of the page
+            let by_rating = filters.min_rating.is_some() || sort == Sort::Rating;
+            if by_rating
+            {
+                let mut scores = self.ratings.scores().await?;
+                for route in &mut routes.items
+                {
+                    route.rating = scores.remove(&route.id);
+                }
+            }
+            routes.items.retain(|route| filters.matches(route));
+            let mut routes = routes.page(window, |a, b| sort.compare(a, b));
+            if !by_rating
+            {
+                let mut scores = self.ratings.scores_by_route(routes.items.iter().map(|route| route.id.clone()).collect()).await?;
+                for route in &mut routes.items
+                {
+                    route.rating = scores.remove(&route.id);
+                }
+            }
+            // the votes of the whole page are read at once
+            let mut votes = self.grades.votes_by_route(routes.items.iter().map(|route| route.id.clone()).collect()).await?;
+            for route in &mut routes.items
+            {
+                grade(route, &votes.remove(&route.id).unwrap_or_default());
+            }
+            Ok(routes)
         }    
 
         pub async fn get(&self, id: RouteId) -> Result<Route, GetError>
         {
-            self.repository.get(id).await
+            let mut route = self.repository.get(id).await?;
+            let rating = match self.ratings.get_all(route.id.clone()).await
+            {
+                Ok(ratings) => score(&ratings),
+                Err(GetAllError::InternalServerError) => return Err(GetError::InternalServerError),
+            };
+            route.rating = rating;
+            let votes = match self.grades.get_all(route.id.clone()).await
+            {
+                Ok(votes) => votes,
+                Err(GetAllError::InternalServerError) => return Err(GetError::InternalServerError),
+            };
+            grade(&mut route, &votes);
+            Ok(route)
         }    
-        
+    }
+
+    /// The community grade is given in the grading system of the route, in French when its grade can't be read.
+    fn grade(route: &mut Route, votes: &[Vote])
+    {
+        route.community_grade = consensus(votes, route.data.grade.system().unwrap_or(GradeSystem::French)).grade;
     }
 }
 
 pub mod post
 {
+    use std::sync::Arc;
+
     use crate::errors::CreateError;
+    use crate::contexts::auth::domain::Access;
+    use crate::typeutil::ids::RelativeId;
 
     use super::super::{domain::{Route, RouteData}, irepository::post::IRepository};
     pub struct UseCase
     {
-        repository: Box<dyn IRepository>,
+        repository: Arc<dyn IRepository>,
     }
-    unsafe impl Send for UseCase {}
-    unsafe impl Sync for UseCase {}
     impl UseCase
     {
-        pub fn new(repo: Box<dyn IRepository>) -> Self
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
         {
             Self { repository: repo }
         }
 
-        pub async fn create(&self, data: RouteData) -> Result<Route, CreateError>
+        /// The route is created in the source of its place.
+        /// Access is checked before the data, but the source can't be told from a malformed `place_id`, which makes it invalid.
+        pub async fn create(&self, access: &Access, data: RouteData) -> Result<Route, CreateError>
         {
+            let Ok(RelativeId { source_id, .. }) = data.place_id.parse() else
+            {
+                return Err(CreateError::Invalid(data.validate().err().unwrap_or_default()));
+            };
+            if !access.can_manage_routes(source_id)
+            {
+                return Err(CreateError::Forbidden(source_id));
+            }
+            data.validate().map_err(CreateError::Invalid)?;
             self.repository.create(data).await
         }     
     }
@@ -56,24 +122,46 @@ pub mod post
 
 pub mod put
 {
-    use crate::errors::UpdateError;
+    use std::sync::Arc;
+
+    use crate::errors::{InvalidField, UpdateError};
+    use crate::contexts::auth::domain::Access;
+    use crate::typeutil::ids::RelativeId;
 
     use super::super::{domain::{RouteData, RouteId}, irepository::put::IRepository};
     pub struct UseCase
     {
-        repository: Box<dyn IRepository>,
+        repository: Arc<dyn IRepository>,
     }
-    unsafe impl Send for UseCase {}
-    unsafe impl Sync for UseCase {}
     impl UseCase
     {
-        pub fn new(repo: Box<dyn IRepository>) -> Self
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
         {
             Self { repository: repo }
         }
 
-        pub async fn update(&self, id: RouteId, data: RouteData) -> Result<(), UpdateError>
+        /// Both the source of the route and the one of its (new) place must be managed by the user.
+        /// Routes stay in their source, they can't be moved to a place of another one.
+        /// As on creation, a malformed `place_id` makes the data invalid.
+        pub async fn update(&self, access: &Access, id: RouteId, data: RouteData) -> Result<(), UpdateError>
         {
+            let Ok(RelativeId { source_id, .. }) = id.parse() else { return Err(UpdateError::MalformedId(id)) };
+            let Ok(RelativeId { source_id: place_source_id, .. }) = data.place_id.parse() else
+            {
+                return Err(UpdateError::Invalid(data.validate().err().unwrap_or_default()));
+            };
+            for source_id in [source_id, place_source_id]
+            {
+                if !access.can_manage_routes(source_id)
+                {
+                    return Err(UpdateError::Forbidden(source_id));
+                }
+            }
+            data.validate().map_err(UpdateError::Invalid)?;
+            if place_source_id != source_id
+            {
+                return Err(UpdateError::Invalid(vec![InvalidField::new("place_id", "must be a place of the source of the route")]));
+            }
             self.repository.update(id, data).await
         }     
     }
@@ -81,24 +169,31 @@ pub mod put
 
 pub mod delete
 {
+    use std::sync::Arc;
+
     use crate::errors::DeleteError;
+    use crate::contexts::auth::domain::Access;
+    use crate::typeutil::ids::RelativeId;
 
     use super::super::{domain::RouteId, irepository::delete::IRepository};
     pub struct UseCase
     {
-        repository: Box<dyn IRepository>,
+        repository: Arc<dyn IRepository>,
     }
-    unsafe impl Send for UseCase {}
-    unsafe impl Sync for UseCase {}
     impl UseCase
     {
-        pub fn new(repo: Box<dyn IRepository>) -> Self
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
         {
             Self { repository: repo }
         }
 
-        pub async fn delete(&self, id: RouteId) -> Result<(), DeleteError>
+        pub async fn delete(&self, access: &Access, id: RouteId) -> Result<(), DeleteError>
         {
+            let Ok(RelativeId { source_id, .. }) = id.parse() else { return Err(DeleteError::MalformedId(id)) };
+            if !access.can_manage_routes(source_id)
+            {
+                return Err(DeleteError::Forbidden(source_id));
+            }
             self.repository.delete(id).await
         }     
     }
@@ -0,0 +1,160 @@
+use std::cmp::Ordering;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+pub type SourceId = u16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorKind
+{
+    Unreachable,
+    Timeout,
+    Status(u16),
+    InvalidPayload,
+    /// The source was skipped because it failed too many times recently.
+    CircuitOpen,
+    /// The source was skipped because its last probe failed.
+    Down,
+    Internal,
+}
+
+#[derive(Debug, Clone)]
+pub struct SourceStatus
+{
+    pub id       : SourceId,
+    pub name     : String,
+    pub latency  : Duration,
+    pub error    : Option<ErrorKind>,
+    /// The source failed and its items come from a previous answer.
+    pub stale    : bool,
+    /// Items of the source that break their schema, they were dropped or made the whole answer rejected.
+    pub rejected : Vec<Rejection>,
+}
+
+/// An item of a source that breaks its schema, with the id the source gave it, if any.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejection
+{
+    pub id         : Option<String>,
+    pub violations : Vec<String>,
+}
+
+/// What the probes sent to a source in the background found out, before any user request fails.
+#[derive(Debug, Clone)]
+pub struct Health
+{
+    pub id           : SourceId,
+    pub name         : String,
+    pub state        : State,
+    /// Time the last probe took.
+    pub latency      : Option<Duration>,
+    pub last_check   : Option<DateTime<Utc>>,
+    pub last_success : Option<DateTime<Utc>>,
+    pub last_error   : Option<Failure>,
+}
+
+/// Outcome of the last probe of a source, `Unknown` until the first one completes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum State
+{
+    Unknown,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure
+{
+    pub at    : DateTime<Utc>,
+    pub error : ErrorKind,
+}
+
+/// Items merged from every source along with the status of each source.
+#[derive(Debug)]
+pub struct Aggregate<T>
+{
+    pub items   : Vec<T>,
+    pub sources : Vec<SourceStatus>,
+}
+
+/// Part of a sorted list: `limit` items starting at the `offset`-th one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Window
+{
+    pub offset : usize,
+    pub limit  : usize,
+}
+
+impl Window
+{
+    pub const DEFAULT_LIMIT : usize = 100;
+    pub const MAX_LIMIT     : usize = 1000;
+
+    /// Position right after the last item of the window.
+    pub fn end(&self) -> usize
+    {
+        self.offset + self.limit
+    }
+
+    /// Keeps the (sorted) items in the window, along with the window of the following page if there are items left.
+    pub fn cut<T>(self, items: Vec<T>) -> (Vec<T>, Option<Window>)
+    {
+        let next = (items.len() > self.end()).then_some(Window { offset: self.end(), limit: self.limit });
+        (items.into_iter().skip(self.offset).take(self.limit).collect(), next)
+    }
+}
+
+impl Default for Window
+{
+    fn default() -> Self
+    {
+        Window { offset: 0, limit: Self::DEFAULT_LIMIT }
+    }
+}
+
+/// Window of the items merged from every source along with the status of each source.
+#[derive(Debug)]
+pub struct Page<T>
+{
+    pub items   : Vec<T>,
+    pub sources : Vec<SourceStatus>,
+    /// Window of the following page, if there are items left.
+    pub next    : Option<Window>,
+}
+
+impl<T> Aggregate<T>
+{
+    /// Sorts the items with `compare` then keeps the ones in the window.
+    /// `compare` must be a total order for pages to be stable across calls.
+    pub fn page(mut self, window: Window, compare: impl FnMut(&T, &T) -> Ordering) -> Page<T>
+    {
+        self.items.sort_by(compare);
+        let (items, next) = window.cut(self.items);
+        Page { items, sources: self.sources, next }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_page()
+    {
+        let aggregate = || Aggregate { items: vec![4, 2, 5, 1, 3], sources: Vec::new() };
+
+        let page = aggregate().page(Window { offset: 0, limit: 2 }, Ord::cmp);
+        assert_eq!(page.items, vec![1, 2]);
+        assert_eq!(page.next, Some(Window { offset: 2, limit: 2 }));
+
+        let page = aggregate().page(Window { offset: 4, limit: 2 }, Ord::cmp);
+        assert_eq!(page.items, vec![5]);
+        assert_eq!(page.next, None);
+
+        let page = aggregate().page(Window { offset: 3, limit: 2 }, |a, b| b.cmp(a));
+        assert_eq!(page.items, vec![2, 1]);
+        assert_eq!(page.next, None);
+    }
+}
@@ -0,0 +1,59 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+
+use super::{domain, router};
+use crate::typeutil::routers::DateTime;
+
+pub fn source_status(s: domain::SourceStatus) -> router::SourceStatus
+{
+    router::SourceStatus {
+        id         : s.id,
+        name       : s.name,
+        latency_ms : s.latency.as_millis() as u64,
+        error      : s.error.map(error_kind),
+        stale      : s.stale,
+        rejected   : s.rejected.into_iter().map(rejection).collect(),
+    }
+}
+
+fn rejection(r: domain::Rejection) -> router::Rejection
+{
+    router::Rejection { id: r.id, violations: r.violations }
+}
+
+pub fn health(h: domain::Health) -> router::SourceHealth
+{
+    router::SourceHealth {
+        id           : h.id,
+        name         : h.name,
+        status       : match h.state
+        {
+            domain::State::Unknown => router::HealthStatus::Unknown,
+            domain::State::Up      => router::HealthStatus::Up,
+            domain::State::Down    => router::HealthStatus::Down,
+        },
+        latency_ms   : h.latency.map(|latency| latency.as_millis() as u64),
+        last_check   : h.last_check.map(DateTime),
+        last_success : h.last_success.map(DateTime),
+        last_error   : h.last_error.map(|failure| router::SourceFailure { at: DateTime(failure.at), error: error_kind(failure.error) }),
+    }
+}
+
+fn error_kind(e: domain::ErrorKind) -> String
+{
+    match e
+    {
+        domain::ErrorKind::Unreachable    => String::from("unreachable"),
+        domain::ErrorKind::Timeout        => String::from("timeout"),
+        domain::ErrorKind::Status(status) => format!("status_{status}"),
+        domain::ErrorKind::InvalidPayload => String::from("invalid_payload"),
+        domain::ErrorKind::CircuitOpen    => String::from("circuit_open"),
+        domain::ErrorKind::Down           => String::from("down"),
+        domain::ErrorKind::Internal       => String::from("internal"),
+    }
+}
+
+/// Opaque cursor of the window, valid for the given sort only.
+pub fn cursor(window: domain::Window, sort: &str) -> String
+{
+    URL_SAFE_NO_PAD.encode(format!("{sort}:{}", window.offset))
+}
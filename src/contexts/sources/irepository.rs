@@ -0,0 +1,14 @@
+pub mod get
+{
+    use async_trait::async_trait;
+    use crate::errors::{GetAllError, GetError};
+    use super::super::domain::{Health, SourceId};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// The health of every configured source, ordered by id.
+        async fn get_all(&self) -> Result<Vec<Health>, GetAllError>;
+        async fn get(&self, id: SourceId) -> Result<Health, GetError>;
+    }
+}
@@ -0,0 +1,334 @@
+use rocket::{Request, response::{self, Responder}, serde::json::Json};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    okapi::{
+        openapi3::{Header, Object, ParameterValue, RefOr, Responses},
+        schemars::{self, JsonSchema},
+    },
+    response::OpenApiResponderInner,
+};
+use crate::problems::Problem;
+use serde::{Deserialize, Serialize};
+
+use super::{domain, domain_to_router};
+use crate::typeutil::routers::DateTime;
+
+pub mod get
+{
+    use rocket::{get, serde::json::Json, State, http::Status};
+    use rocket_okapi::openapi;
+
+    use crate::errors::{GetAllError, GetError};
+    use crate::problems::Problem;
+
+    use super::super::{use_cases::get::UseCase, domain_to_router};
+    use super::{SourceHealth, SourceId};
+
+    /// # Get the health of every source
+    ///
+    /// Returns what the probes sent to each source in the background found out, sources being probed every 30 seconds by default.
+    #[openapi(tag = "Source")]
+    #[get("/sources")]
+    pub async fn get_sources(use_case: &State<UseCase>) -> Result<Json<Vec<SourceHealth>>, Problem>
+    {
+        match use_case.get_all().await
+        {
+            Ok(health) => Ok(Json(health.into_iter().map(domain_to_router::health).collect())),
+            Err(GetAllError::InternalServerError) => Err(Problem::internal()),
+        }
+    }
+
+    /// # Get the health of a source
+    ///
+    /// Returns whether the last probe of the source succeeded, how long it took, and when the source last answered or failed.
+    #[openapi(tag = "Source")]
+    #[get("/sources/<id>/health")]
+    pub async fn get_health(id: SourceId, use_case: &State<UseCase>) -> Result<Json<SourceHealth>, Problem>
+    {
+        match use_case.get(id).await
+        {
+            Ok(health) => Ok(Json(domain_to_router::health(health))),
+            Err(GetError::NonExistingId(id)) => Err(Problem::new(Status::NotFound, format!("No source with id `{id}`.")).id(id)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub type SourceId = u16;
+
+/// `status` is `unknown` until the first probe of the source completes, `latencyMs` is the time the last probe took.
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceHealth
+{
+    pub id           : SourceId,
+    pub name         : String,
+    pub status       : HealthStatus,
+    pub latency_ms   : Option<u64>,
+    pub last_check   : Option<DateTime>,
+    pub last_success : Option<DateTime>,
+    pub last_error   : Option<SourceFailure>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus
+{
+    Unknown,
+    Up,
+    Down,
+}
+
+/// `error` is named as in `X-Source-Status`, ex: `timeout`, `status_503`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct SourceFailure
+{
+    pub at    : DateTime,
+    pub error : String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceStatus
+{
+    pub id         : SourceId,
+    pub name       : String,
+    pub latency_ms : u64,
+    pub error      : Option<String>,
+    pub stale      : bool,
+    pub rejected   : Vec<Rejection>,
+}
+
+/// An item of a source that breaks its schema, with the id the source gave it, if any.
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Rejection
+{
+    pub id         : Option<String>,
+    pub violations : Vec<String>,
+}
+
+/// A list merged from every source, answered as a JSON array.
+///
+/// The status of each source is given in the headers:
+/// - `X-Sources-Total`   : number of sources that were queried
+/// - `X-Sources-Failed`  : comma separated ids of the sources that failed
+/// - `X-Source-Status`   : one header per source, ex: `id=2; name="Gym"; status=timeout; latency=5000ms; stale`
+/// - `X-Source-Rejected` : one header per source with items that break their schema, giving the first of them,
+///   ex: `source=1; count=3; id="7"; violations="/name: 3 is not of type 'string'"`. The others are only logged.
+///
+/// `stale` marks a failing source whose items come from a previous answer,
+/// `rejected=N` a source of which N items were dropped, or whose whole answer was rejected if the source is strict about its schema.
+///
+/// When there are items left, the following page is given by:
+/// - `Link`          : URL of the following page, with `rel="next"`
+/// - `X-Next-Cursor` : cursor to give to get the following page
+pub struct Listing<T>
+{
+    pub items   : Vec<T>,
+    pub sources : Vec<SourceStatus>,
+    pub next    : Option<String>,
+}
+
+impl<T> Listing<T>
+{
+    /// `sort` is the sort the page was made with, the cursor of the following page is only valid for it.
+    pub fn from_page<D>(page: domain::Page<D>, item: impl FnMut(D) -> T, sort: &str) -> Self
+    {
+        Listing {
+            items   : page.items.into_iter().map(item).collect(),
+            sources : page.sources.into_iter().map(domain_to_router::source_status).collect(),
+            next    : page.next.map(|window| domain_to_router::cursor(window, sort)),
+        }
+    }
+
+    /// Strict clients get a `502 Bad Gateway` as soon as one source failed, listing the sources that failed.
+    pub fn strict(self, strict: bool) -> Result<Self, Problem>
+    {
+        let (ids, failures): (Vec<SourceId>, Vec<String>) = self.sources.iter()
+            .filter_map(|source| source.error.as_ref().map(|error| (source.id, format!("{} ({}): {error}", source.id, source.name))))
+            .unzip();
+
+        if strict && !failures.is_empty()
+        {
+            return Err(Problem::sources_failed(ids, format!("Some sources failed: {}.", failures.join(", "))));
+        }
+        Ok(self)
+    }
+}
+
+impl<'r, T: Serialize> Responder<'r, 'static> for Listing<T>
+{
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static>
+    {
+        let failed: Vec<String> = self.sources.iter()
+            .filter(|source| source.error.is_some())
+            .map(|source| source.id.to_string())
+            .collect();
+
+        let mut response = Paginated { items: self.items, next: self.next }.respond_to(request)?;
+        response.set_raw_header("X-Sources-Total", self.sources.len().to_string());
+        response.set_raw_header("X-Sources-Failed", failed.join(","));
+        for source in self.sources
+        {
+            response.adjoin_raw_header("X-Source-Status", format!(
+                "id={}; name=\"{}\"; status={}; latency={}ms{}{}",
+                source.id,
+                source.name.replace('"', "'"),
+                source.error.as_deref().unwrap_or("ok"),
+                source.latency_ms,
+                if source.stale { "; stale" } else { "" },
+                if source.rejected.is_empty() { String::new() } else { format!("; rejected={}", source.rejected.len()) },
+            ));
+            if let Some(rejected) = rejected_header(source.id, &source.rejected)
+            {
+                response.adjoin_raw_header("X-Source-Rejected", rejected);
+            }
+        }
+        Ok(response)
+    }
+}
+
+/// The longest text of a source put in the `X-Source-Rejected` header, in characters.
+const MAX_REJECTION_LENGTH: usize = 200;
+
+/// Sums up the rejected items of a source with their count and the first of them, None without any.
+/// The text comes from the source: it is cut and reduced to visible ASCII to fit in a header.
+fn rejected_header(source_id: SourceId, rejected: &[Rejection]) -> Option<String>
+{
+    let text = |text: &str| text.chars()
+        .map(|c| match c { '"' => '\'', c if c.is_ascii_graphic() || c == ' ' => c, _ => '?' })
+        .take(MAX_REJECTION_LENGTH)
+        .collect::<String>();
+    let first = rejected.first()?;
+    Some(format!(
+        "source={}; count={}; id=\"{}\"; violations=\"{}\"",
+        source_id,
+        rejected.len(),
+        text(first.id.as_deref().unwrap_or_default()),
+        text(&first.violations.join(", ")),
+    ))
+}
+
+impl<T: Serialize + JsonSchema + Send> OpenApiResponderInner for Listing<T>
+{
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses>
+    {
+        let mut responses = Paginated::<T>::responses(gen)?;
+        if let Some(RefOr::Object(response)) = responses.responses.get_mut("200")
+        {
+            response.headers.insert("X-Sources-Total".to_owned(), header(gen, "Number of sources that were queried"));
+            response.headers.insert("X-Sources-Failed".to_owned(), header(gen, "Comma separated ids of the sources that failed"));
+            response.headers.insert("X-Source-Status".to_owned(), header(gen, "Status of a source, repeated for each source. Ex: `id=2; name=\"Gym\"; status=timeout; latency=5000ms; stale`"));
+            response.headers.insert("X-Source-Rejected".to_owned(), header(gen, "Number of items of a source that break their schema and the first of them, repeated for each such source. Ex: `source=1; count=3; id=\"7\"; violations=\"/name: 3 is not of type 'string'\"`"));
+        }
+        let mut failed = Problem::responses(gen)?.responses.remove("5XX");
+        if let Some(RefOr::Object(response)) = &mut failed
+        {
+            response.description = "Some sources failed and the client asked for a strict answer".to_owned();
+        }
+        responses.responses.extend(failed.map(|response| ("502".to_owned(), response)));
+        Ok(responses)
+    }
+}
+
+/// A list of items answered as a JSON array, for lists that don't come from the sources.
+///
+/// When there are items left, the following page is given by:
+/// - `Link`          : URL of the following page, with `rel="next"`
+/// - `X-Next-Cursor` : cursor to give to get the following page
+pub struct Paginated<T>
+{
+    pub items : Vec<T>,
+    pub next  : Option<String>,
+}
+
+impl<T> Paginated<T>
+{
+    /// `sort` is the sort the page was made with, the cursor of the following page is only valid for it.
+    pub fn new(items: Vec<T>, next: Option<domain::Window>, sort: &str) -> Self
+    {
+        Paginated { items, next: next.map(|window| domain_to_router::cursor(window, sort)) }
+    }
+}
+
+impl<'r, T: Serialize> Responder<'r, 'static> for Paginated<T>
+{
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static>
+    {
+        let mut response = Json(self.items).respond_to(request)?;
+        if let Some(cursor) = self.next
+        {
+            // Same query, starting from the cursor
+            let mut query: Vec<&str> = request.uri().query()
+                .map(|query| query.raw_segments()
+                    .map(|segment| segment.as_str())
+                    .filter(|segment| !matches!(segment.split('=').next(), Some("cursor" | "offset")))
+                    .collect())
+                .unwrap_or_default();
+            let cursor_segment = format!("cursor={cursor}");
+            query.push(&cursor_segment);
+            response.set_raw_header("Link", format!("<{}?{}>; rel=\"next\"", request.uri().path(), query.join("&")));
+            response.set_raw_header("X-Next-Cursor", cursor);
+        }
+        Ok(response)
+    }
+}
+
+impl<T: Serialize + JsonSchema + Send> OpenApiResponderInner for Paginated<T>
+{
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses>
+    {
+        let mut responses = Json::<Vec<T>>::responses(gen)?;
+        if let Some(RefOr::Object(response)) = responses.responses.get_mut("200")
+        {
+            response.headers.insert("Link".to_owned(), header(gen, "URL of the following page with `rel=\"next\"`, absent on the last page"));
+            response.headers.insert("X-Next-Cursor".to_owned(), header(gen, "Cursor of the following page, absent on the last page"));
+        }
+        Ok(responses)
+    }
+}
+
+fn header(gen: &mut OpenApiGenerator, description: &str) -> RefOr<Header>
+{
+    RefOr::Object(Header {
+        description       : Some(description.to_owned()),
+        required          : false,
+        deprecated        : false,
+        allow_empty_value : true,
+        extensions        : Object::default(),
+        value             : ParameterValue::Schema {
+            style          : None,
+            explode        : None,
+            allow_reserved : false,
+            schema         : gen.json_schema::<String>(),
+            example        : None,
+            examples       : None,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn rejection(id: &str, violation: &str) -> Rejection
+    {
+        Rejection { id: Some(String::from(id)), violations: vec![String::from(violation)] }
+    }
+
+    #[test]
+    fn test_rejected_header()
+    {
+        assert_eq!(rejected_header(1, &[]), None);
+
+        let rejected = [rejection("7", "/name: 3 is not of type \"string\""), rejection("8", "/city: null is not of type \"string\"")];
+        assert_eq!(rejected_header(1, &rejected).unwrap(), r#"source=1; count=2; id="7"; violations="/name: 3 is not of type 'string'""#);
+
+        // whatever the source answers, the header stays short and readable
+        let rejected = [rejection("é\r\n", &"x".repeat(1000))];
+        let header = rejected_header(2, &rejected).unwrap();
+        assert!(header.starts_with(r#"source=2; count=1; id="???"; violations="xxx"#));
+        assert!(header.len() < 2 * MAX_REJECTION_LENGTH);
+    }
+}
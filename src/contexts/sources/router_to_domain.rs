@@ -0,0 +1,64 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use rocket::http::Status;
+
+use super::domain;
+use crate::problems::Problem;
+
+/// Window asked by the client, either from an offset or from the cursor of a previous page.
+/// A cursor is only valid for the sort it was given with.
+pub fn window(limit: Option<usize>, offset: Option<usize>, cursor: Option<String>, sort: &str) -> Result<domain::Window, Problem>
+{
+    let limit = match limit
+    {
+        None        => domain::Window::DEFAULT_LIMIT,
+        Some(0)     => return Err(Problem::new(Status::BadRequest, String::from("`limit` must be positive."))),
+        Some(limit) => limit.min(domain::Window::MAX_LIMIT),
+    };
+    let offset = match (offset, cursor)
+    {
+        (Some(_), Some(_))   => return Err(Problem::new(Status::BadRequest, String::from("Give either an `offset` or a `cursor`, not both."))),
+        (Some(offset), None) => offset,
+        (None, Some(cursor)) => offset_from_cursor(&cursor, sort)
+            .ok_or_else(|| Problem::new(Status::BadRequest, format!("`{cursor}` is not a valid cursor for this list.")))?,
+        (None, None)         => 0,
+    };
+    Ok(domain::Window { offset, limit })
+}
+
+fn offset_from_cursor(cursor: &str, sort: &str) -> Option<usize>
+{
+    let cursor = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+    let (cursor_sort, offset) = cursor.rsplit_once(':')?;
+    if cursor_sort != sort
+    {
+        return None;
+    }
+    offset.parse().ok()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use super::super::domain_to_router;
+
+    #[test]
+    fn test_window()
+    {
+        assert_eq!(window(None, None, None, "name").unwrap(), domain::Window::default());
+        assert_eq!(window(Some(10), Some(20), None, "name").unwrap(), domain::Window { offset: 20, limit: 10 });
+        assert_eq!(window(Some(100000), None, None, "name").unwrap().limit, domain::Window::MAX_LIMIT);
+        assert!(window(Some(0), None, None, "name").is_err());
+        assert!(window(None, Some(0), Some(String::from("abc")), "name").is_err());
+    }
+
+    #[test]
+    fn test_cursor()
+    {
+        let cursor = domain_to_router::cursor(domain::Window { offset: 40, limit: 20 }, "name");
+        assert_eq!(window(Some(20), None, Some(cursor.clone()), "name").unwrap(), domain::Window { offset: 40, limit: 20 });
+        // the cursor of another sort
+        assert!(window(Some(20), None, Some(cursor), "city").is_err());
+        assert!(window(Some(20), None, Some(String::from("not a cursor")), "name").is_err());
+    }
+}
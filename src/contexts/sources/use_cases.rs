@@ -0,0 +1,29 @@
+pub mod get
+{
+    use std::sync::Arc;
+
+    use crate::errors::{GetAllError, GetError};
+
+    use super::super::{domain::{Health, SourceId}, irepository::get::IRepository};
+    pub struct UseCase
+    {
+        repository: Arc<dyn IRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
+        {
+            Self { repository: repo }
+        }
+
+        pub async fn get_all(&self) -> Result<Vec<Health>, GetAllError>
+        {
+            self.repository.get_all().await
+        }
+
+        pub async fn get(&self, id: SourceId) -> Result<Health, GetError>
+        {
+            self.repository.get(id).await
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::errors::InvalidField;
+
+/// Users are owned by the core, they are identified by their username.
+#[derive(Debug, Clone, PartialEq)]
+pub struct User
+{
+    pub username : Username,
+    pub profile  : Profile,
+}
+pub type Username = String;
+
+/// What a user can change after registering.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile
+{
+    pub first_name : String,
+    pub last_name  : String,
+    pub email      : String,
+}
+
+impl User
+{
+    /// Lists every rule the user breaks, usernames being part of paths such as `/users/<username>`.
+    pub fn validate(&self) -> Result<(), Vec<InvalidField>>
+    {
+        let mut invalid = Vec::new();
+        if self.username.trim().is_empty()
+        {
+            invalid.push(InvalidField::new("username", "must not be blank"));
+        }
+        else if self.username.contains(|c: char| c.is_whitespace() || c == '/')
+        {
+            invalid.push(InvalidField::new("username", "must not hold whitespace or `/`"));
+        }
+        invalid.extend(self.profile.validate().err().unwrap_or_default());
+        match invalid.is_empty()
+        {
+            true => Ok(()),
+            false => Err(invalid),
+        }
+    }
+}
+
+impl Profile
+{
+    /// Lists every rule the profile breaks.
+    pub fn validate(&self) -> Result<(), Vec<InvalidField>>
+    {
+        let mut invalid = Vec::new();
+        if !is_email(&self.email)
+        {
+            invalid.push(InvalidField::new("email", "must be an email address"));
+        }
+        match invalid.is_empty()
+        {
+            true => Ok(()),
+            false => Err(invalid),
+        }
+    }
+}
+
+/// A local part and a domain around a single `@`, without spaces, the domain having a dot between two labels.
+fn is_email(email: &str) -> bool
+{
+    match email.split_once('@')
+    {
+        Some((local, domain)) => !local.is_empty()
+            && !domain.contains('@')
+            && !email.chars().any(char::is_whitespace)
+            && domain.split('.').count() > 1
+            && domain.split('.').all(|label| !label.is_empty()),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn profile(email: &str) -> Profile
+    {
+        Profile { first_name: String::from("John"), last_name: String::from("Doe"), email: String::from(email) }
+    }
+
+    #[test]
+    fn test_validate_username()
+    {
+        let user = |username: &str| User { username: String::from(username), profile: profile("jdoe@example.com") };
+        assert_eq!(user("jdoe").validate(), Ok(()));
+        assert_eq!(user("Émile_42").validate(), Ok(()));
+        for username in ["", "  "]
+        {
+            assert_eq!(user(username).validate(), Err(vec![InvalidField::new("username", "must not be blank")]), "{username}");
+        }
+        for username in ["j doe", "jdoe\n", "j/doe"]
+        {
+            assert_eq!(user(username).validate(), Err(vec![InvalidField::new("username", "must not hold whitespace or `/`")]), "{username}");
+        }
+        let both = User { username: String::new(), profile: profile("jdoe") };
+        assert_eq!(both.validate().unwrap_err().len(), 2);
+    }
+
+    #[test]
+    fn test_validate()
+    {
+        assert_eq!(profile("jdoe@example.com").validate(), Ok(()));
+        assert_eq!(profile("john.doe+climbing@mail.example.fr").validate(), Ok(()));
+        for email in ["", "jdoe", "jdoe@", "@example.com", "jdoe@example", "jdoe@@example.com", "j doe@example.com", "jdoe@example..com", "jdoe@.example.com"]
+        {
+            assert_eq!(profile(email).validate(), Err(vec![InvalidField::new("email", "must be an email address")]), "{email}");
+        }
+    }
+}
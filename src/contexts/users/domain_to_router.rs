@@ -0,0 +1,12 @@
+use super::{domain, router};
+
+/// The email is left out unless `email` is set.
+pub fn user(u: domain::User, email: bool) -> router::User
+{
+    router::User {
+        username   : u.username,
+        first_name : u.profile.first_name,
+        last_name  : u.profile.last_name,
+        email      : email.then_some(u.profile.email),
+    }
+}
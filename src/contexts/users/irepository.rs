@@ -0,0 +1,54 @@
+pub mod get
+{
+    use async_trait::async_trait;
+    use crate::errors::{GetAllError, GetError};
+    use super::super::domain::{User, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        async fn get_all(&self) -> Result<Vec<User>, GetAllError>;
+        async fn get(&self, username: Username) -> Result<User, GetError>;
+    }
+}
+
+pub mod post
+{
+    use async_trait::async_trait;
+    use crate::errors::CreateError;
+    use super::super::domain::User;
+    use crate::contexts::auth::domain::AccountId;
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        /// Usernames are unique regardless of case, the password is only stored hashed.
+        async fn create(&self, user: User, password_hash: String, account: AccountId) -> Result<User, CreateError>;
+    }
+}
+
+pub mod put
+{
+    use async_trait::async_trait;
+    use crate::errors::UpdateError;
+    use super::super::domain::{Profile, Username};
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        async fn update(&self, username: Username, profile: Profile) -> Result<(), UpdateError>;
+    }
+}
+
+pub mod delete
+{
+    use async_trait::async_trait;
+    use crate::errors::DeleteError;
+    use super::super::domain::Username;
+
+    #[async_trait]
+    pub trait IRepository : Send + Sync
+    {
+        async fn delete(&self, username: Username) -> Result<(), DeleteError>;
+    }
+}
@@ -0,0 +1,174 @@
+use rocket_okapi::okapi::schemars::{self, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+pub mod get
+{
+    use rocket::{get, serde::json::Json, State, http::Status};
+    use rocket_okapi::openapi;
+
+    use super::super::{use_cases::get::UseCase, domain_to_router};
+    use super::User;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+    use crate::errors::{GetAllError, GetError};
+    use crate::problems::Problem;
+
+    /// # Get every user
+    ///
+    /// Emails are only given to their user and to platform admins.
+    #[openapi(tag = "User")]
+    #[get("/users")]
+    pub async fn get_all_users(user: Option<AuthenticatedUser>, use_case: &State<UseCase>) -> Result<Json<Vec<User>>, Problem>
+    {
+        match use_case.get_all().await
+        {
+            Ok(users) => Ok(Json(users.into_iter().map(|found| {
+                let email = matches!(&user, Some(user) if user.may_act_as(&found.username));
+                domain_to_router::user(found, email)
+            }).collect())),
+            Err(GetAllError::InternalServerError) => Err(Problem::internal()),
+        }
+    }
+
+    /// # Get the user that has the given username
+    ///
+    /// The email is only given to the user and to platform admins.
+    #[openapi(tag = "User")]
+    #[get("/users/<username>")]
+    pub async fn get_user(username: String, user: Option<AuthenticatedUser>, use_case: &State<UseCase>) -> Result<Json<User>, Problem>
+    {
+        let email = matches!(user, Some(user) if user.may_act_as(&username));
+        match use_case.get(username).await
+        {
+            Ok(user) => Ok(Json(domain_to_router::user(user, email))),
+            Err(GetError::NonExistingId(username)) => Err(Problem::new(Status::NotFound, format!("User `{username}` was not found.")).id(username)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod post
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{post, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use crate::errors::CreateError;
+    use crate::problems::Problem;
+
+    use super::super::{use_cases::post::UseCase, domain_to_router, router_to_domain};
+
+    use super::{Registration, User};
+
+    /// # Register a new user
+    ///
+    /// Returns the registered user, usernames are unique regardless of case.
+    #[openapi(tag = "User")]
+    #[post("/users", data = "<registration>")]
+    pub async fn register_user(registration: Json<Registration>, use_case: &State<UseCase>) -> Result<status::Created<Json<User>>, Problem>
+    {
+        let (user, password) = router_to_domain::registration(registration.into_inner());
+        match use_case.register(user, password).await
+        {
+            Ok(user) => Ok(status::Created::new(format!("/users/{}", user.username)).body(Json(domain_to_router::user(user, true)))),
+            Err(CreateError::AlreadyExists(username)) => Err(Problem::new(Status::Conflict, format!("Username `{username}` is already taken.")).id(username)),
+            Err(CreateError::Invalid(fields)) => Err(Problem::invalid(fields)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod put
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{put, serde::json::Json, State};
+    use rocket_okapi::openapi;
+
+    use crate::errors::UpdateError;
+    use crate::problems::Problem;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+
+    use super::super::{use_cases::put::UseCase, router_to_domain};
+
+    use super::Profile;
+
+    /// # Update the profile of a user
+    ///
+    /// Users can only update their own profile, platform admins any.
+    #[openapi(tag = "User")]
+    #[put("/users/<username>", data = "<profile>")]
+    pub async fn update_user(username: String, profile: Json<Profile>, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
+    {
+        user.acting_as(&username)?;
+        match use_case.update(username, router_to_domain::profile(profile.into_inner())).await
+        {
+            Ok(()) => Ok(status::NoContent),
+            Err(UpdateError::NonExistingId(username)) => Err(Problem::new(Status::NotFound, format!("No existing user `{username}`.")).id(username)),
+            Err(UpdateError::Invalid(fields)) => Err(Problem::invalid(fields)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+pub mod delete
+{
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::{delete, State};
+    use rocket_okapi::openapi;
+
+    use crate::errors::DeleteError;
+    use crate::problems::Problem;
+
+    use crate::contexts::auth::router::AuthenticatedUser;
+
+    use super::super::use_cases::delete::UseCase;
+
+    /// # Delete a user
+    ///
+    /// Users can only delete their own account, platform admins any.
+    #[openapi(tag = "User")]
+    #[delete("/users/<username>")]
+    pub async fn delete_user(username: String, user: AuthenticatedUser, use_case: &State<UseCase>) -> Result<status::NoContent, Problem>
+    {
+        user.acting_as(&username)?;
+        match use_case.delete(username).await
+        {
+            Ok(()) => Ok(status::NoContent),
+            Err(DeleteError::NonExistingId(username)) => Err(Problem::new(Status::NotFound, format!("No existing user `{username}`.")).id(username)),
+            Err(_) => Err(Problem::internal()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct User
+{
+    pub username   : String,
+    pub first_name : String,
+    pub last_name  : String,
+    /// Only given to the user and to platform admins.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email      : Option<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Registration
+{
+    pub username   : String,
+    pub password   : String,
+    pub first_name : String,
+    pub last_name  : String,
+    pub email      : String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Profile
+{
+    pub first_name : String,
+    pub last_name  : String,
+    pub email      : String,
+}
@@ -0,0 +1,24 @@
+use super::{domain, router};
+
+/// The user to register and their password.
+pub fn registration(r: router::Registration) -> (domain::User, String)
+{
+    let user = domain::User {
+        username : r.username,
+        profile  : domain::Profile {
+            first_name : r.first_name,
+            last_name  : r.last_name,
+            email      : r.email,
+        },
+    };
+    (user, r.password)
+}
+
+pub fn profile(p: router::Profile) -> domain::Profile
+{
+    domain::Profile {
+        first_name : p.first_name,
+        last_name  : p.last_name,
+        email      : p.email,
+    }
+}
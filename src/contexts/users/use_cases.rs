@@ -0,0 +1,140 @@
+pub mod get
+{
+    use std::sync::Arc;
+
+    use crate::errors::{GetAllError, GetError};
+
+    use super::super::{domain::{User, Username}, irepository::get::IRepository};
+    pub struct UseCase
+    {
+        repository: Arc<dyn IRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
+        {
+            Self { repository: repo }
+        }
+
+        pub async fn get_all(&self) -> Result<Vec<User>, GetAllError>
+        {
+            self.repository.get_all().await
+        }
+
+        pub async fn get(&self, username: Username) -> Result<User, GetError>
+        {
+            self.repository.get(username).await
+        }
+    }
+}
+
+pub mod post
+{
+    use std::sync::Arc;
+
+    use crate::errors::CreateError;
+
+    use crate::contexts::auth::domain::{new_account_id, password};
+
+    use super::super::{domain::User, irepository::post::IRepository};
+    pub struct UseCase
+    {
+        repository: Arc<dyn IRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
+        {
+            Self { repository: repo }
+        }
+
+        pub async fn register(&self, user: User, password: String) -> Result<User, CreateError>
+        {
+            let mut invalid = user.validate().err().unwrap_or_default();
+            invalid.extend(password::validate(&password).err());
+            if !invalid.is_empty()
+            {
+                return Err(CreateError::Invalid(invalid));
+            }
+            // Argon2 is slow on purpose, it would hold up the async workers
+            let password_hash = tokio::task::spawn_blocking(move || password::hash(&password)).await
+                .map_err(|_| CreateError::InternalServerError)?;
+            self.repository.create(user, password_hash, new_account_id()).await
+        }
+    }
+}
+
+pub mod put
+{
+    use std::sync::Arc;
+
+    use crate::errors::UpdateError;
+
+    use super::super::{domain::{Profile, Username}, irepository::put::IRepository};
+    pub struct UseCase
+    {
+        repository: Arc<dyn IRepository>,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>) -> Self
+        {
+            Self { repository: repo }
+        }
+
+        pub async fn update(&self, username: Username, profile: Profile) -> Result<(), UpdateError>
+        {
+            profile.validate().map_err(UpdateError::Invalid)?;
+            self.repository.update(username, profile).await
+        }
+    }
+}
+
+pub mod delete
+{
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use crate::errors::DeleteError;
+    use crate::contexts::{ascents, comments, grades, moderation, progress, ratings};
+
+    use super::super::{domain::Username, irepository::delete::IRepository};
+
+    /// What the other contexts keep about a user, which goes along with the user.
+    pub struct Traces
+    {
+        pub progress   : Arc<dyn progress::irepository::delete::IRepository>,
+        pub ascents    : Arc<dyn ascents::irepository::delete::IRepository>,
+        pub comments   : Arc<dyn comments::irepository::delete::IRepository>,
+        pub grades     : Arc<dyn grades::irepository::delete::IRepository>,
+        pub ratings    : Arc<dyn ratings::irepository::delete::IRepository>,
+        pub moderation : Arc<dyn moderation::irepository::delete::IRepository>,
+    }
+
+    pub struct UseCase
+    {
+        repository: Arc<dyn IRepository>,
+        traces: Traces,
+    }
+    impl UseCase
+    {
+        pub fn new(repo: Arc<dyn IRepository>, traces: Traces) -> Self
+        {
+            Self { repository: repo, traces }
+        }
+
+        /// Deletes the user, then what the user left behind, so that whoever registers the username next starts afresh.
+        /// The comments of the user are marked as deleted rather than removed, they are kept for their replies.
+        pub async fn delete(&self, username: Username) -> Result<(), DeleteError>
+        {
+            self.repository.delete(username.clone()).await?;
+            self.traces.progress.forget(username.clone()).await?;
+            self.traces.ascents.forget(username.clone()).await?;
+            self.traces.comments.forget(username.clone(), Utc::now()).await?;
+            self.traces.grades.forget(username.clone()).await?;
+            self.traces.ratings.forget(username.clone()).await?;
+            self.traces.moderation.forget(username).await
+        }
+    }
+}
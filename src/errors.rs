@@ -1,24 +1,102 @@
+use crate::repositories::FetchError;
+
+#[derive(Debug)]
 pub enum GetAllError
 {
     InternalServerError,
 }
 
+/// `MalformedId` holds an id that could not be parsed,
+/// `UnknownSource` an id whose source is not configured
+/// and `BadUpstreamId` an id returned by a source that is not a 32bit integer.
+/// `Source` is a source that failed to answer.
+#[derive(Debug)]
 pub enum GetError
 {
     NonExistingId(String),
+    MalformedId(String),
+    UnknownSource(String),
+    BadUpstreamId(String),
+    Source(SourceFailure),
     InternalServerError,
 }
+/// `AlreadyExists` holds the id that is already taken,
+/// `Forbidden` the source the user may not change
+/// and `NonExistingReference` an id the data refers to that does not exist.
+/// `Invalid` lists the fields of the data that break a rule, it is checked before any source is asked.
+#[derive(Debug)]
 pub enum CreateError
 {
+    AlreadyExists(String),
+    Invalid(Vec<InvalidField>),
+    Forbidden(u16),
+    NonExistingReference(String),
+    MalformedId(String),
+    UnknownSource(String),
+    BadUpstreamId(String),
+    Source(SourceFailure),
     InternalServerError,
 }
+/// `Forbidden` holds the source the user may not change,
+/// `NotOwner` the id of a resource that belongs to another user
+/// and `NonExistingReference` an id the data refers to that does not exist.
+/// `Invalid` lists the fields of the data that break a rule.
+#[derive(Debug)]
 pub enum UpdateError
 {
     NonExistingId(String),
+    Invalid(Vec<InvalidField>),
+    Forbidden(u16),
+    NotOwner(String),
+    NonExistingReference(String),
+    MalformedId(String),
+    UnknownSource(String),
+    Source(SourceFailure),
     InternalServerError,
 }
+/// `Forbidden` holds the source the user may not change
+/// and `NotOwner` the id of a resource that belongs to another user.
+#[derive(Debug)]
 pub enum DeleteError
 {
     NonExistingId(String),
+    Forbidden(u16),
+    NotOwner(String),
+    MalformedId(String),
+    UnknownSource(String),
+    Source(SourceFailure),
+    InternalServerError,
+}
+/// `InvalidCredentials` does not tell an unknown user from a wrong password,
+/// `InvalidToken` is for tokens that are malformed, expired, of the wrong kind or of a deleted user.
+#[derive(Debug)]
+pub enum AuthError
+{
+    InvalidCredentials,
+    InvalidToken,
     InternalServerError,
-}
\ No newline at end of file
+}
+
+/// A source that failed to answer, along with the cause of the failure for the logs.
+#[derive(Debug)]
+pub struct SourceFailure
+{
+    pub source_id : u16,
+    pub cause     : FetchError,
+}
+
+/// A field of the data given by a client and the rule it breaks, ex: `name` "must not be blank".
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidField
+{
+    pub field  : &'static str,
+    pub reason : String,
+}
+
+impl InvalidField
+{
+    pub fn new(field: &'static str, reason: impl Into<String>) -> Self
+    {
+        InvalidField { field, reason: reason.into() }
+    }
+}
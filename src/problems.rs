@@ -0,0 +1,244 @@
+use std::io::Cursor;
+use std::ops::Deref;
+
+use rocket::{Request, catch, http::{ContentType, Status}, response::{self, Responder, Response}};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    okapi::{
+        openapi3::{MediaType, RefOr, Responses},
+        schemars::{self, JsonSchema},
+    },
+    response::OpenApiResponderInner,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{InvalidField, SourceFailure};
+use crate::repositories::FetchError;
+use crate::typeutil::ids::ParseIdError;
+
+/// An error answered as `application/problem+json` (RFC 7807), boxed since routers return it as their `Err`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem(Box<Details>);
+
+/// `type` names the kind of problem (`/problems/not-found`, `/problems/source-failure`...),
+/// `title` sums it up and `detail` explains this occurrence of it.
+/// `id` is the offending id, if any, `sources` the ids of the sources that failed
+/// and `invalid-params` the fields of the body that break a rule.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[schemars(rename = "Problem")]
+pub struct Details
+{
+    #[serde(rename = "type")]
+    pub kind    : String,
+    pub title   : String,
+    pub status  : u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail  : Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id      : Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources : Vec<u16>,
+    #[serde(default, rename = "invalid-params", skip_serializing_if = "Vec::is_empty")]
+    pub invalid : Vec<InvalidParam>,
+}
+
+/// A field of the body, named as in the body, and the rule it breaks.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct InvalidParam
+{
+    pub name   : String,
+    pub reason : String,
+}
+
+impl Problem
+{
+    /// A problem named after the status, ex: `404 Not Found` is `/problems/not-found`.
+    pub fn new(status: Status, detail: impl Into<String>) -> Self
+    {
+        let title = status.reason_lossy();
+        Problem(Box::new(Details {
+            kind    : format!("/problems/{}", title.to_lowercase().replace(' ', "-").replace('\'', "")),
+            title   : String::from(title),
+            status  : status.code,
+            detail  : Some(detail.into()),
+            id      : None,
+            sources : Vec::new(),
+            invalid : Vec::new(),
+        }))
+    }
+
+    /// A `500 Internal Server Error`, whose cause is left to the logs.
+    pub fn internal() -> Self
+    {
+        let mut problem = Problem::new(Status::InternalServerError, "");
+        problem.0.detail = None;
+        problem
+    }
+
+    /// The given sources failed, while the client asked for an answer from each of them.
+    pub fn sources_failed(sources: Vec<u16>, detail: impl Into<String>) -> Self
+    {
+        let mut problem = Problem::new(Status::BadGateway, detail);
+        problem.0.kind = String::from("/problems/source-failure");
+        problem.0.title = String::from("Source failure");
+        problem.0.sources = sources;
+        problem
+    }
+
+    /// A `422 Unprocessable Entity` listing the fields that break a rule, the fields of the domain being named in camelCase as in the bodies.
+    pub fn invalid(fields: Vec<InvalidField>) -> Self
+    {
+        let mut problem = Problem::new(Status::UnprocessableEntity, "Some fields are invalid.");
+        problem.0.invalid = fields.into_iter()
+            .map(|invalid| InvalidParam { name: camel_case(invalid.field), reason: invalid.reason })
+            .collect();
+        problem
+    }
+
+    /// The offending id.
+    pub fn id(mut self, id: impl Into<String>) -> Self
+    {
+        self.0.id = Some(id.into());
+        self
+    }
+}
+
+/// Ex: "closing_date" -> "closingDate"
+fn camel_case(field: &str) -> String
+{
+    let mut words = field.split('_');
+    let first = words.next().unwrap_or_default().to_owned();
+    words.fold(first, |mut name, word|
+    {
+        let mut chars = word.chars();
+        name.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+        name.push_str(chars.as_str());
+        name
+    })
+}
+
+impl Deref for Problem
+{
+    type Target = Details;
+    fn deref(&self) -> &Self::Target { &self.0 }
+}
+
+impl From<SourceFailure> for Problem
+{
+    /// `503 Service Unavailable` while the source is skipped,
+    /// `504 Gateway Timeout` if it did not answer in time, and `502 Bad Gateway` otherwise.
+    fn from(failure: SourceFailure) -> Self
+    {
+        eprintln!("Source {} failed: {:?}", failure.source_id, failure.cause);
+        let (status, detail) = match failure.cause
+        {
+            FetchError::CircuitOpen => (Status::ServiceUnavailable, "failed too many times recently and is skipped"),
+            FetchError::Down => (Status::ServiceUnavailable, "is down and is skipped"),
+            FetchError::Networking(e) if e.is_timeout() => (Status::GatewayTimeout, "did not answer in time"),
+            FetchError::Networking(_) => (Status::BadGateway, "could not be reached"),
+            FetchError::Status(_) => (Status::BadGateway, "answered with an error"),
+            _ => (Status::BadGateway, "gave an invalid answer"),
+        };
+        let mut problem = Problem::sources_failed(vec![failure.source_id], format!("The source `{}` {detail}.", failure.source_id));
+        problem.0.status = status.code;
+        problem
+    }
+}
+
+impl From<ParseIdError> for Problem
+{
+    fn from(e: ParseIdError) -> Self
+    {
+        let id = match &e
+        {
+            ParseIdError::Format(id) | ParseIdError::SourceId(id, _) | ParseIdError::ResourceId(id, _) => id.clone(),
+        };
+        Problem::new(Status::BadRequest, e.to_string()).id(id)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for Problem
+{
+    fn respond_to(self, _request: &'r Request<'_>) -> response::Result<'static>
+    {
+        let body = serde_json::to_string(&*self.0).map_err(|_| Status::InternalServerError)?;
+        Response::build()
+            .status(Status::new(self.status))
+            .header(ContentType::new("application", "problem+json"))
+            .sized_body(body.len(), Cursor::new(body))
+            .ok()
+    }
+}
+
+impl OpenApiResponderInner for Problem
+{
+    /// Any `4XX` or `5XX` status, with a problem as the body.
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses>
+    {
+        let schema = gen.json_schema::<Details>();
+        let mut responses = Responses::default();
+        for (status, description) in [("4XX", "The request is invalid or not allowed"), ("5XX", "The server or a source failed")]
+        {
+            let mut response = rocket_okapi::okapi::openapi3::Response { description: String::from(description), ..Default::default() };
+            response.content.insert(String::from("application/problem+json"), MediaType { schema: Some(schema.clone()), ..Default::default() });
+            responses.responses.insert(String::from(status), RefOr::Object(response));
+        }
+        Ok(responses)
+    }
+}
+
+/// Errors that don't come from a route, such as a failed request guard or an unknown path, are problems too.
+#[catch(default)]
+pub fn default_catcher(status: Status, request: &Request) -> Problem
+{
+    match status.code
+    {
+        401 => Problem::new(status, "Missing, invalid or expired access token."),
+        404 => Problem::new(status, format!("No endpoint at `{} {}`.", request.method(), request.uri().path())),
+        500 => Problem::internal(),
+        _ => Problem::new(status, status.reason_lossy()),
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_new()
+    {
+        let problem = Problem::new(Status::NotFound, "Place with id `0001-00000001` was not found.").id("0001-00000001");
+        assert_eq!(serde_json::to_value(&*problem).unwrap(), serde_json::json!({
+            "type"   : "/problems/not-found",
+            "title"  : "Not Found",
+            "status" : 404,
+            "detail" : "Place with id `0001-00000001` was not found.",
+            "id"     : "0001-00000001",
+        }));
+        assert_eq!(Problem::new(Status::ImATeapot, "").kind, "/problems/im-a-teapot");
+    }
+
+    #[test]
+    fn test_source_failure()
+    {
+        let problem = Problem::from(SourceFailure { source_id: 2, cause: FetchError::CircuitOpen });
+        assert_eq!((problem.kind.as_str(), problem.status, problem.sources.as_slice()), ("/problems/source-failure", 503, &[2][..]));
+
+        let problem = Problem::from(SourceFailure { source_id: 2, cause: FetchError::Status(reqwest::StatusCode::INTERNAL_SERVER_ERROR) });
+        assert_eq!((problem.status, problem.detail.as_deref()), (502, Some("The source `2` answered with an error.")));
+    }
+
+    #[test]
+    fn test_invalid()
+    {
+        let problem = Problem::invalid(vec![InvalidField::new("closing_date", "must not be before the opening date")]);
+        assert_eq!(serde_json::to_value(&*problem).unwrap(), serde_json::json!({
+            "type"           : "/problems/unprocessable-entity",
+            "title"          : "Unprocessable Entity",
+            "status"         : 422,
+            "detail"         : "Some fields are invalid.",
+            "invalid-params" : [{ "name": "closingDate", "reason": "must not be before the opening date" }],
+        }));
+    }
+}
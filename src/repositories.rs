@@ -1,12 +1,68 @@
+mod breaker;
+mod cache;
 mod common;
 mod config;
+mod health;
+mod schemas;
+pub mod storage;
+
+pub use common::{FetchError, Upstream};
 
 pub mod places
 {
     pub mod repository;
+    pub mod memory;
 }
 
 pub mod routes
 {
     pub mod repository;
+    pub mod memory;
+}
+
+pub mod sources
+{
+    pub mod repository;
+}
+
+pub mod users
+{
+    pub mod repository;
+    pub mod sqlite;
+}
+
+pub mod progress
+{
+    pub mod repository;
+    pub mod sqlite;
+}
+
+pub mod ascents
+{
+    pub mod repository;
+    pub mod sqlite;
+}
+
+pub mod comments
+{
+    pub mod repository;
+    pub mod sqlite;
+}
+
+pub mod grades
+{
+    pub mod repository;
+    pub mod sqlite;
+}
+
+pub mod moderation
+{
+    pub mod repository;
+    pub mod sqlite;
+}
+
+pub mod ratings
+{
+    pub mod repository;
+    pub mod sqlite;
 }
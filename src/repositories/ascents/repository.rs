@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::contexts::ascents::{domain, irepository};
+use crate::errors::{CreateError, DeleteError, GetAllError, GetError, UpdateError};
+
+/// Ascents are kept in memory with the lowercased username of their climber, ids are given in sequence.
+/// Clones share the same ascents.
+#[derive(Clone, Default)]
+pub struct Repository {
+    logbook: Arc<Mutex<Logbook>>,
+}
+
+#[derive(Default)]
+struct Logbook {
+    last_id: domain::AscentId,
+    ascents: BTreeMap<domain::AscentId, (String, domain::Ascent)>,
+}
+
+impl Logbook {
+    /// The ascent of the given id, if it belongs to the user.
+    fn get_mut(&mut self, username: &str, id: domain::AscentId) -> Option<&mut domain::Ascent> {
+        match self.ascents.get_mut(&id) {
+            Some((climber, ascent)) if *climber == key(username) => Some(ascent),
+            _ => None,
+        }
+    }
+}
+
+impl Repository {
+    fn logbook(&self) -> std::sync::MutexGuard<'_, Logbook> {
+        self.logbook.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn key(username: &str) -> String {
+    username.to_lowercase()
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self, username: domain::Username) -> Result<Vec<domain::Ascent>, GetAllError> {
+        let username = key(&username);
+        Ok(self.logbook().ascents.values()
+            .filter(|(climber, _)| *climber == username)
+            .map(|(_, ascent)| ascent.clone())
+            .collect())
+    }
+
+    async fn get(&self, username: domain::Username, id: domain::AscentId) -> Result<domain::Ascent, GetError> {
+        self.logbook().get_mut(&username, id).map(|ascent| ascent.clone()).ok_or(GetError::NonExistingId(id.to_string()))
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::post::IRepository for Repository {
+    async fn create(&self, username: domain::Username, data: domain::AscentData) -> Result<domain::Ascent, CreateError> {
+        let mut logbook = self.logbook();
+        logbook.last_id += 1;
+        let ascent = domain::Ascent { id: logbook.last_id, data };
+        logbook.ascents.insert(ascent.id, (key(&username), ascent.clone()));
+        Ok(ascent)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn update(&self, username: domain::Username, id: domain::AscentId, data: domain::AscentData) -> Result<(), UpdateError> {
+        match self.logbook().get_mut(&username, id) {
+            Some(ascent) => {
+                ascent.data = data;
+                Ok(())
+            },
+            None => Err(UpdateError::NonExistingId(id.to_string())),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, username: domain::Username, id: domain::AscentId) -> Result<(), DeleteError> {
+        let mut logbook = self.logbook();
+        if logbook.get_mut(&username, id).is_none() {
+            return Err(DeleteError::NonExistingId(id.to_string()));
+        }
+        logbook.ascents.remove(&id);
+        Ok(())
+    }
+
+    async fn forget(&self, username: domain::Username) -> Result<(), DeleteError> {
+        let climber = key(&username);
+        self.logbook().ascents.retain(|_, (key, _)| *key != climber);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use irepository::{get::IRepository, post::IRepository as _, put::IRepository as _, delete::IRepository as _};
+
+    fn data(route_id: &str, style: domain::Style) -> domain::AscentData {
+        domain::AscentData {
+            route_id: String::from(route_id),
+            date: domain::Date::from_ymd_opt(2023, 5, 1).unwrap(),
+            style,
+            attempts: 1,
+            grade: None,
+            rating: None,
+            notes: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get() {
+        let repo = Repository::default();
+        let first = repo.create(String::from("jdoe"), data("0001-00000001", domain::Style::Flash)).await.unwrap();
+        let second = repo.create(String::from("asmith"), data("0001-00000001", domain::Style::Onsight)).await.unwrap();
+        assert_ne!(first.id, second.id);
+
+        assert_eq!(repo.get_all(String::from("JDoe")).await.unwrap(), vec![first.clone()]);
+        assert_eq!(repo.get(String::from("jdoe"), first.id).await.unwrap(), first);
+        // ascents of other users are not found
+        assert!(matches!(repo.get(String::from("jdoe"), second.id).await, Err(GetError::NonExistingId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete() {
+        let repo = Repository::default();
+        let ascent = repo.create(String::from("jdoe"), data("0001-00000001", domain::Style::Flash)).await.unwrap();
+
+        assert!(matches!(repo.update(String::from("asmith"), ascent.id, data("0001-00000002", domain::Style::Repeat)).await, Err(UpdateError::NonExistingId(_))));
+        repo.update(String::from("jdoe"), ascent.id, data("0001-00000002", domain::Style::Repeat)).await.unwrap();
+        assert_eq!(repo.get(String::from("jdoe"), ascent.id).await.unwrap().data, data("0001-00000002", domain::Style::Repeat));
+
+        assert!(matches!(repo.delete(String::from("asmith"), ascent.id).await, Err(DeleteError::NonExistingId(_))));
+        repo.delete(String::from("jdoe"), ascent.id).await.unwrap();
+        assert!(repo.get_all(String::from("jdoe")).await.unwrap().is_empty());
+    }
+}
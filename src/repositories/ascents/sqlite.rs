@@ -0,0 +1,192 @@
+use rusqlite::{params, OptionalExtension, Row};
+
+use crate::contexts::ascents::{domain, irepository};
+use crate::errors::{CreateError, DeleteError, GetAllError, GetError, UpdateError};
+use crate::repositories::storage::{self, Pool};
+
+/// Ascents are kept in the database with the lowercased username of their climber, ids are given in sequence.
+#[derive(Clone)]
+pub struct Repository {
+    pool: Pool,
+}
+
+impl Repository {
+    pub fn new(pool: Pool) -> Self {
+        Repository { pool }
+    }
+}
+
+fn key(username: &str) -> String {
+    username.to_lowercase()
+}
+
+const ASCENT: &str = "SELECT id, route_id, date, style, attempts, grade, rating, notes FROM ascents";
+
+fn ascent(row: &Row) -> rusqlite::Result<domain::Ascent> {
+    let style: String = row.get(3)?;
+    let grade: Option<String> = row.get(5)?;
+    Ok(domain::Ascent {
+        id: row.get(0)?,
+        data: domain::AscentData {
+            route_id: row.get(1)?,
+            date: row.get(2)?,
+            style: parse_style(&style)?,
+            attempts: row.get(4)?,
+            grade: grade.as_deref().map(domain::Grade::parse),
+            rating: row.get(6)?,
+            notes: row.get(7)?,
+        },
+    })
+}
+
+fn style(style: domain::Style) -> &'static str {
+    match style {
+        domain::Style::Onsight => "onsight",
+        domain::Style::Flash => "flash",
+        domain::Style::Redpoint => "redpoint",
+        domain::Style::Repeat => "repeat",
+        domain::Style::Toprope => "toprope",
+    }
+}
+
+fn parse_style(text: &str) -> rusqlite::Result<domain::Style> {
+    match text {
+        "onsight" => Ok(domain::Style::Onsight),
+        "flash" => Ok(domain::Style::Flash),
+        "redpoint" => Ok(domain::Style::Redpoint),
+        "repeat" => Ok(domain::Style::Repeat),
+        "toprope" => Ok(domain::Style::Toprope),
+        _ => Err(storage::unreadable(3, text)),
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self, username: domain::Username) -> Result<Vec<domain::Ascent>, GetAllError> {
+        let username = key(&username);
+        self.pool.run(move |connection| {
+            connection.prepare(&format!("{ASCENT} WHERE climber_key = ?1 ORDER BY id"))?.query_map([username], ascent)?.collect()
+        }).await.map_err(|_| GetAllError::InternalServerError)
+    }
+
+    async fn get(&self, username: domain::Username, id: domain::AscentId) -> Result<domain::Ascent, GetError> {
+        let username = key(&username);
+        let ascent = self.pool.run(move |connection| {
+            connection.query_row(&format!("{ASCENT} WHERE climber_key = ?1 AND id = ?2"), params![username, id], ascent).optional()
+        }).await.map_err(|_| GetError::InternalServerError)?;
+
+        ascent.ok_or(GetError::NonExistingId(id.to_string()))
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::post::IRepository for Repository {
+    async fn create(&self, username: domain::Username, data: domain::AscentData) -> Result<domain::Ascent, CreateError> {
+        let username = key(&username);
+        self.pool.run(move |connection| {
+            connection.execute(
+                "INSERT INTO ascents (climber_key, route_id, date, style, attempts, grade, rating, notes) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![username, data.route_id, data.date, style(data.style), data.attempts, data.grade.as_ref().map(|grade| &grade.text), data.rating, data.notes],
+            )?;
+            Ok(domain::Ascent { id: connection.last_insert_rowid() as domain::AscentId, data })
+        }).await.map_err(|_| CreateError::InternalServerError)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn update(&self, username: domain::Username, id: domain::AscentId, data: domain::AscentData) -> Result<(), UpdateError> {
+        let username = key(&username);
+        let updated = self.pool.run(move |connection| {
+            connection.execute(
+                "UPDATE ascents SET route_id = ?3, date = ?4, style = ?5, attempts = ?6, grade = ?7, rating = ?8, notes = ?9 WHERE climber_key = ?1 AND id = ?2",
+                params![username, id, data.route_id, data.date, style(data.style), data.attempts, data.grade.as_ref().map(|grade| &grade.text), data.rating, data.notes],
+            )
+        }).await.map_err(|_| UpdateError::InternalServerError)?;
+
+        match updated {
+            0 => Err(UpdateError::NonExistingId(id.to_string())),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, username: domain::Username, id: domain::AscentId) -> Result<(), DeleteError> {
+        let username = key(&username);
+        let deleted = self.pool.run(move |connection| connection.execute("DELETE FROM ascents WHERE climber_key = ?1 AND id = ?2", params![username, id]))
+            .await.map_err(|_| DeleteError::InternalServerError)?;
+
+        match deleted {
+            0 => Err(DeleteError::NonExistingId(id.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    async fn forget(&self, username: domain::Username) -> Result<(), DeleteError> {
+        let username = key(&username);
+        self.pool.run(move |connection| connection.execute("DELETE FROM ascents WHERE climber_key = ?1", [username]))
+            .await.map(|_| ()).map_err(|_| DeleteError::InternalServerError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use irepository::{get::IRepository, post::IRepository as _, put::IRepository as _, delete::IRepository as _};
+
+    fn data(route_id: &str, style: domain::Style) -> domain::AscentData {
+        domain::AscentData {
+            route_id: String::from(route_id),
+            date: domain::Date::from_ymd_opt(2023, 5, 1).unwrap(),
+            style,
+            attempts: 1,
+            grade: Some(domain::Grade::parse("6b+")),
+            rating: Some(4),
+            notes: String::from("Crimpy"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        let first = repo.create(String::from("jdoe"), data("0001-00000001", domain::Style::Flash)).await.unwrap();
+        let second = repo.create(String::from("asmith"), data("0001-00000001", domain::Style::Onsight)).await.unwrap();
+        assert_ne!(first.id, second.id);
+
+        assert_eq!(repo.get_all(String::from("JDoe")).await.unwrap(), vec![first.clone()]);
+        assert_eq!(repo.get(String::from("jdoe"), first.id).await.unwrap(), first);
+        // ascents of other users are not found
+        assert!(matches!(repo.get(String::from("jdoe"), second.id).await, Err(GetError::NonExistingId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        let ascent = repo.create(String::from("jdoe"), data("0001-00000001", domain::Style::Flash)).await.unwrap();
+        let repeat = domain::AscentData { grade: None, rating: None, ..data("0001-00000002", domain::Style::Repeat) };
+
+        assert!(matches!(repo.update(String::from("asmith"), ascent.id, repeat.clone()).await, Err(UpdateError::NonExistingId(_))));
+        repo.update(String::from("jdoe"), ascent.id, repeat.clone()).await.unwrap();
+        assert_eq!(repo.get(String::from("jdoe"), ascent.id).await.unwrap().data, repeat);
+
+        assert!(matches!(repo.delete(String::from("asmith"), ascent.id).await, Err(DeleteError::NonExistingId(_))));
+        repo.delete(String::from("jdoe"), ascent.id).await.unwrap();
+        assert!(repo.get_all(String::from("jdoe")).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_forget() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        repo.create(String::from("jdoe"), data("0001-00000001", domain::Style::Flash)).await.unwrap();
+        let other = repo.create(String::from("asmith"), data("0001-00000001", domain::Style::Onsight)).await.unwrap();
+
+        repo.forget(String::from("JDoe")).await.unwrap();
+        assert!(repo.get_all(String::from("jdoe")).await.unwrap().is_empty());
+        assert_eq!(repo.get_all(String::from("asmith")).await.unwrap(), vec![other]);
+    }
+}
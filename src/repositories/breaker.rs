@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use super::config::Resilience;
+
+/// Circuit breakers of the sources, keyed by source id.
+///
+/// A breaker opens after `breaker_threshold` consecutive failures of its source: requests are
+/// rejected during `breaker_cooldown_ms`, then a single request is let through to probe the source.
+/// A success closes the breaker, a failure opens it again.
+#[derive(Default)]
+pub struct Breakers {
+    states: Mutex<HashMap<u16, State>>,
+}
+
+#[derive(Default)]
+struct State {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl Breakers {
+    /// Whether a request may be sent to the source.
+    pub fn allow(&self, source_id: u16, resilience: &Resilience) -> bool {
+        let mut states = self.states.lock().unwrap_or_else(PoisonError::into_inner);
+        let state = states.entry(source_id).or_default();
+        match state.open_until {
+            Some(until) if Instant::now() < until => false,
+            Some(_) => {
+                // The probe goes through, other requests are rejected until it completes
+                state.open_until = Some(Instant::now() + cooldown(resilience));
+                true
+            }
+            None => true,
+        }
+    }
+
+    pub fn success(&self, source_id: u16) {
+        let mut states = self.states.lock().unwrap_or_else(PoisonError::into_inner);
+        states.remove(&source_id);
+    }
+
+    pub fn failure(&self, source_id: u16, resilience: &Resilience) {
+        let mut states = self.states.lock().unwrap_or_else(PoisonError::into_inner);
+        let state = states.entry(source_id).or_default();
+        state.failures += 1;
+        if resilience.breaker_threshold > 0 && state.failures >= resilience.breaker_threshold {
+            state.open_until = Some(Instant::now() + cooldown(resilience));
+        }
+    }
+}
+
+fn cooldown(resilience: &Resilience) -> Duration {
+    Duration::from_millis(resilience.breaker_cooldown_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resilience(breaker_cooldown_ms: u64) -> Resilience {
+        Resilience { breaker_threshold: 2, breaker_cooldown_ms, ..Resilience::default() }
+    }
+
+    #[test]
+    fn test_opens_after_threshold() {
+        let breakers = Breakers::default();
+        let resilience = resilience(60000);
+
+        assert!(breakers.allow(1, &resilience));
+        breakers.failure(1, &resilience);
+        assert!(breakers.allow(1, &resilience));
+        breakers.failure(1, &resilience);
+        assert!(!breakers.allow(1, &resilience));
+        // other sources are not affected
+        assert!(breakers.allow(2, &resilience));
+    }
+
+    #[test]
+    fn test_success_resets_failures() {
+        let breakers = Breakers::default();
+        let resilience = resilience(60000);
+
+        breakers.failure(1, &resilience);
+        breakers.success(1);
+        breakers.failure(1, &resilience);
+        assert!(breakers.allow(1, &resilience));
+    }
+
+    #[test]
+    fn test_probe_after_cooldown() {
+        let breakers = Breakers::default();
+        let resilience = resilience(0);
+
+        breakers.failure(1, &resilience);
+        breakers.failure(1, &resilience);
+        // the cooldown is over, a probe is let through
+        assert!(breakers.allow(1, &resilience));
+        breakers.success(1);
+        assert!(breakers.allow(1, &resilience));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let breakers = Breakers::default();
+        let resilience = Resilience { breaker_threshold: 0, ..Resilience::default() };
+
+        for _ in 0..10 {
+            breakers.failure(1, &resilience);
+        }
+        assert!(breakers.allow(1, &resilience));
+    }
+}
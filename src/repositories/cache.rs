@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Bodies of the lists fetched from the sources, keyed by source id and path (filters included).
+/// A single cache holds the lists of every resource, so that a change to a place also drops the routes of its source.
+#[derive(Default)]
+pub struct ResponseCache {
+    entries: Mutex<Entries>,
+}
+
+#[derive(Default)]
+struct Entries {
+    bodies: HashMap<(u16, String), Entry>,
+    /// Number of invalidations of each source, lists fetched before the last one are not cached.
+    generations: HashMap<u16, u64>,
+}
+
+impl Entries {
+    fn generation(&self, source_id: u16) -> u64 {
+        self.generations.get(&source_id).copied().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub body: String,
+    /// Validators given by the source, sent back to revalidate the entry.
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    fetched_at: Instant,
+}
+
+impl Entry {
+    pub fn new(body: String, etag: Option<String>, last_modified: Option<String>) -> Self {
+        Self { body, etag, last_modified, fetched_at: Instant::now() }
+    }
+
+    pub fn age(&self) -> Duration {
+        self.fetched_at.elapsed()
+    }
+}
+
+impl ResponseCache {
+    pub fn get(&self, source_id: u16, path: &str) -> Option<Entry> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.bodies.get(&(source_id, path.to_owned())).cloned()
+    }
+
+    /// To be read before asking the source for a list, and given back when caching it.
+    pub fn generation(&self, source_id: u16) -> u64 {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner).generation(source_id)
+    }
+
+    /// Caches the entry unless the source was invalidated since `generation`:
+    /// the list was asked for while something changed and may not show it.
+    pub fn insert(&self, source_id: u16, path: &str, entry: Entry, generation: u64) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if entries.generation(source_id) == generation {
+            entries.bodies.insert((source_id, path.to_owned()), entry);
+        }
+    }
+
+    /// The source confirmed that the entry did not change, unless it was invalidated since `generation`.
+    pub fn refresh(&self, source_id: u16, path: &str, generation: u64) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if entries.generation(source_id) != generation {
+            return;
+        }
+        if let Some(entry) = entries.bodies.get_mut(&(source_id, path.to_owned())) {
+            entry.fetched_at = Instant::now();
+        }
+    }
+
+    /// Drops every entry of the source, whatever the resource, along with the lists being fetched from it.
+    pub fn invalidate(&self, source_id: u16) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.bodies.retain(|(id, _), _| *id != source_id);
+        *entries.generations.entry(source_id).or_default() += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalidate() {
+        let cache = ResponseCache::default();
+        cache.insert(1, "places", Entry::new(String::from("[]"), None, None), 0);
+        cache.insert(1, "routes?tags=roof", Entry::new(String::from("[]"), None, None), 0);
+        cache.insert(2, "places", Entry::new(String::from("[]"), Some(String::from("\"v1\"")), None), 0);
+
+        cache.invalidate(1);
+
+        assert!(cache.get(1, "places").is_none());
+        assert!(cache.get(1, "routes?tags=roof").is_none());
+        assert_eq!(cache.get(2, "places").unwrap().etag.as_deref(), Some("\"v1\""));
+    }
+
+    #[test]
+    fn test_late_insert() {
+        let cache = ResponseCache::default();
+        let generation = cache.generation(1);
+        // a write to the source completes while the list is being fetched
+        cache.invalidate(1);
+        cache.insert(1, "places", Entry::new(String::from("[]"), None, None), generation);
+        assert!(cache.get(1, "places").is_none());
+
+        // lists fetched afterwards are cached again
+        cache.insert(1, "places", Entry::new(String::from("[]"), None, None), cache.generation(1));
+        assert!(cache.get(1, "places").is_some());
+        // other sources are not affected
+        cache.insert(2, "places", Entry::new(String::from("[]"), None, None), generation);
+        assert!(cache.get(2, "places").is_some());
+    }
+}
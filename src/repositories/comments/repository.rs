@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::contexts::comments::{domain, irepository};
+use crate::errors::{CreateError, DeleteError, GetAllError, GetError, UpdateError};
+
+/// Comments are kept in memory, ids are given in sequence.
+/// Clones share the same comments.
+#[derive(Clone, Default)]
+pub struct Repository {
+    store: Arc<Mutex<Store>>,
+}
+
+#[derive(Default)]
+struct Store {
+    last_id: domain::CommentId,
+    comments: BTreeMap<domain::CommentId, domain::Comment>,
+}
+
+impl Repository {
+    fn store(&self) -> std::sync::MutexGuard<'_, Store> {
+        self.store.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self, route_id: domain::RouteId) -> Result<Vec<domain::Comment>, GetAllError> {
+        Ok(self.store().comments.values().filter(|comment| comment.route_id == route_id).cloned().collect())
+    }
+
+    async fn get(&self, id: domain::CommentId) -> Result<domain::Comment, GetError> {
+        self.store().comments.get(&id).cloned().ok_or(GetError::NonExistingId(id.to_string()))
+    }
+
+    async fn get_many(&self, ids: Vec<domain::CommentId>) -> Result<Vec<domain::Comment>, GetAllError> {
+        Ok(self.store().comments.values().filter(|comment| ids.contains(&comment.id)).cloned().collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::post::IRepository for Repository {
+    async fn create(&self, comment: domain::NewComment) -> Result<domain::Comment, CreateError> {
+        let mut store = self.store();
+        store.last_id += 1;
+        let comment = domain::Comment::new(store.last_id, comment);
+        store.comments.insert(comment.id, comment.clone());
+        Ok(comment)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn update(&self, comment: domain::Comment) -> Result<(), UpdateError> {
+        match self.store().comments.get_mut(&comment.id) {
+            Some(stored) => {
+                *stored = comment;
+                Ok(())
+            },
+            None => Err(UpdateError::NonExistingId(comment.id.to_string())),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, id: domain::CommentId, date: domain::Instant) -> Result<(), DeleteError> {
+        match self.store().comments.get_mut(&id) {
+            Some(comment) => {
+                comment.delete(date);
+                Ok(())
+            },
+            None => Err(DeleteError::NonExistingId(id.to_string())),
+        }
+    }
+
+    async fn forget(&self, author: domain::Username, date: domain::Instant) -> Result<(), DeleteError> {
+        let author = author.to_lowercase();
+        for comment in self.store().comments.values_mut().filter(|comment| comment.author.to_lowercase() == author) {
+            comment.delete(date);
+        }
+        Ok(())
+    }
+
+    async fn purge(&self, id: domain::CommentId) -> Result<(), DeleteError> {
+        let mut store = self.store();
+        if store.comments.remove(&id).is_none() {
+            return Err(DeleteError::NonExistingId(id.to_string()));
+        }
+        store.comments.retain(|_, comment| comment.reply_to != Some(id));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use irepository::{get::IRepository, post::IRepository as _, put::IRepository as _, delete::IRepository as _};
+
+    fn new_comment(route_id: &str) -> domain::NewComment {
+        domain::NewComment {
+            route_id: String::from(route_id),
+            reply_to: None,
+            author: String::from("jdoe"),
+            content: String::from("Nice route"),
+            date: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get() {
+        let repo = Repository::default();
+        let first = repo.create(new_comment("0001-00000001")).await.unwrap();
+        let second = repo.create(new_comment("0001-00000002")).await.unwrap();
+        assert_ne!(first.id, second.id);
+
+        assert_eq!(repo.get_all(String::from("0001-00000001")).await.unwrap(), vec![first.clone()]);
+        assert_eq!(repo.get(second.id).await.unwrap(), second);
+        assert!(matches!(repo.get(42).await, Err(GetError::NonExistingId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete() {
+        let repo = Repository::default();
+        let mut comment = repo.create(new_comment("0001-00000001")).await.unwrap();
+
+        comment.edit(String::from("Great route"), Utc::now());
+        repo.update(comment.clone()).await.unwrap();
+        assert_eq!(repo.get(comment.id).await.unwrap(), comment);
+
+        repo.delete(comment.id, Utc::now()).await.unwrap();
+        // deleted comments are kept
+        assert!(repo.get(comment.id).await.unwrap().is_deleted());
+        assert!(matches!(repo.delete(42, Utc::now()).await, Err(DeleteError::NonExistingId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_purge() {
+        let repo = Repository::default();
+        let comment = repo.create(new_comment("0001-00000001")).await.unwrap();
+        let reply = repo.create(domain::NewComment { reply_to: Some(comment.id), ..new_comment("0001-00000001") }).await.unwrap();
+        let other = repo.create(new_comment("0001-00000001")).await.unwrap();
+
+        repo.purge(comment.id).await.unwrap();
+        assert!(matches!(repo.get(comment.id).await, Err(GetError::NonExistingId(_))));
+        assert!(matches!(repo.get(reply.id).await, Err(GetError::NonExistingId(_))));
+        assert_eq!(repo.get_all(String::from("0001-00000001")).await.unwrap(), vec![other]);
+        assert!(matches!(repo.purge(comment.id).await, Err(DeleteError::NonExistingId(_))));
+    }
+}
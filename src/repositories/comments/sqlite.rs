@@ -0,0 +1,258 @@
+use rusqlite::{params, params_from_iter, Connection, Params, Row};
+
+use crate::contexts::comments::{domain, irepository};
+use crate::errors::{CreateError, DeleteError, GetAllError, GetError, UpdateError};
+use crate::repositories::storage::Pool;
+
+/// Comments are kept in the database with their revisions, ids are given in sequence and never reused.
+#[derive(Clone)]
+pub struct Repository {
+    pool: Pool,
+}
+
+impl Repository {
+    pub fn new(pool: Pool) -> Self {
+        Repository { pool }
+    }
+}
+
+fn comment(row: &Row) -> rusqlite::Result<domain::Comment> {
+    Ok(domain::Comment {
+        id: row.get(0)?,
+        route_id: row.get(1)?,
+        reply_to: row.get(2)?,
+        author: row.get(3)?,
+        content: row.get(4)?,
+        date: row.get(5)?,
+        history: Vec::new(),
+        edited: row.get(6)?,
+        deleted: row.get(7)?,
+        hidden: row.get(8)?,
+    })
+}
+
+/// The comments matching the condition on their columns, with their history.
+fn select(connection: &Connection, condition: &str, values: impl Params) -> rusqlite::Result<Vec<domain::Comment>> {
+    let mut comments: Vec<domain::Comment> = connection
+        .prepare(&format!("SELECT id, route_id, reply_to, author, content, date, edited, deleted, hidden FROM comments WHERE {condition} ORDER BY id"))?
+        .query_map(values, comment)?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut revisions = connection.prepare("SELECT content, date FROM revisions WHERE comment_id = ?1 ORDER BY position")?;
+    for comment in &mut comments {
+        comment.history = revisions
+            .query_map([comment.id], |row| Ok(domain::Revision { content: row.get(0)?, date: row.get(1)? }))?
+            .collect::<rusqlite::Result<_>>()?;
+    }
+    Ok(comments)
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self, route_id: domain::RouteId) -> Result<Vec<domain::Comment>, GetAllError> {
+        self.pool.run(move |connection| select(connection, "route_id = ?1", [route_id]))
+            .await.map_err(|_| GetAllError::InternalServerError)
+    }
+
+    async fn get(&self, id: domain::CommentId) -> Result<domain::Comment, GetError> {
+        let comments = self.pool.run(move |connection| select(connection, "id = ?1", [id]))
+            .await.map_err(|_| GetError::InternalServerError)?;
+
+        comments.into_iter().next().ok_or(GetError::NonExistingId(id.to_string()))
+    }
+
+    async fn get_many(&self, ids: Vec<domain::CommentId>) -> Result<Vec<domain::Comment>, GetAllError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.pool.run(move |connection| {
+            let placeholders = vec!["?"; ids.len()].join(", ");
+            select(connection, &format!("id IN ({placeholders})"), params_from_iter(ids))
+        }).await.map_err(|_| GetAllError::InternalServerError)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::post::IRepository for Repository {
+    async fn create(&self, comment: domain::NewComment) -> Result<domain::Comment, CreateError> {
+        self.pool.run(move |connection| {
+            connection.execute(
+                "INSERT INTO comments (route_id, reply_to, author, content, date, hidden) VALUES (?1, ?2, ?3, ?4, ?5, FALSE)",
+                params![comment.route_id, comment.reply_to, comment.author, comment.content, comment.date],
+            )?;
+            Ok(domain::Comment::new(connection.last_insert_rowid() as domain::CommentId, comment))
+        }).await.map_err(|_| CreateError::InternalServerError)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn update(&self, comment: domain::Comment) -> Result<(), UpdateError> {
+        let id = comment.id;
+        let updated = self.pool.run(move |connection| {
+            let transaction = connection.transaction()?;
+            let updated = transaction.execute(
+                "UPDATE comments SET content = ?2, edited = ?3, deleted = ?4, hidden = ?5 WHERE id = ?1",
+                params![comment.id, comment.content, comment.edited, comment.deleted, comment.hidden],
+            )?;
+            if updated != 0 {
+                transaction.execute("DELETE FROM revisions WHERE comment_id = ?1", [comment.id])?;
+                for (position, revision) in comment.history.iter().enumerate() {
+                    transaction.execute(
+                        "INSERT INTO revisions (comment_id, position, content, date) VALUES (?1, ?2, ?3, ?4)",
+                        params![comment.id, position, revision.content, revision.date],
+                    )?;
+                }
+            }
+            transaction.commit()?;
+            Ok(updated)
+        }).await.map_err(|_| UpdateError::InternalServerError)?;
+
+        match updated {
+            0 => Err(UpdateError::NonExistingId(id.to_string())),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, id: domain::CommentId, date: domain::Instant) -> Result<(), DeleteError> {
+        let deleted = self.pool.run(move |connection| {
+            // the first deletion date is kept
+            connection.execute("UPDATE comments SET deleted = COALESCE(deleted, ?2) WHERE id = ?1", params![id, date])
+        }).await.map_err(|_| DeleteError::InternalServerError)?;
+
+        match deleted {
+            0 => Err(DeleteError::NonExistingId(id.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    async fn forget(&self, author: domain::Username, date: domain::Instant) -> Result<(), DeleteError> {
+        let author = author.to_lowercase();
+        self.pool.run(move |connection| {
+            let transaction = connection.transaction()?;
+            // usernames are compared regardless of case the way Rust lowercases them, which SQLite does not know
+            let ids: Vec<domain::CommentId> = transaction.prepare("SELECT id, author FROM comments WHERE deleted IS NULL")?
+                .query_map([], |row| Ok((row.get(0)?, row.get::<_, String>(1)?)))?
+                .filter_map(|comment| match comment {
+                    Ok((_, written_by)) if written_by.to_lowercase() != author => None,
+                    comment => Some(comment.map(|(id, _)| id)),
+                })
+                .collect::<rusqlite::Result<_>>()?;
+            for id in ids {
+                transaction.execute("UPDATE comments SET deleted = ?2 WHERE id = ?1", params![id, date])?;
+            }
+            transaction.commit()
+        }).await.map_err(|_| DeleteError::InternalServerError)
+    }
+
+    async fn purge(&self, id: domain::CommentId) -> Result<(), DeleteError> {
+        let purged = self.pool.run(move |connection| {
+            let transaction = connection.transaction()?;
+            let purged = transaction.execute("DELETE FROM comments WHERE id = ?1", [id])?;
+            if purged != 0 {
+                transaction.execute("DELETE FROM comments WHERE reply_to = ?1", [id])?;
+            }
+            transaction.commit()?;
+            Ok(purged)
+        }).await.map_err(|_| DeleteError::InternalServerError)?;
+
+        match purged {
+            0 => Err(DeleteError::NonExistingId(id.to_string())),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::repositories::storage;
+    use irepository::{get::IRepository, post::IRepository as _, put::IRepository as _, delete::IRepository as _};
+
+    fn at(minute: u32) -> domain::Instant {
+        Utc.with_ymd_and_hms(2023, 5, 1, 12, minute, 0).unwrap()
+    }
+
+    fn new_comment(route_id: &str) -> domain::NewComment {
+        domain::NewComment {
+            route_id: String::from(route_id),
+            reply_to: None,
+            author: String::from("jdoe"),
+            content: String::from("Nice route"),
+            date: at(0),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_update_and_delete() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        let mut comment = repo.create(new_comment("0001-00000001")).await.unwrap();
+        let other = repo.create(new_comment("0001-00000002")).await.unwrap();
+        assert_ne!(comment.id, other.id);
+        assert_eq!(repo.get_all(String::from("0001-00000001")).await.unwrap(), vec![comment.clone()]);
+
+        comment.edit(String::from("Great route"), at(1));
+        comment.edit(String::from("Great route!"), at(2));
+        comment.hidden = true;
+        repo.update(comment.clone()).await.unwrap();
+        assert_eq!(repo.get(comment.id).await.unwrap(), comment);
+
+        repo.delete(comment.id, at(3)).await.unwrap();
+        repo.delete(comment.id, at(4)).await.unwrap();
+        assert_eq!(repo.get(comment.id).await.unwrap().deleted, Some(at(3)));
+        assert!(matches!(repo.get(42).await, Err(GetError::NonExistingId(_))));
+        assert!(matches!(repo.delete(42, at(5)).await, Err(DeleteError::NonExistingId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_many() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        let first = repo.create(new_comment("0001-00000001")).await.unwrap();
+        repo.create(new_comment("0001-00000001")).await.unwrap();
+        let mut third = repo.create(new_comment("0001-00000002")).await.unwrap();
+        third.edit(String::from("Great route"), at(1));
+        repo.update(third.clone()).await.unwrap();
+
+        assert_eq!(repo.get_many(vec![third.id, first.id, 42]).await.unwrap(), vec![first, third]);
+        assert!(repo.get_many(Vec::new()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_forget() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        let comment = repo.create(domain::NewComment { author: String::from("Émile"), ..new_comment("0001-00000001") }).await.unwrap();
+        let other = repo.create(new_comment("0001-00000001")).await.unwrap();
+
+        repo.forget(String::from("éMILE"), at(1)).await.unwrap();
+        assert_eq!(repo.get(comment.id).await.unwrap().deleted, Some(at(1)));
+        assert_eq!(repo.get(other.id).await.unwrap().deleted, None);
+    }
+
+    #[tokio::test]
+    async fn test_purge() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        let mut comment = repo.create(new_comment("0001-00000001")).await.unwrap();
+        comment.edit(String::from("Great route"), at(1));
+        repo.update(comment.clone()).await.unwrap();
+        let reply = repo.create(domain::NewComment { reply_to: Some(comment.id), ..new_comment("0001-00000001") }).await.unwrap();
+        let other = repo.create(new_comment("0001-00000001")).await.unwrap();
+
+        repo.purge(comment.id).await.unwrap();
+        assert!(matches!(repo.get(comment.id).await, Err(GetError::NonExistingId(_))));
+        assert!(matches!(repo.get(reply.id).await, Err(GetError::NonExistingId(_))));
+        assert_eq!(repo.get_all(String::from("0001-00000001")).await.unwrap(), vec![other.clone()]);
+        assert!(matches!(repo.purge(comment.id).await, Err(DeleteError::NonExistingId(_))));
+
+        // ids of purged comments are not given again
+        assert!(repo.create(new_comment("0001-00000001")).await.unwrap().id > other.id);
+    }
+}
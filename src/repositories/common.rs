@@ -1,11 +1,22 @@
+use super::breaker::Breakers;
+use super::cache::{Entry, ResponseCache};
 use super::config::{Config, Source};
+use super::health::{Checks, Monitor};
+use super::schemas::Validated;
 use futures::stream::StreamExt;
-use reqwest::{self, StatusCode};
+use rand::Rng;
+use reqwest::{self, header, StatusCode};
+use serde_json::Value;
+use std::fmt;
+use urlencoding::encode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 const CONCURRENT_REQUESTS: usize = 10;
 
 pub type FilterList = Vec<(String, String)>;
 /// Incorporate filters of the list in the path to form an HTTP request.
+/// Keys and values are percent-encoded, the path may already have a query.
 /// 
 /// Ex: path?name1=value1&name2=value2
 pub fn path_with_filters(path: &str, filters_list: FilterList) -> String
@@ -13,44 +24,37 @@ pub fn path_with_filters(path: &str, filters_list: FilterList) -> String
     let mut complete_path = String::from(path);
     if ! filters_list.is_empty()
     { // Add the given filters to the path
-        complete_path.push('?');
+        complete_path.push(if path.contains('?') { '&' } else { '?' });
         let mut filters_iter = filters_list.iter();
         let (key, value) = filters_iter.next().unwrap(); // It is safe to call unwrap because we already checked that there is at least one element
-        complete_path.push_str(&format!("{key}={value}"));
+        complete_path.push_str(&format!("{}={}", encode(key), encode(value)));
         
         for (key, value) in filters_iter
         {
             complete_path.push('&');
-            complete_path.push_str(&format!("{key}={value}"));
+            complete_path.push_str(&format!("{}={}", encode(key), encode(value)));
         }
     }
 
     complete_path
 }
 
-
-pub struct RelativeId {
-    pub source_id: u16,
-    pub resource_id: u32,
-}
-
-impl RelativeId {
-    /// Parses a string of the form `FFFF-FFFFFFFF` into a `RelativeId`.
-    pub fn from_str(id: &str) -> Self {
-        let mut parts = id.split('-');
-        let source_id = u16::from_str_radix(parts.next().unwrap(), 16).expect("Failed to parse source id");
-        let resource_id = u32::from_str_radix(parts.next().unwrap(), 16).expect("Failed to parse resource id");
-        Self {
-            source_id,
-            resource_id,
-        }
-    }
-
-    pub fn to_string(&self) -> String {
-        format!("{:04X}-{:08X}", self.source_id, self.resource_id)
+/// Reads an id some sources give as a string and others as an integer.
+pub fn string_or_integer<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Id {
+        String(String),
+        Integer(u64),
     }
+    Ok(match serde::Deserialize::deserialize(deserializer)? {
+        Id::String(id) => id,
+        Id::Integer(id) => id.to_string(),
+    })
 }
 
+pub use crate::typeutil::ids::RelativeId;
+
 pub trait Identifiable {
     fn id(&mut self) -> &mut String;
 }
@@ -66,11 +70,43 @@ macro_rules! impl_identifiable_for {
 }
 pub(crate) use impl_identifiable_for;
 
+// Some causes are only read through `Debug` when logging
 #[derive(Debug)]
 pub enum FetchError {
-    Serialization(serde_json::Error),
+    Serialization(#[allow(dead_code)] serde_json::Error),
     Networking(reqwest::Error),
-    Internal,
+    /// The source answered with an unexpected status code.
+    Status(StatusCode),
+    /// No configured source has this id.
+    UnknownSource(#[allow(dead_code)] u16),
+    /// The source answered with an id that is not a 32bit integer.
+    BadUpstreamId(String),
+    /// The source failed too many times recently and is skipped.
+    CircuitOpen,
+    /// The last probe of the source failed and it is skipped.
+    Down,
+    /// The items answered by the source break their schema.
+    Invalid(Vec<Rejection>),
+}
+
+/// An item answered by a source that breaks its schema, with the id the source gave it, if any.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejection {
+    pub id: Option<String>,
+    pub violations: Vec<String>,
+}
+
+impl Rejection {
+    fn new(item: &Value, violations: Vec<String>) -> Self {
+        let id = item.get("id").map(|id| id.as_str().map(String::from).unwrap_or_else(|| id.to_string()));
+        Self { id, violations }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "item `{}`: {}", self.id.as_deref().unwrap_or("?"), self.violations.join(", "))
+    }
 }
 
 impl From<serde_json::Error> for FetchError {
@@ -85,57 +121,183 @@ impl From<reqwest::Error> for FetchError {
     }
 }
 
+/// What the managers of every resource share: the configuration, the HTTP client (and its connection pool),
+/// the circuit breakers, since a source that fails for places fails for routes too, the outcomes of the probes
+/// and the cache, since a change to a place may change the routes of its source.
+/// Clones share the same client, breakers, probes and cache.
+#[derive(Clone)]
+pub struct Upstream {
+    config: Arc<Config>,
+    client: reqwest::Client,
+    breakers: Arc<Breakers>,
+    monitor: Arc<Monitor>,
+    cache: Arc<ResponseCache>,
+}
+
+impl Upstream {
+    pub fn new(config: Config, client: reqwest::Client) -> Self {
+        Self { config: Arc::new(config), client, breakers: Arc::default(), monitor: Arc::default(), cache: Arc::default() }
+    }
+
+    /// The sources of the configuration file at `CLIMBHUB_CONFIG`.
+    pub fn from_env() -> Self {
+        Self::new(Config::from_env(), reqwest::Client::new())
+    }
+
+    pub fn sources(&self) -> &[Source] {
+        &self.config.sources
+    }
+
+    /// What the probes of the source found out, None until the first one completes.
+    pub fn checks(&self, source_id: u16) -> Option<Checks> {
+        self.monitor.get(source_id)
+    }
+
+    /// Requests the `probe_path` of the source within its timeout and records the outcome.
+    /// Probes are neither retried nor counted by the circuit breaker, they go through while it is open.
+    pub async fn probe(&self, source: &Source) {
+        let url = format!("{}/{}", source.url, source.probing.probe_path);
+        let start = Instant::now();
+        let outcome = match self.client.get(&url).timeout(Duration::from_millis(source.resilience.timeout_ms)).send().await {
+            Ok(response) if response.status().is_success() => Ok(()),
+            Ok(response) => Err(FetchError::Status(response.status())),
+            Err(error) => Err(FetchError::from(error)),
+        };
+        if let Err(error) = &outcome {
+            eprintln!("Probe of {} failed: {:?}", source.name, error);
+        }
+        self.monitor.record(source.id, start.elapsed(), outcome.map_err(|error| repository_to_domain::error_kind(&error)));
+    }
+
+    /// Probes each source every `probe_interval_ms`, forever: meant to be spawned once the server runs.
+    pub async fn probe_forever(self) {
+        let upstream = &self;
+        let probes = self.sources().iter()
+            .filter(|source| source.probing.probe_interval_ms > 0)
+            .map(|source| async move {
+                let mut interval = tokio::time::interval(Duration::from_millis(source.probing.probe_interval_ms));
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    interval.tick().await;
+                    upstream.probe(source).await;
+                }
+            });
+        futures::future::join_all(probes).await;
+    }
+}
+
+/// Requests the sources for one resource, whose lists it caches.
 pub struct Manager<T> {
-    config: Config,
+    config: Arc<Config>,
     client: reqwest::Client,
+    breakers: Arc<Breakers>,
+    monitor: Arc<Monitor>,
+    cache: Arc<ResponseCache>,
     _phantom: std::marker::PhantomData<T>,
 }
 
-impl<T: Identifiable + serde::de::DeserializeOwned + serde::Serialize> Manager<T> {
+impl<T: Identifiable + Validated + serde::de::DeserializeOwned + serde::Serialize> Manager<T> {
+    /// A manager that shares nothing with the others.
+    #[cfg(test)]
     pub fn new(config: Config, client: reqwest::Client) -> Self {
+        Self::shared(&Upstream::new(config, client))
+    }
+
+    /// A manager on the client, breakers, probes and cache of `upstream`.
+    pub fn shared(upstream: &Upstream) -> Self {
         Self {
-            config,
-            client,
+            config: upstream.config.clone(),
+            client: upstream.client.clone(),
+            breakers: upstream.breakers.clone(),
+            monitor: upstream.monitor.clone(),
+            cache: upstream.cache.clone(),
             _phantom: std::marker::PhantomData,
         }
     }
 
-    fn to_absolute(&self, object: &mut T, source_id: u16) {
-        *object.id() = RelativeId {
-            source_id,
-            resource_id: object.id().parse::<u32>().expect("Failed to parse resource id"),
-        }.to_string();
+    /// Sends the request built by `request` to the source within its timeout.
+    /// Idempotent requests are retried after a failure with a jittered exponential backoff.
+    /// Failures are counted by the circuit breaker of the source, which rejects requests while open.
+    async fn send(&self, source: &Source, idempotent: bool, request: impl Fn() -> reqwest::RequestBuilder) -> Result<reqwest::Response, FetchError> {
+        let resilience = &source.resilience;
+        if !self.breakers.allow(source.id, resilience) {
+            return Err(FetchError::CircuitOpen);
+        }
+
+        let attempts = if idempotent { resilience.retries + 1 } else { 1 };
+        let mut attempt = 1;
+        loop {
+            let result = request().timeout(Duration::from_millis(resilience.timeout_ms)).send().await;
+            let failed = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(_) => true,
+            };
+
+            if !failed {
+                self.breakers.success(source.id);
+                return Ok(result?);
+            }
+            if attempt >= attempts {
+                self.breakers.failure(source.id, resilience);
+                return Ok(result?);
+            }
+            eprintln!("Attempt {attempt}/{attempts} to {} failed, retrying", source.name);
+            tokio::time::sleep(backoff(resilience.backoff_ms, attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    fn to_absolute(&self, object: &mut T, source_id: u16) -> Result<(), FetchError> {
+        let resource_id = object.id().parse::<u32>().map_err(|_| FetchError::BadUpstreamId(object.id().clone()))?;
+        *object.id() = RelativeId { source_id, resource_id }.to_string();
+        Ok(())
+    }
+
+    /// Reads the item once it is checked against the schema of `T`.
+    fn read(item: Value) -> Result<T, Rejection> {
+        if let Err(violations) = T::schema().check(&item) {
+            return Err(Rejection::new(&item, violations));
+        }
+        serde_json::from_value(item.clone()).map_err(|error| Rejection::new(&item, vec![error.to_string()]))
+    }
+
+    /// Reads the single object of the body with an absolute id.
+    fn parse_object(&self, source_id: u16, body: &str) -> Result<T, FetchError> {
+        let mut object = Self::read(serde_json::from_str(body)?).map_err(|rejection| FetchError::Invalid(vec![rejection]))?;
+        self.to_absolute(&mut object, source_id)?;
+        Ok(object)
     }
 
-    fn to_relative(&self, object: &mut T) {
-        *object.id() = RelativeId::from_str(&object.id()).resource_id.to_string();
+    fn source(&self, source_id: u16) -> Result<&Source, FetchError> {
+        self.config.get_source(source_id).ok_or(FetchError::UnknownSource(source_id))
     }
 
     pub async fn get(&self, source_id: u16, path: &str) -> Result<Option<T>, FetchError> 
     {
-        let source = self.config.get_source(source_id).expect(&format!("Couldn't find a source with id {}", source_id));
+        let source = self.source(source_id)?;
         let url = format!("{}/{}", source.url, path);
-        let response = self.client.get(&url).send().await?; 
+        let response = self.send(source, true, || self.client.get(&url)).await?; 
         
         match response.status() 
         {
-            ref status if status.is_success() => 
-            {
-                let mut object: T = serde_json::from_str(response.text().await?.as_str())?;
-                self.to_absolute(&mut object, source_id);
-                Ok(Some(object))
-            },
+            ref status if status.is_success() => Ok(Some(self.parse_object(source_id, &response.text().await?)?)),
             StatusCode::NOT_FOUND => Ok(None),
-            _ => Err(FetchError::Internal),
+            status => Err(FetchError::Status(status)),
         }
     }
 
-    pub async fn post(&self, source_id: u16, path: &str, object: &mut T, ) -> Result<T, FetchError> {
-        let source = self.config.get_source(source_id).expect(&format!("Couldn't find a source with id {}", source_id));
+    /// Sends `data` to the given source and returns the created object with an absolute id.
+    pub async fn post<D: serde::Serialize>(&self, source_id: u16, path: &str, data: &D) -> Result<T, FetchError> {
+        let source = self.source(source_id)?;
         let url = format!("{}/{}", source.url, path);
-        let response = self.client.post(&url).json(object).send().await;
+        let response = self.send(source, false, || self.client.post(&url).json(data)).await;
+        self.cache.invalidate(source.id);
 
         let body = match response {
+            Ok(response) if !response.status().is_success() => {
+                eprintln!("Error: POST {url} ({}) returned {}", source.name, response.status());
+                return Err(FetchError::Status(response.status()));
+            },
             Ok(response) => {
                 match response.text().await {
                     Ok(body) => body,
@@ -146,103 +308,624 @@ impl<T: Identifiable + serde::de::DeserializeOwned + serde::Serialize> Manager<T
                 }
             },
             Err(error) => {
-                eprintln!("Error: {}", error);
-                return Err(FetchError::from(error))
+                eprintln!("Error: {:?}", error);
+                return Err(error)
             }
         };
 
-        let mut object: T = match serde_json::from_str(&body) {
-            Ok(object) => object,
+        self.parse_object(source.id, &body).inspect_err(|error| eprintln!("Deserialization error: {:?}", error))
+    }
+
+    /// Sends `data` to the given source to replace the object at `path`.
+    pub async fn put<D: serde::Serialize>(&self, source_id: u16, path: &str, data: &D) -> Result<StatusCode, FetchError> {
+        let source = self.source(source_id)?;
+        let url = format!("{}/{}", source.url, path);
+        let response = self.send(source, false, || self.client.put(&url).json(data)).await;
+        self.cache.invalidate(source.id);
+        match response {
+            Ok(response) => Ok(response.status()),
             Err(error) => {
-                eprintln!("Deserialization error: {}", error);
-                return Err(FetchError::from(error));
+                eprintln!("Error: {:?}", error);
+                Err(error)
             }
-        };
-
-        self.to_absolute(&mut object, source.id);
-        Ok(object)
+        }
     }
 
     pub async fn delete(&self, source_id: u16, path: &str) -> Result<StatusCode, FetchError> {
-        let source = self.config.get_source(source_id).expect(&format!("Couldn't find a source with id {}", source_id));
+        let source = self.source(source_id)?;
         let url = format!("{}/{}", source.url, path);
-        match self.client.delete(&url).send().await {
+        let response = self.send(source, true, || self.client.delete(&url)).await;
+        self.cache.invalidate(source.id);
+        match response {
             Ok(response) => Ok(response.status()),
             Err(error) => {
-                eprintln!("Error: {}", error);
-                Err(FetchError::from(error))
+                eprintln!("Error: {:?}", error);
+                Err(error)
             }
         }
     }
 
-    async fn get_objects(&self, source: Source, path: &str) -> Result<Vec<T>, FetchError> {
-        let client = &self.client;
+    /// Body of the list at `path` on the source.
+    /// It is taken from the cache while younger than the TTL of the source,
+    /// and revalidated with the validators given by the source once expired.
+    /// A body fetched while something changed on the source is not cached.
+    async fn get_list_body(&self, source: &Source, path: &str) -> Result<String, FetchError> {
+        let ttl = Duration::from_millis(source.caching.cache_ttl_ms);
+        let generation = self.cache.generation(source.id);
+        let cached = self.cache.get(source.id, path);
+        if let Some(entry) = &cached {
+            if entry.age() < ttl {
+                return Ok(entry.body.clone());
+            }
+        }
+
         let url = format!("{}/{}", source.url, path);
         println!("GET {url}");
-        let response = client.get(&url).send().await;
-        let body = match response {
-            Ok(response) => response.text().await,
+        let request = || {
+            let mut request = self.client.get(&url);
+            if let Some(entry) = &cached {
+                if let Some(etag) = &entry.etag {
+                    request = request.header(header::IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = &entry.last_modified {
+                    request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+            request
+        };
+        let response = match self.send(source, true, request).await {
+            Ok(response) => response,
             Err(error) => {
-                eprintln!("Error: {}", error);
-                Err(error)
+                eprintln!("Error: {:?}", error);
+                return Err(error);
             }
         };
-        match body {
-            Ok(body) => match serde_json::from_str(&body) {
-                Ok(mut objects) => {
-                    for object in &mut objects {
-                        self.to_absolute(object, source.id);
+
+        match (response.status(), cached) {
+            (StatusCode::NOT_MODIFIED, Some(entry)) => {
+                self.cache.refresh(source.id, path, generation);
+                Ok(entry.body)
+            },
+            (status, _) if !status.is_success() => {
+                eprintln!("Error: GET {url} ({}) returned {}", source.name, status);
+                Err(FetchError::Status(status))
+            },
+            _ => {
+                let validator = |name| response.headers().get(name).and_then(|value| value.to_str().ok()).map(String::from);
+                let (etag, last_modified) = (validator(header::ETAG), validator(header::LAST_MODIFIED));
+                let body = match response.text().await {
+                    Ok(body) => body,
+                    Err(error) => {
+                        eprintln!("Error: {}", error);
+                        return Err(FetchError::from(error));
                     }
-                    Ok(objects)
+                };
+                if !ttl.is_zero() {
+                    self.cache.insert(source.id, path, Entry::new(body.clone(), etag, last_modified), generation);
                 }
-                Err(error) => {
-                    eprintln!("Deserialization error: {}", error);
-                    Err(FetchError::from(error))
+                Ok(body)
+            },
+        }
+    }
+
+    /// Objects of the list with absolute ids, along with the items that break their schema or have an invalid id.
+    /// These are dropped one at a time, unless the source is strict about its schema and the whole list is rejected.
+    fn parse_objects(&self, source: &Source, body: &str) -> Result<(Vec<T>, Vec<Rejection>), FetchError> {
+        let items: Vec<Value> = serde_json::from_str(body).map_err(|error| {
+            eprintln!("Deserialization error: {}", error);
+            FetchError::from(error)
+        })?;
+
+        let (mut objects, mut rejections) = (Vec::new(), Vec::new());
+        for item in items {
+            let object = Self::read(item).and_then(|mut object| match self.to_absolute(&mut object, source.id) {
+                Ok(()) => Ok(object),
+                Err(_) => Err(Rejection { id: Some(object.id().clone()), violations: vec![String::from("/id: not a 32bit integer")] }),
+            });
+            match object {
+                Ok(object) => objects.push(object),
+                Err(rejection) => {
+                    eprintln!("{} answered an invalid {rejection}", source.name);
+                    rejections.push(rejection);
                 }
             }
-            Err(error) => {
-                eprintln!("Error: {}", error);
-                Err(FetchError::from(error))
-            }
         }
+
+        if source.strict_schema && !rejections.is_empty() {
+            return Err(FetchError::Invalid(rejections));
+        }
+        Ok((objects, rejections))
     }
 
-    pub async fn dispatch(&self, path: &str) -> (Vec<T>, Vec<FetchError>) {
+    async fn get_objects(&self, source: &Source, path: &str) -> Result<(Vec<T>, Vec<Rejection>), FetchError> {
+        let body = self.get_list_body(source, path).await?;
+        let objects = self.parse_objects(source, &body);
+        if objects.is_err() {
+            self.cache.invalidate(source.id);
+        }
+        objects
+    }
+
+    /// Objects of a previous answer of the source, if it is not older than the maximum staleness.
+    fn stale_objects(&self, source: &Source, path: &str) -> Option<(Vec<T>, Vec<Rejection>)> {
+        let entry = self.cache.get(source.id, path)?;
+        if entry.age() > Duration::from_millis(source.caching.cache_max_stale_ms) {
+            return None;
+        }
+        self.parse_objects(source, &entry.body).ok()
+    }
+
+    /// Fetches `path` from every source and merges the objects they returned.
+    /// The `pagination` parameters are only added for the sources that support them.
+    /// Sources that are skipped when down are not requested while their last probe failed.
+    /// A report is given for each source, failing or not.
+    pub async fn dispatch(&self, path: &str, pagination: FilterList) -> (Vec<T>, Vec<SourceReport>) {
+        let pagination = &pagination;
         let results = futures::stream::iter(self.config.sources.clone())
             // create a stream of futures
-            .map(|source| async { self.get_objects(source, path) })
+            .map(|source| async move {
+                let path = match source.pagination {
+                    true => path_with_filters(path, pagination.clone()),
+                    false => path.to_owned(),
+                };
+                let path = path.as_str();
+                let start = Instant::now();
+                let objects = match source.skip_when_down && self.monitor.is_down(source.id) {
+                    true => Err(FetchError::Down),
+                    false => self.get_objects(&source, path).await,
+                };
+                let ((objects, rejected), error, stale) = match objects {
+                    Ok(objects) => (objects, None, false),
+                    // serve the last known objects rather than nothing
+                    Err(error) => match self.stale_objects(&source, path) {
+                        Some(objects) => (objects, Some(error), true),
+                        None => ((Vec::new(), Vec::new()), Some(error), false),
+                    },
+                };
+                (SourceReport { source, latency: start.elapsed(), error, stale, rejected }, objects)
+            })
             // execute the futures concurrently
             .buffer_unordered(CONCURRENT_REQUESTS);
 
         // merges the Vec<T> from the different sources into a single Vec<T>
-        let (successes, failures): (Vec<T>, Vec<FetchError>) = results
-            .fold((Vec::new(), Vec::new()), |mut acc, list| async {
-                match list.await {
-                    Ok(list) => acc.0.extend(list),
-                    Err(error) => acc.1.push(error),
-                }
+        let (objects, mut reports): (Vec<T>, Vec<SourceReport>) = results
+            .fold((Vec::new(), Vec::new()), |mut acc, (report, objects)| async move {
+                acc.0.extend(objects);
+                acc.1.push(report);
                 acc
             })
             .await;
 
-        (successes, failures)
+        reports.sort_by_key(|report| report.source.id);
+        (objects, reports)
+    }
+}
+
+/// Delay before the given retry (starting at 1): `base_ms` doubled on each retry,
+/// with a random jitter of up to half the delay so that retries of concurrent requests spread out.
+fn backoff(base_ms: u64, attempt: u32) -> Duration {
+    let delay = base_ms.saturating_mul(1 << (attempt - 1).min(16));
+    Duration::from_millis(rand::thread_rng().gen_range(delay / 2..=delay))
+}
+
+/// Outcome of the request sent to a single source by [`Manager::dispatch`].
+#[derive(Debug)]
+pub struct SourceReport {
+    pub source   : Source,
+    pub latency  : Duration,
+    pub error    : Option<FetchError>,
+    /// The source failed and its objects come from a previous answer.
+    pub stale    : bool,
+    /// Items of the answer that were dropped, the rejections of a strict source being in its error.
+    pub rejected : Vec<Rejection>,
+}
+
+pub mod repository_to_domain {
+    use super::{FetchError, Rejection, SourceReport};
+    use crate::contexts::sources::domain;
+    use crate::errors::{CreateError, DeleteError, GetError, SourceFailure, UpdateError};
+
+    pub fn get_error(e: FetchError, source_id: u16, id: String) -> GetError {
+        match e {
+            FetchError::UnknownSource(_) => GetError::UnknownSource(id),
+            FetchError::BadUpstreamId(upstream_id) => GetError::BadUpstreamId(upstream_id),
+            cause => GetError::Source(SourceFailure { source_id, cause }),
+        }
+    }
+
+    pub fn create_error(e: FetchError, source_id: u16, place_id: String) -> CreateError {
+        match e {
+            FetchError::UnknownSource(_) => CreateError::UnknownSource(place_id),
+            FetchError::BadUpstreamId(upstream_id) => CreateError::BadUpstreamId(upstream_id),
+            cause => CreateError::Source(SourceFailure { source_id, cause }),
+        }
+    }
+
+    pub fn update_error(e: FetchError, source_id: u16, id: String) -> UpdateError {
+        match e {
+            FetchError::UnknownSource(_) => UpdateError::UnknownSource(id),
+            cause => UpdateError::Source(SourceFailure { source_id, cause }),
+        }
+    }
+
+    pub fn delete_error(e: FetchError, source_id: u16, id: String) -> DeleteError {
+        match e {
+            FetchError::UnknownSource(_) => DeleteError::UnknownSource(id),
+            cause => DeleteError::Source(SourceFailure { source_id, cause }),
+        }
+    }
+
+    pub fn source_status(r: SourceReport) -> domain::SourceStatus {
+        let rejected = match &r.error {
+            Some(FetchError::Invalid(rejections)) => rejections.clone(),
+            _ => r.rejected,
+        };
+        domain::SourceStatus {
+            id       : r.source.id,
+            name     : r.source.name,
+            latency  : r.latency,
+            error    : r.error.map(|e| error_kind(&e)),
+            stale    : r.stale,
+            rejected : rejected.into_iter().map(rejection).collect(),
+        }
+    }
+
+    fn rejection(r: Rejection) -> domain::Rejection {
+        domain::Rejection { id: r.id, violations: r.violations }
+    }
+
+    pub fn error_kind(e: &FetchError) -> domain::ErrorKind {
+        match e {
+            FetchError::Networking(e) if e.is_timeout() => domain::ErrorKind::Timeout,
+            FetchError::Networking(e) if e.is_decode() => domain::ErrorKind::InvalidPayload,
+            FetchError::Networking(_) => domain::ErrorKind::Unreachable,
+            FetchError::Serialization(_) => domain::ErrorKind::InvalidPayload,
+            FetchError::Status(status) => domain::ErrorKind::Status(status.as_u16()),
+            FetchError::BadUpstreamId(_) => domain::ErrorKind::InvalidPayload,
+            FetchError::Invalid(_) => domain::ErrorKind::InvalidPayload,
+            FetchError::CircuitOpen => domain::ErrorKind::CircuitOpen,
+            FetchError::Down => domain::ErrorKind::Down,
+            FetchError::UnknownSource(_) => domain::ErrorKind::Internal,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::repositories::config::{Caching, Probing, Resilience};
+    use crate::repositories::schemas::Schema;
+    use std::sync::OnceLock;
 
-    #[test]
-    fn test_from_str() {
-        let id = RelativeId::from_str("0199-58B583BD");
-        assert_eq!(id.source_id, 409);
-        assert_eq!(id.resource_id, 1488290749);
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    struct Object { id: String }
+    impl_identifiable_for!(Object);
+
+    impl Validated for Object {
+        fn schema() -> &'static Schema {
+            static SCHEMA: OnceLock<Schema> = OnceLock::new();
+            SCHEMA.get_or_init(|| Schema::compile(r#"{ "type": "object", "properties": { "id": { "type": "string" } }, "required": ["id"] }"#))
+        }
+    }
+
+    fn source(id: u16, name: &str, url: String, resilience: Resilience) -> Source {
+        Source { id, name: String::from(name), url, resilience, caching: Caching::default(), probing: Probing::default(), pagination: false, strict_schema: false, skip_when_down: false }
+    }
+
+    fn fast(retries: u32, breaker_threshold: u32, breaker_cooldown_ms: u64) -> Resilience {
+        Resilience { timeout_ms: 1000, retries, backoff_ms: 1, breaker_threshold, breaker_cooldown_ms }
     }
 
     #[test]
-    fn test_to_string() {
-        let id = RelativeId::from_str("0199-58B583BD");
-        assert_eq!(id.to_string(), "0199-58B583BD");
+    fn test_path_with_filters() {
+        assert_eq!(path_with_filters("places", FilterList::new()), "places");
+        assert_eq!(
+            path_with_filters("places", vec![
+                (String::from("country"), String::from("France")),
+                (String::from("city"), String::from("Gif sur Yvette")),
+            ]),
+            "places?country=France&city=Gif%20sur%20Yvette"
+        );
+        assert_eq!(
+            path_with_filters("places?country=France", vec![(String::from("limit"), String::from("10"))]),
+            "places?country=France&limit=10"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_reports() {
+        let mut ok_server = mockito::Server::new_async().await;
+        ok_server.mock("GET", "/objects").with_status(200).with_body(r#"[{ "id": "1" }, { "id": "2" }]"#).create_async().await;
+        let mut failing_server = mockito::Server::new_async().await;
+        failing_server.mock("GET", "/objects").with_status(503).create_async().await;
+
+        let config = Config {
+            sources: vec![
+                source(2, "Failing", failing_server.url(), Resilience::default()),
+                source(1, "Ok", ok_server.url(), Resilience::default()),
+            ],
+        };
+        let manager = Manager::<Object>::new(config, reqwest::Client::new());
+        let (objects, reports) = manager.dispatch("objects", FilterList::new()).await;
+
+        assert_eq!(objects.len(), 2);
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].source.id, 1);
+        assert!(reports[0].error.is_none());
+        assert_eq!(reports[1].source.name, "Failing");
+        assert!(matches!(reports[1].error, Some(FetchError::Status(StatusCode::SERVICE_UNAVAILABLE))));
+
+        let status = repository_to_domain::source_status(reports.into_iter().nth(1).unwrap());
+        assert_eq!(status.id, 2);
+        assert!(matches!(status.error, Some(crate::contexts::sources::domain::ErrorKind::Status(503))));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_drops_invalid_items() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/objects").with_status(200).with_body(r#"[{ "id": "1" }, { "id": 2 }, { "id": "three" }, {}]"#).create_async().await;
+
+        let config = Config { sources: vec![source(1, "Sloppy", server.url(), Resilience::default())] };
+        let manager = Manager::<Object>::new(config, reqwest::Client::new());
+        let (objects, reports) = manager.dispatch("objects", FilterList::new()).await;
+
+        assert_eq!(objects.len(), 1);
+        assert!(reports[0].error.is_none());
+        assert_eq!(reports[0].rejected, vec![
+            Rejection { id: Some(String::from("2")), violations: vec![String::from(r#"/id: 2 is not of type "string""#)] },
+            Rejection { id: Some(String::from("three")), violations: vec![String::from("/id: not a 32bit integer")] },
+            Rejection { id: None, violations: vec![String::from(r#"/: "id" is a required property"#)] },
+        ]);
+
+        let status = repository_to_domain::source_status(reports.into_iter().next().unwrap());
+        assert_eq!(status.rejected.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_strict_schema() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/objects").with_status(200).with_body(r#"[{ "id": "1" }, { "id": 2 }]"#).create_async().await;
+
+        let strict = Source { strict_schema: true, ..source(1, "Strict", server.url(), Resilience::default()) };
+        let manager = Manager::<Object>::new(Config { sources: vec![strict] }, reqwest::Client::new());
+        let (objects, reports) = manager.dispatch("objects", FilterList::new()).await;
+
+        assert!(objects.is_empty());
+        assert!(matches!(&reports[0].error, Some(FetchError::Invalid(rejections)) if rejections.len() == 1));
+
+        let status = repository_to_domain::source_status(reports.into_iter().next().unwrap());
+        assert_eq!(status.error, Some(crate::contexts::sources::domain::ErrorKind::InvalidPayload));
+        assert_eq!(status.rejected[0].id.as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_skips_down_sources() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/places").with_status(503).create_async().await;
+        let objects = server.mock("GET", "/objects").with_status(200).with_body(r#"[{ "id": "1" }]"#).expect(1).create_async().await;
+
+        let skipped = Source { skip_when_down: true, ..source(1, "Skipped", server.url(), fast(0, 0, 0)) };
+        let requested = source(2, "Requested", server.url(), fast(0, 0, 0));
+        let upstream = Upstream::new(Config { sources: vec![skipped, requested] }, reqwest::Client::new());
+        let manager = Manager::<Object>::shared(&upstream);
+
+        // not probed yet, hence not down
+        assert!(upstream.sources().iter().all(|source| upstream.checks(source.id).is_none()));
+        for source in upstream.sources() {
+            upstream.probe(source).await;
+        }
+        let (_, reports) = manager.dispatch("objects", FilterList::new()).await;
+        assert!(matches!(reports[0].error, Some(FetchError::Down)));
+        assert!(reports[1].error.is_none());
+        objects.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_invalid() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/objects/1").with_status(200).with_body(r#"{ "id": 1 }"#).create_async().await;
+
+        let config = Config { sources: vec![source(1, "Sloppy", server.url(), Resilience::default())] };
+        let manager = Manager::<Object>::new(config, reqwest::Client::new());
+        assert!(matches!(manager.get(1, "objects/1").await, Err(FetchError::Invalid(rejections)) if rejections[0].id.as_deref() == Some("1")));
+    }
+
+    #[tokio::test]
+    async fn test_get_retries() {
+        let mut server = mockito::Server::new_async().await;
+        let failing = server.mock("GET", "/objects/1").with_status(503).expect(1).create_async().await;
+        let ok = server.mock("GET", "/objects/1").with_status(200).with_body(r#"{ "id": "1" }"#).expect(1).create_async().await;
+
+        let config = Config { sources: vec![source(1, "Flaky", server.url(), fast(2, 5, 60000))] };
+        let manager = Manager::<Object>::new(config, reqwest::Client::new());
+        let object = manager.get(1, "objects/1").await.unwrap();
+
+        assert_eq!(object.unwrap().id, "0001-00000001");
+        failing.assert_async().await;
+        ok.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_gives_up_after_retries() {
+        let mut server = mockito::Server::new_async().await;
+        let failing = server.mock("GET", "/objects").with_status(503).expect(3).create_async().await;
+
+        let config = Config { sources: vec![source(1, "Down", server.url(), fast(2, 5, 60000))] };
+        let manager = Manager::<Object>::new(config, reqwest::Client::new());
+        let (_, reports) = manager.dispatch("objects", FilterList::new()).await;
+
+        assert!(matches!(reports[0].error, Some(FetchError::Status(StatusCode::SERVICE_UNAVAILABLE))));
+        failing.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_post_not_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let failing = server.mock("POST", "/objects").with_status(503).expect(1).create_async().await;
+
+        let config = Config { sources: vec![source(1, "Down", server.url(), fast(2, 5, 60000))] };
+        let manager = Manager::<Object>::new(config, reqwest::Client::new());
+        let result = manager.post(1, "objects", &Object { id: String::from("1") }).await;
+
+        assert!(matches!(result, Err(FetchError::Status(StatusCode::SERVICE_UNAVAILABLE))));
+        failing.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/objects")
+            .with_status(200)
+            .with_chunked_body(|writer| {
+                std::thread::sleep(Duration::from_millis(500));
+                writer.write_all(b"[]")
+            })
+            .create_async().await;
+
+        let resilience = Resilience { timeout_ms: 50, ..fast(0, 5, 60000) };
+        let config = Config { sources: vec![source(1, "Slow", server.url(), resilience)] };
+        let manager = Manager::<Object>::new(config, reqwest::Client::new());
+        let (_, reports) = manager.dispatch("objects", FilterList::new()).await;
+
+        assert!(matches!(&reports[0].error, Some(FetchError::Networking(error)) if error.is_timeout()));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker() {
+        let mut server = mockito::Server::new_async().await;
+        let failing = server.mock("GET", "/objects").with_status(503).expect(2).create_async().await;
+
+        let config = Config { sources: vec![source(1, "Down", server.url(), fast(0, 2, 60000))] };
+        let manager = Manager::<Object>::new(config, reqwest::Client::new());
+        manager.dispatch("objects", FilterList::new()).await;
+        manager.dispatch("objects", FilterList::new()).await;
+        // the breaker is open, the source is not requested anymore
+        let (_, reports) = manager.dispatch("objects", FilterList::new()).await;
+
+        assert!(matches!(reports[0].error, Some(FetchError::CircuitOpen)));
+        failing.assert_async().await;
+
+        let status = repository_to_domain::source_status(reports.into_iter().next().unwrap());
+        assert!(matches!(status.error, Some(crate::contexts::sources::domain::ErrorKind::CircuitOpen)));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_shared() {
+        let mut server = mockito::Server::new_async().await;
+        let failing = server.mock("GET", "/objects").with_status(503).expect(2).create_async().await;
+
+        let upstream = Upstream::new(Config { sources: vec![source(1, "Down", server.url(), fast(0, 2, 60000))] }, reqwest::Client::new());
+        let objects = Manager::<Object>::shared(&upstream);
+        objects.dispatch("objects", FilterList::new()).await;
+        objects.dispatch("objects", FilterList::new()).await;
+        // another resource of the same source is not requested either
+        let others = Manager::<Object>::shared(&upstream);
+        let (_, reports) = others.dispatch("objects", FilterList::new()).await;
+
+        assert!(matches!(reports[0].error, Some(FetchError::CircuitOpen)));
+        failing.assert_async().await;
+    }
+
+    fn cached(caching: Caching) -> impl Fn(u16, &str, String) -> Source {
+        move |id, name, url| Source { caching: caching.clone(), ..source(id, name, url, fast(0, 0, 0)) }
+    }
+
+    #[tokio::test]
+    async fn test_cache_fresh() {
+        let mut server = mockito::Server::new_async().await;
+        let list = server.mock("GET", "/objects").with_status(200).with_body(r#"[{ "id": "1" }]"#).expect(1).create_async().await;
+
+        let source = cached(Caching { cache_ttl_ms: 60000, cache_max_stale_ms: 0 });
+        let config = Config { sources: vec![source(1, "Gym", server.url())] };
+        let manager = Manager::<Object>::new(config, reqwest::Client::new());
+        manager.dispatch("objects", FilterList::new()).await;
+        let (objects, reports) = manager.dispatch("objects", FilterList::new()).await;
+
+        assert_eq!(objects.len(), 1);
+        assert!(reports[0].error.is_none());
+        list.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_cache_revalidation() {
+        let mut server = mockito::Server::new_async().await;
+        let list = server.mock("GET", "/objects")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("etag", "\"v1\"")
+            .with_header("last-modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .with_body(r#"[{ "id": "1" }, { "id": "2" }]"#)
+            .expect(1)
+            .create_async().await;
+        let not_modified = server.mock("GET", "/objects")
+            .match_header("if-none-match", "\"v1\"")
+            .match_header("if-modified-since", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .with_status(304)
+            .expect(1)
+            .create_async().await;
+
+        // expired right away, every read revalidates
+        let source = cached(Caching { cache_ttl_ms: 1, cache_max_stale_ms: 0 });
+        let config = Config { sources: vec![source(1, "Gym", server.url())] };
+        let manager = Manager::<Object>::new(config, reqwest::Client::new());
+        manager.dispatch("objects", FilterList::new()).await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let (objects, reports) = manager.dispatch("objects", FilterList::new()).await;
+
+        assert_eq!(objects.len(), 2);
+        assert!(reports[0].error.is_none());
+        list.assert_async().await;
+        not_modified.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_cache_stale_on_error() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/objects").with_status(200).with_body(r#"[{ "id": "1" }]"#).expect(1).create_async().await;
+        server.mock("GET", "/objects").with_status(503).create_async().await;
+
+        let source = cached(Caching { cache_ttl_ms: 1, cache_max_stale_ms: 60000 });
+        let config = Config { sources: vec![source(1, "Gym", server.url())] };
+        let manager = Manager::<Object>::new(config, reqwest::Client::new());
+        manager.dispatch("objects", FilterList::new()).await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let (objects, reports) = manager.dispatch("objects", FilterList::new()).await;
+
+        assert_eq!(objects.len(), 1);
+        assert!(matches!(reports[0].error, Some(FetchError::Status(StatusCode::SERVICE_UNAVAILABLE))));
+        assert!(reports[0].stale);
+    }
+
+    #[tokio::test]
+    async fn test_cache_invalidated_by_writes() {
+        let mut server = mockito::Server::new_async().await;
+        let list = server.mock("GET", "/objects").with_status(200).with_body(r#"[{ "id": "1" }]"#).expect(2).create_async().await;
+        server.mock("DELETE", "/objects/1").with_status(204).create_async().await;
+
+        let source = cached(Caching { cache_ttl_ms: 60000, cache_max_stale_ms: 0 });
+        let config = Config { sources: vec![source(1, "Gym", server.url())] };
+        let manager = Manager::<Object>::new(config, reqwest::Client::new());
+        manager.dispatch("objects", FilterList::new()).await;
+        manager.delete(1, "objects/1").await.unwrap();
+        manager.dispatch("objects", FilterList::new()).await;
+
+        list.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_cache_invalidated_across_resources() {
+        let mut server = mockito::Server::new_async().await;
+        let list = server.mock("GET", "/children").with_status(200).with_body(r#"[{ "id": "1" }]"#).expect(2).create_async().await;
+        server.mock("DELETE", "/parents/1").with_status(204).create_async().await;
+
+        let source = cached(Caching { cache_ttl_ms: 60000, cache_max_stale_ms: 0 });
+        let upstream = Upstream::new(Config { sources: vec![source(1, "Gym", server.url())] }, reqwest::Client::new());
+        let (parents, children) = (Manager::<Object>::shared(&upstream), Manager::<Object>::shared(&upstream));
+        children.dispatch("children", FilterList::new()).await;
+        // deleting a parent may change the children of the source
+        parents.delete(1, "parents/1").await.unwrap();
+        children.dispatch("children", FilterList::new()).await;
+
+        list.assert_async().await;
     }
 }
@@ -7,6 +7,87 @@ pub struct Source {
     pub id: u16,
     pub name: String,
     pub url: String,
+    #[serde(flatten)]
+    pub resilience: Resilience,
+    #[serde(flatten)]
+    pub caching: Caching,
+    #[serde(flatten)]
+    pub probing: Probing,
+    /// The source sorts and limits its lists with the `sort` and `limit` query parameters.
+    #[serde(default)]
+    pub pagination: bool,
+    /// A list holding an item that breaks its schema is rejected as a whole, instead of the item being dropped.
+    #[serde(default)]
+    pub strict_schema: bool,
+    /// Lists are not requested from the source while its last probe failed.
+    #[serde(default)]
+    pub skip_when_down: bool,
+}
+
+/// How requests to a source are bounded, retried and cut off when the source keeps failing.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Resilience {
+    /// Time allowed for a whole request, in milliseconds.
+    pub timeout_ms: u64,
+    /// How many times an idempotent request (GET, DELETE) is retried after a failure.
+    pub retries: u32,
+    /// Delay before the first retry in milliseconds, doubled on each retry and jittered.
+    pub backoff_ms: u64,
+    /// Consecutive failures after which the source is skipped.
+    pub breaker_threshold: u32,
+    /// Time during which a failing source is skipped before being probed again, in milliseconds.
+    pub breaker_cooldown_ms: u64,
+}
+
+impl Default for Resilience {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 5000,
+            retries: 2,
+            backoff_ms: 100,
+            breaker_threshold: 5,
+            breaker_cooldown_ms: 30000,
+        }
+    }
+}
+
+/// How long the lists fetched from a source are kept.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Caching {
+    /// Time during which a list is served without asking the source, in milliseconds. 0 disables the cache.
+    pub cache_ttl_ms: u64,
+    /// Age up to which a list is still served when the source fails, in milliseconds.
+    pub cache_max_stale_ms: u64,
+}
+
+impl Default for Caching {
+    fn default() -> Self {
+        Self {
+            cache_ttl_ms: 60000,
+            cache_max_stale_ms: 86400000,
+        }
+    }
+}
+
+/// How the health of a source is checked in the background.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Probing {
+    /// Path requested to check that the source answers, relative to its URL.
+    pub probe_path: String,
+    /// Time between two probes, in milliseconds. 0 disables the probes.
+    pub probe_interval_ms: u64,
+}
+
+impl Default for Probing {
+    fn default() -> Self {
+        Self {
+            probe_path: String::from("places"),
+            probe_interval_ms: 30000,
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -16,7 +97,7 @@ pub struct Config {
 
 impl Config {
     pub fn from_str(config: &str) -> Self {
-        toml::from_str(&config).expect("Could not parse config file")
+        toml::from_str(config).expect("Could not parse config file")
     }
 
     pub fn from_file(path: &str) -> Self {
@@ -80,6 +161,89 @@ mod tests {
         assert_eq!(config.sources[1].url, "https://twitter.com/");
     }
 
+    #[test]
+    fn test_resilience() {
+        let config = Config::from_str(
+            r#"
+            [[sources]]
+            id = 1
+            name = "Default"
+            url = "https://docs.rs/"
+
+            [[sources]]
+            id = 2
+            name = "Slow"
+            url = "https://twitter.com/"
+            timeout_ms = 10000
+            retries = 0
+            "#,
+        );
+        assert_eq!(config.sources[0].resilience, Resilience::default());
+        assert_eq!(config.sources[1].resilience.timeout_ms, 10000);
+        assert_eq!(config.sources[1].resilience.retries, 0);
+        assert_eq!(config.sources[1].resilience.backoff_ms, Resilience::default().backoff_ms);
+    }
+
+    #[test]
+    fn test_caching() {
+        let config = Config::from_str(
+            r#"
+            sources = [
+                { id = 1, name = "Default", url = "https://docs.rs/" },
+                { id = 2, name = "Live", url = "https://twitter.com/", cache_ttl_ms = 0 },
+            ]
+            "#,
+        );
+        assert_eq!(config.sources[0].caching, Caching::default());
+        assert_eq!(config.sources[1].caching.cache_ttl_ms, 0);
+        assert_eq!(config.sources[1].caching.cache_max_stale_ms, Caching::default().cache_max_stale_ms);
+    }
+
+    #[test]
+    fn test_pagination() {
+        let config = Config::from_str(
+            r#"
+            sources = [
+                { id = 1, name = "Default", url = "https://docs.rs/" },
+                { id = 2, name = "Paginated", url = "https://twitter.com/", pagination = true },
+            ]
+            "#,
+        );
+        assert!(!config.sources[0].pagination);
+        assert!(config.sources[1].pagination);
+    }
+
+    #[test]
+    fn test_strict_schema() {
+        let config = Config::from_str(
+            r#"
+            sources = [
+                { id = 1, name = "Default", url = "https://docs.rs/" },
+                { id = 2, name = "Strict", url = "https://twitter.com/", strict_schema = true },
+            ]
+            "#,
+        );
+        assert!(!config.sources[0].strict_schema);
+        assert!(config.sources[1].strict_schema);
+    }
+
+    #[test]
+    fn test_probing() {
+        let config = Config::from_str(
+            r#"
+            sources = [
+                { id = 1, name = "Default", url = "https://docs.rs/" },
+                { id = 2, name = "Probed", url = "https://twitter.com/", probe_path = "health", skip_when_down = true },
+            ]
+            "#,
+        );
+        assert_eq!(config.sources[0].probing, Probing::default());
+        assert!(!config.sources[0].skip_when_down);
+        assert_eq!(config.sources[1].probing.probe_path, "health");
+        assert_eq!(config.sources[1].probing.probe_interval_ms, Probing::default().probe_interval_ms);
+        assert!(config.sources[1].skip_when_down);
+    }
+
     #[test]
     fn test_get_source() {
         let config = Config::from_str(
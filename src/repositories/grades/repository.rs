@@ -0,0 +1,119 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::contexts::grades::{domain, irepository};
+use crate::errors::{DeleteError, GetAllError, UpdateError};
+
+/// Votes are kept in memory, keyed by the route id and the lowercased username.
+/// Clones share the same votes.
+#[derive(Clone, Default)]
+pub struct Repository {
+    votes: Arc<Mutex<BTreeMap<(domain::RouteId, String), domain::Vote>>>,
+}
+
+impl Repository {
+    fn votes(&self) -> std::sync::MutexGuard<'_, BTreeMap<(domain::RouteId, String), domain::Vote>> {
+        self.votes.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self, route_id: domain::RouteId) -> Result<Vec<domain::Vote>, GetAllError> {
+        Ok(self.votes().iter()
+            .filter(|((route, _), _)| *route == route_id)
+            .map(|(_, vote)| vote.clone())
+            .collect())
+    }
+
+    async fn votes_by_route(&self, route_ids: Vec<domain::RouteId>) -> Result<HashMap<domain::RouteId, Vec<domain::Vote>>, GetAllError> {
+        let mut by_route: HashMap<domain::RouteId, Vec<domain::Vote>> = HashMap::new();
+        for ((route_id, _), vote) in self.votes().iter().filter(|((route, _), _)| route_ids.contains(route)) {
+            by_route.entry(route_id.clone()).or_default().push(vote.clone());
+        }
+        Ok(by_route)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn save(&self, route_id: domain::RouteId, vote: domain::Vote) -> Result<(), UpdateError> {
+        self.votes().insert((route_id, vote.username.to_lowercase()), vote);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, route_id: domain::RouteId, username: domain::Username) -> Result<(), DeleteError> {
+        match self.votes().remove(&(route_id.clone(), username.to_lowercase())) {
+            Some(_) => Ok(()),
+            None => Err(DeleteError::NonExistingId(route_id)),
+        }
+    }
+
+    async fn forget(&self, username: domain::Username) -> Result<(), DeleteError> {
+        let username = username.to_lowercase();
+        self.votes().retain(|(_, user), _| *user != username);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use irepository::{get::IRepository, put::IRepository as _, delete::IRepository as _};
+
+    fn vote(username: &str, grade: &str) -> domain::Vote {
+        domain::Vote { username: String::from(username), grade: domain::Grade::parse(grade) }
+    }
+
+    #[tokio::test]
+    async fn test_save_replaces_vote() {
+        let repo = Repository::default();
+        repo.save(String::from("0001-00000001"), vote("jdoe", "6a")).await.unwrap();
+        repo.save(String::from("0001-00000001"), vote("JDoe", "6b")).await.unwrap();
+        repo.save(String::from("0001-00000001"), vote("asmith", "6a+")).await.unwrap();
+        repo.save(String::from("0001-00000002"), vote("jdoe", "7a")).await.unwrap();
+
+        let votes = repo.get_all(String::from("0001-00000001")).await.unwrap();
+        assert_eq!(votes, vec![vote("asmith", "6a+"), vote("JDoe", "6b")]);
+    }
+
+    #[tokio::test]
+    async fn test_delete() {
+        let repo = Repository::default();
+        repo.save(String::from("0001-00000001"), vote("jdoe", "6a")).await.unwrap();
+
+        assert!(repo.delete(String::from("0001-00000001"), String::from("JDOE")).await.is_ok());
+        assert!(matches!(repo.delete(String::from("0001-00000001"), String::from("jdoe")).await, Err(DeleteError::NonExistingId(_))));
+        assert!(repo.get_all(String::from("0001-00000001")).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_forget() {
+        let repo = Repository::default();
+        repo.save(String::from("0001-00000001"), vote("jdoe", "6a")).await.unwrap();
+        repo.save(String::from("0001-00000002"), vote("jdoe", "7a")).await.unwrap();
+        repo.save(String::from("0001-00000001"), vote("asmith", "6a+")).await.unwrap();
+
+        repo.forget(String::from("JDoe")).await.unwrap();
+        assert_eq!(repo.get_all(String::from("0001-00000001")).await.unwrap(), vec![vote("asmith", "6a+")]);
+        assert!(repo.get_all(String::from("0001-00000002")).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_votes() {
+        let repo = Repository::default();
+        repo.save(String::from("0001-00000001"), vote("jdoe", "6a")).await.unwrap();
+        repo.save(String::from("0001-00000001"), vote("asmith", "6a+")).await.unwrap();
+        repo.save(String::from("0001-00000002"), vote("jdoe", "7a")).await.unwrap();
+        repo.save(String::from("0001-00000003"), vote("jdoe", "8a")).await.unwrap();
+
+        let votes = repo.votes_by_route(vec![String::from("0001-00000001"), String::from("0001-00000002"), String::from("0001-00000004")]).await.unwrap();
+        assert_eq!(votes.len(), 2);
+        assert_eq!(votes["0001-00000001"], vec![vote("asmith", "6a+"), vote("jdoe", "6a")]);
+        assert_eq!(votes["0001-00000002"], vec![vote("jdoe", "7a")]);
+        assert!(repo.votes_by_route(Vec::new()).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use rusqlite::{params, params_from_iter};
+
+use crate::contexts::grades::{domain, irepository};
+use crate::errors::{DeleteError, GetAllError, UpdateError};
+use crate::repositories::storage::Pool;
+
+/// Votes are kept in the database, keyed by the route id and the lowercased username.
+/// Grades are stored as written and read again when loaded.
+#[derive(Clone)]
+pub struct Repository {
+    pool: Pool,
+}
+
+impl Repository {
+    pub fn new(pool: Pool) -> Self {
+        Repository { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self, route_id: domain::RouteId) -> Result<Vec<domain::Vote>, GetAllError> {
+        self.pool.run(move |connection| {
+            connection.prepare("SELECT username, grade FROM votes WHERE route_id = ?1 ORDER BY user_key")?
+                .query_map([route_id], |row| {
+                    let grade: String = row.get(1)?;
+                    Ok(domain::Vote { username: row.get(0)?, grade: domain::Grade::parse(&grade) })
+                })?
+                .collect()
+        }).await.map_err(|_| GetAllError::InternalServerError)
+    }
+
+    async fn votes_by_route(&self, route_ids: Vec<domain::RouteId>) -> Result<HashMap<domain::RouteId, Vec<domain::Vote>>, GetAllError> {
+        if route_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let votes: Vec<(domain::RouteId, domain::Vote)> = self.pool.run(move |connection| {
+            let placeholders = vec!["?"; route_ids.len()].join(", ");
+            connection.prepare(&format!("SELECT route_id, username, grade FROM votes WHERE route_id IN ({placeholders}) ORDER BY user_key"))?
+                .query_map(params_from_iter(route_ids), |row| {
+                    let grade: String = row.get(2)?;
+                    Ok((row.get(0)?, domain::Vote { username: row.get(1)?, grade: domain::Grade::parse(&grade) }))
+                })?
+                .collect()
+        }).await.map_err(|_| GetAllError::InternalServerError)?;
+
+        let mut by_route: HashMap<domain::RouteId, Vec<domain::Vote>> = HashMap::new();
+        for (route_id, vote) in votes {
+            by_route.entry(route_id).or_default().push(vote);
+        }
+        Ok(by_route)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn save(&self, route_id: domain::RouteId, vote: domain::Vote) -> Result<(), UpdateError> {
+        self.pool.run(move |connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO votes (route_id, user_key, username, grade) VALUES (?1, ?2, ?3, ?4)",
+                params![route_id, vote.username.to_lowercase(), vote.username, vote.grade.text],
+            )
+        }).await.map(|_| ()).map_err(|_| UpdateError::InternalServerError)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, route_id: domain::RouteId, username: domain::Username) -> Result<(), DeleteError> {
+        let route = route_id.clone();
+        let deleted = self.pool.run(move |connection| {
+            connection.execute("DELETE FROM votes WHERE route_id = ?1 AND user_key = ?2", [route, username.to_lowercase()])
+        }).await.map_err(|_| DeleteError::InternalServerError)?;
+
+        match deleted {
+            0 => Err(DeleteError::NonExistingId(route_id)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn forget(&self, username: domain::Username) -> Result<(), DeleteError> {
+        let username = username.to_lowercase();
+        self.pool.run(move |connection| connection.execute("DELETE FROM votes WHERE user_key = ?1", [username]))
+            .await.map(|_| ()).map_err(|_| DeleteError::InternalServerError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::storage;
+    use irepository::{get::IRepository, put::IRepository as _, delete::IRepository as _};
+
+    fn vote(username: &str, grade: &str) -> domain::Vote {
+        domain::Vote { username: String::from(username), grade: domain::Grade::parse(grade) }
+    }
+
+    #[tokio::test]
+    async fn test_save_and_delete() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        repo.save(String::from("0001-00000001"), vote("jdoe", "6a")).await.unwrap();
+        repo.save(String::from("0001-00000001"), vote("JDoe", "5.10b")).await.unwrap();
+        repo.save(String::from("0001-00000001"), vote("asmith", "6a+")).await.unwrap();
+        repo.save(String::from("0001-00000002"), vote("jdoe", "7a")).await.unwrap();
+
+        assert_eq!(repo.get_all(String::from("0001-00000001")).await.unwrap(), vec![vote("asmith", "6a+"), vote("JDoe", "5.10b")]);
+
+        assert!(repo.delete(String::from("0001-00000001"), String::from("JDOE")).await.is_ok());
+        assert!(matches!(repo.delete(String::from("0001-00000001"), String::from("jdoe")).await, Err(DeleteError::NonExistingId(_))));
+        assert_eq!(repo.get_all(String::from("0001-00000001")).await.unwrap(), vec![vote("asmith", "6a+")]);
+    }
+
+    #[tokio::test]
+    async fn test_votes() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        repo.save(String::from("0001-00000001"), vote("jdoe", "6a")).await.unwrap();
+        repo.save(String::from("0001-00000001"), vote("asmith", "6a+")).await.unwrap();
+        repo.save(String::from("0001-00000002"), vote("jdoe", "7a")).await.unwrap();
+        repo.save(String::from("0001-00000003"), vote("jdoe", "8a")).await.unwrap();
+
+        let votes = repo.votes_by_route(vec![String::from("0001-00000001"), String::from("0001-00000002"), String::from("0001-00000004")]).await.unwrap();
+        assert_eq!(votes.len(), 2);
+        assert_eq!(votes["0001-00000001"], vec![vote("asmith", "6a+"), vote("jdoe", "6a")]);
+        assert_eq!(votes["0001-00000002"], vec![vote("jdoe", "7a")]);
+        assert!(repo.votes_by_route(Vec::new()).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::contexts::sources::domain::{ErrorKind, Failure};
+
+/// Outcomes of the probes sent to the sources in the background, keyed by source id.
+#[derive(Default)]
+pub struct Monitor {
+    checks: Mutex<HashMap<u16, Checks>>,
+}
+
+/// What the probes of a source found out, `up` being the outcome of the last one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checks {
+    pub up: bool,
+    pub latency: Duration,
+    pub last_check: DateTime<Utc>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<Failure>,
+}
+
+impl Monitor {
+    /// Records the outcome of a probe that took `latency`, keeping the last success or error of the previous ones.
+    pub fn record(&self, source_id: u16, latency: Duration, outcome: Result<(), ErrorKind>) {
+        let now = Utc::now();
+        let mut checks = self.checks.lock().unwrap_or_else(PoisonError::into_inner);
+        let previous = checks.remove(&source_id);
+        let (last_success, last_error) = match &outcome {
+            Ok(()) => (Some(now), previous.and_then(|checks| checks.last_error)),
+            Err(error) => (previous.and_then(|checks| checks.last_success), Some(Failure { at: now, error: error.clone() })),
+        };
+        checks.insert(source_id, Checks { up: outcome.is_ok(), latency, last_check: now, last_success, last_error });
+    }
+
+    /// None until the first probe of the source completes.
+    pub fn get(&self, source_id: u16) -> Option<Checks> {
+        self.checks.lock().unwrap_or_else(PoisonError::into_inner).get(&source_id).cloned()
+    }
+
+    /// Whether the last probe of the source failed, a source that was not probed yet is not down.
+    pub fn is_down(&self, source_id: u16) -> bool {
+        matches!(self.get(source_id), Some(Checks { up: false, .. }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let monitor = Monitor::default();
+        assert!(monitor.get(1).is_none());
+        assert!(!monitor.is_down(1));
+
+        monitor.record(1, Duration::from_millis(8), Ok(()));
+        let up = monitor.get(1).unwrap();
+        assert!(up.up && up.last_error.is_none());
+        assert_eq!(up.last_success, Some(up.last_check));
+
+        monitor.record(1, Duration::from_millis(5000), Err(ErrorKind::Timeout));
+        let down = monitor.get(1).unwrap();
+        assert!(monitor.is_down(1));
+        // the last success is kept
+        assert_eq!(down.last_success, up.last_success);
+        assert_eq!(down.last_error, Some(Failure { at: down.last_check, error: ErrorKind::Timeout }));
+        // other sources are not affected
+        assert!(!monitor.is_down(2));
+    }
+}
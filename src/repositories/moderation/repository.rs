@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::contexts::moderation::{domain, irepository};
+use crate::errors::{CreateError, DeleteError, GetAllError};
+
+/// Reports and the audit trail are kept in memory, reports are keyed by the comment and the lowercased reporter.
+/// Clones share the same reports and audit trail.
+#[derive(Clone, Default)]
+pub struct Repository {
+    store: Arc<Mutex<Store>>,
+}
+
+#[derive(Default)]
+struct Store {
+    reports: BTreeMap<(domain::CommentId, Option<String>), domain::Report>,
+    journal: Vec<domain::Entry>,
+}
+
+impl Repository {
+    fn store(&self) -> std::sync::MutexGuard<'_, Store> {
+        self.store.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn reports(&self) -> Result<Vec<domain::Report>, GetAllError> {
+        let mut reports: Vec<_> = self.store().reports.values().cloned().collect();
+        reports.sort_by_key(|report| report.date);
+        Ok(reports)
+    }
+
+    async fn journal(&self) -> Result<Vec<domain::Entry>, GetAllError> {
+        Ok(self.store().journal.clone())
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::post::IRepository for Repository {
+    async fn report(&self, report: domain::Report) -> Result<(), CreateError> {
+        let key = (report.comment_id, report.reporter.as_deref().map(str::to_lowercase));
+        self.store().reports.insert(key, report);
+        Ok(())
+    }
+
+    async fn record(&self, entry: domain::Entry) -> Result<(), CreateError> {
+        self.store().journal.push(entry);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn dismiss(&self, comment_id: domain::CommentId) -> Result<(), DeleteError> {
+        self.store().reports.retain(|(id, _), _| *id != comment_id);
+        Ok(())
+    }
+
+    async fn forget(&self, reporter: domain::Username) -> Result<(), DeleteError> {
+        let reporter = Some(reporter.to_lowercase());
+        self.store().reports.retain(|(_, key), _| *key != reporter);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use irepository::{get::IRepository, post::IRepository as _, delete::IRepository as _};
+
+    fn report(comment_id: domain::CommentId, reporter: Option<&str>, minute: u32) -> domain::Report {
+        domain::Report {
+            comment_id,
+            reporter: reporter.map(String::from),
+            reason: String::from("Rude"),
+            date: Utc.with_ymd_and_hms(2023, 5, 1, 12, minute, 0).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_report_and_dismiss() {
+        let repo = Repository::default();
+        repo.report(report(1, Some("jdoe"), 0)).await.unwrap();
+        repo.report(report(2, None, 1)).await.unwrap();
+        repo.report(report(1, Some("asmith"), 2)).await.unwrap();
+        // a second report of the same user replaces the first
+        repo.report(report(1, Some("JDoe"), 3)).await.unwrap();
+
+        assert_eq!(repo.reports().await.unwrap(), vec![report(2, None, 1), report(1, Some("asmith"), 2), report(1, Some("JDoe"), 3)]);
+
+        repo.dismiss(1).await.unwrap();
+        assert_eq!(repo.reports().await.unwrap(), vec![report(2, None, 1)]);
+    }
+}
@@ -0,0 +1,156 @@
+use rusqlite::{params, Row};
+
+use crate::contexts::moderation::{domain, irepository};
+use crate::errors::{CreateError, DeleteError, GetAllError};
+use crate::repositories::storage::{self, Pool};
+
+/// Reports and the audit trail are kept in the database, reports are keyed by the comment and the lowercased reporter.
+#[derive(Clone)]
+pub struct Repository {
+    pool: Pool,
+}
+
+impl Repository {
+    pub fn new(pool: Pool) -> Self {
+        Repository { pool }
+    }
+}
+
+fn report(row: &Row) -> rusqlite::Result<domain::Report> {
+    Ok(domain::Report { comment_id: row.get(0)?, reporter: row.get(1)?, reason: row.get(2)?, date: row.get(3)? })
+}
+
+fn entry(row: &Row) -> rusqlite::Result<domain::Entry> {
+    let action: String = row.get(4)?;
+    Ok(domain::Entry {
+        comment_id: row.get(0)?,
+        route_id: row.get(1)?,
+        author: row.get(2)?,
+        moderator: row.get(3)?,
+        action: parse_action(&action)?,
+        reason: row.get(5)?,
+        date: row.get(6)?,
+    })
+}
+
+fn action(action: domain::Action) -> &'static str {
+    match action {
+        domain::Action::Hide => "hide",
+        domain::Action::Restore => "restore",
+        domain::Action::Delete => "delete",
+    }
+}
+
+fn parse_action(text: &str) -> rusqlite::Result<domain::Action> {
+    match text {
+        "hide" => Ok(domain::Action::Hide),
+        "restore" => Ok(domain::Action::Restore),
+        "delete" => Ok(domain::Action::Delete),
+        _ => Err(storage::unreadable(4, text)),
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn reports(&self) -> Result<Vec<domain::Report>, GetAllError> {
+        self.pool.run(|connection| {
+            connection.prepare("SELECT comment_id, reporter, reason, date FROM reports ORDER BY date, comment_id, reporter_key")?
+                .query_map([], report)?
+                .collect()
+        }).await.map_err(|_| GetAllError::InternalServerError)
+    }
+
+    async fn journal(&self) -> Result<Vec<domain::Entry>, GetAllError> {
+        self.pool.run(|connection| {
+            connection.prepare("SELECT comment_id, route_id, author, moderator, action, reason, date FROM journal ORDER BY id")?
+                .query_map([], entry)?
+                .collect()
+        }).await.map_err(|_| GetAllError::InternalServerError)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::post::IRepository for Repository {
+    async fn report(&self, report: domain::Report) -> Result<(), CreateError> {
+        self.pool.run(move |connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO reports (comment_id, reporter_key, reporter, reason, date) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![report.comment_id, report.reporter.as_deref().unwrap_or_default().to_lowercase(), report.reporter, report.reason, report.date],
+            )
+        }).await.map(|_| ()).map_err(|_| CreateError::InternalServerError)
+    }
+
+    async fn record(&self, entry: domain::Entry) -> Result<(), CreateError> {
+        self.pool.run(move |connection| {
+            connection.execute(
+                "INSERT INTO journal (comment_id, route_id, author, moderator, action, reason, date) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![entry.comment_id, entry.route_id, entry.author, entry.moderator, action(entry.action), entry.reason, entry.date],
+            )
+        }).await.map(|_| ()).map_err(|_| CreateError::InternalServerError)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn dismiss(&self, comment_id: domain::CommentId) -> Result<(), DeleteError> {
+        self.pool.run(move |connection| connection.execute("DELETE FROM reports WHERE comment_id = ?1", [comment_id]))
+            .await.map(|_| ()).map_err(|_| DeleteError::InternalServerError)
+    }
+
+    async fn forget(&self, reporter: domain::Username) -> Result<(), DeleteError> {
+        let reporter = reporter.to_lowercase();
+        self.pool.run(move |connection| connection.execute("DELETE FROM reports WHERE reporter_key = ?1", [reporter]))
+            .await.map(|_| ()).map_err(|_| DeleteError::InternalServerError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use irepository::{get::IRepository, post::IRepository as _, delete::IRepository as _};
+
+    fn at(minute: u32) -> domain::Instant {
+        Utc.with_ymd_and_hms(2023, 5, 1, 12, minute, 0).unwrap()
+    }
+
+    fn report(comment_id: domain::CommentId, reporter: Option<&str>, minute: u32) -> domain::Report {
+        domain::Report { comment_id, reporter: reporter.map(String::from), reason: String::from("Rude"), date: at(minute) }
+    }
+
+    #[tokio::test]
+    async fn test_report_and_dismiss() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        repo.report(report(1, Some("jdoe"), 0)).await.unwrap();
+        repo.report(report(2, None, 1)).await.unwrap();
+        repo.report(report(1, Some("asmith"), 2)).await.unwrap();
+        // a second report of the same user replaces the first
+        repo.report(report(1, Some("JDoe"), 3)).await.unwrap();
+
+        assert_eq!(repo.reports().await.unwrap(), vec![report(2, None, 1), report(1, Some("asmith"), 2), report(1, Some("JDoe"), 3)]);
+
+        repo.dismiss(1).await.unwrap();
+        assert_eq!(repo.reports().await.unwrap(), vec![report(2, None, 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_record() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        let entry = |action, reason: Option<&str>| domain::Entry {
+            comment_id: 1,
+            route_id: String::from("0001-00000001"),
+            author: String::from("jdoe"),
+            moderator: String::from("asmith"),
+            action,
+            reason: reason.map(String::from),
+            date: at(0),
+        };
+        repo.record(entry(domain::Action::Hide, Some("Rude"))).await.unwrap();
+        repo.record(entry(domain::Action::Restore, None)).await.unwrap();
+
+        assert_eq!(repo.journal().await.unwrap(), vec![entry(domain::Action::Hide, Some("Rude")), entry(domain::Action::Restore, None)]);
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::contexts::places::{domain, irepository};
+use crate::contexts::sources::domain::{Aggregate, Window};
+use crate::errors::{CreateError, DeleteError, GetAllError, GetError, UpdateError};
+use crate::repositories::common::RelativeId;
+
+/// Source of the places seeded from the examples.
+pub const SEED_SOURCE: domain::SourceId = 1;
+
+/// The place of `schemas/place/example.json`, whose id is relative to its source.
+#[derive(serde::Deserialize)]
+struct Example {
+    id: String,
+    name: String,
+    description: String,
+    address: String,
+    postcode: String,
+    city: String,
+    country: String,
+}
+
+/// Places are kept in memory instead of being asked to the sources, keyed by their id written in full.
+/// A new place takes the id following the last one of its source.
+/// Clones share the same places.
+#[derive(Clone, Default)]
+pub struct Repository {
+    places: Arc<Mutex<BTreeMap<domain::PlaceId, domain::PlaceData>>>,
+}
+
+impl Repository {
+    /// The place of `schemas/place/example.json`, in the seed source.
+    pub fn seeded() -> Self {
+        let example: Example = serde_json::from_str(include_str!("../../../schemas/place/example.json")).expect("The place example is invalid");
+        let id = RelativeId { source_id: SEED_SOURCE, resource_id: example.id.parse().expect("The place example has an invalid id") };
+        let data = domain::PlaceData {
+            name        : example.name,
+            description : example.description,
+            address     : example.address,
+            postcode    : example.postcode,
+            city        : example.city,
+            country     : example.country,
+        };
+
+        let repo = Repository::default();
+        repo.places().insert(id.to_string(), data);
+        repo
+    }
+
+    fn places(&self) -> std::sync::MutexGuard<'_, BTreeMap<domain::PlaceId, domain::PlaceData>> {
+        self.places.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Whether the place exists.
+    pub fn contains(&self, id: &RelativeId) -> bool {
+        self.places().contains_key(&id.to_string())
+    }
+}
+
+/// The id following the last one of the source among the given ids, written in full.
+pub fn next_id<'a>(source_id: domain::SourceId, ids: impl Iterator<Item = &'a String>) -> String {
+    let last = ids.filter_map(|id| id.parse::<RelativeId>().ok())
+        .filter(|id| id.source_id == source_id)
+        .map(|id| id.resource_id)
+        .max()
+        .unwrap_or(0);
+    RelativeId { source_id, resource_id: last + 1 }.to_string()
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    /// Every matching place is returned, the use case sorts and pages them.
+    async fn get_all(&self, filters: domain::get::Filters, _sort: domain::get::Sort, _window: Window) -> Result<Aggregate<domain::Place>, GetAllError> {
+        let items = self.places().iter()
+            .filter(|(_, data)| filters.matches(data))
+            .map(|(id, data)| domain::Place { id: id.clone(), data: data.clone() })
+            .collect();
+        Ok(Aggregate { items, sources: Vec::new() })
+    }
+
+    async fn get(&self, id: domain::PlaceId) -> Result<domain::Place, GetError> {
+        let Ok(key) = id.parse::<RelativeId>() else { return Err(GetError::MalformedId(id)) };
+        let key = key.to_string();
+        match self.places().get(&key) {
+            Some(data) => Ok(domain::Place { id: key, data: data.clone() }),
+            None => Err(GetError::NonExistingId(id)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::post::IRepository for Repository {
+    async fn create(&self, source_id: domain::SourceId, data: domain::PlaceData) -> Result<domain::Place, CreateError> {
+        let mut places = self.places();
+        let id = next_id(source_id, places.keys());
+        places.insert(id.clone(), data.clone());
+        Ok(domain::Place { id, data })
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn update(&self, id: domain::PlaceId, data: domain::PlaceData) -> Result<(), UpdateError> {
+        let Ok(key) = id.parse::<RelativeId>() else { return Err(UpdateError::MalformedId(id)) };
+        match self.places().get_mut(&key.to_string()) {
+            Some(stored) => {
+                *stored = data;
+                Ok(())
+            },
+            None => Err(UpdateError::NonExistingId(id)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, id: domain::PlaceId) -> Result<(), DeleteError> {
+        let Ok(key) = id.parse::<RelativeId>() else { return Err(DeleteError::MalformedId(id)) };
+        match self.places().remove(&key.to_string()) {
+            Some(_) => Ok(()),
+            None => Err(DeleteError::NonExistingId(id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use irepository::{get::IRepository, post::IRepository as _, put::IRepository as _, delete::IRepository as _};
+
+    fn filters(city: Option<&str>) -> domain::get::Filters {
+        domain::get::Filters { country: None, city: city.map(String::from) }
+    }
+
+    #[tokio::test]
+    async fn test_seeded() {
+        let repo = Repository::seeded();
+        let place = repo.get(String::from("1-1")).await.unwrap();
+        assert_eq!((place.id.as_str(), place.data.name.as_str()), ("0001-00000001", "COUM"));
+        assert!(matches!(repo.get(String::from("garbage")).await, Err(GetError::MalformedId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get_all() {
+        let repo = Repository::seeded();
+        let other = domain::PlaceData { city: String::from("Paris"), ..repo.get(String::from("0001-00000001")).await.unwrap().data };
+        let created = repo.create(SEED_SOURCE, other.clone()).await.unwrap();
+        assert_eq!(created.id, "0001-00000002");
+        assert_eq!(repo.create(2, other).await.unwrap().id, "0002-00000001");
+
+        let ids = |aggregate: Aggregate<domain::Place>| aggregate.items.into_iter().map(|place| place.id).collect::<Vec<_>>();
+        assert_eq!(ids(repo.get_all(filters(None), domain::get::Sort::Id, Window::default()).await.unwrap()), ["0001-00000001", "0001-00000002", "0002-00000001"]);
+        assert_eq!(ids(repo.get_all(filters(Some("paris")), domain::get::Sort::Id, Window::default()).await.unwrap()), ["0001-00000002", "0002-00000001"]);
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete() {
+        let repo = Repository::seeded();
+        let mut data = repo.get(String::from("0001-00000001")).await.unwrap().data;
+        data.name = String::from("Arkose");
+        repo.update(String::from("1-1"), data.clone()).await.unwrap();
+        assert_eq!(repo.get(String::from("0001-00000001")).await.unwrap().data.name, "Arkose");
+        assert!(matches!(repo.update(String::from("0001-00000002"), data).await, Err(UpdateError::NonExistingId(_))));
+
+        repo.delete(String::from("0001-00000001")).await.unwrap();
+        assert!(matches!(repo.get(String::from("0001-00000001")).await, Err(GetError::NonExistingId(_))));
+        assert!(matches!(repo.delete(String::from("0001-00000001")).await, Err(DeleteError::NonExistingId(_))));
+    }
+}
@@ -1,14 +1,19 @@
+use reqwest::StatusCode;
+use std::sync::Arc;
+
 use crate::contexts::places::{domain, irepository};
+use crate::contexts::sources::domain::{Aggregate, Window};
+use crate::errors::{CreateError, DeleteError, GetAllError, GetError, SourceFailure, UpdateError};
 use crate::repositories::{
-    common::{impl_identifiable_for, Identifiable, Manager},
-    config::Config,
+    common::{self, impl_identifiable_for, FetchError, FilterList, Identifiable, Manager, Upstream, RelativeId, repository_to_domain::{get_error, create_error, update_error, delete_error}},
+    schemas::{Kind, Schema, Validated},
 };
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 pub struct Place {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub _id: Option<String>,
-    pub id: u32,
+    /// Given as a string or as an integer by the sources.
+    #[serde(deserialize_with = "common::string_or_integer")]
+    pub id: String,
     pub name: String,
     pub description: String,
     pub address: String,
@@ -17,23 +22,32 @@ pub struct Place {
     pub country: String,
 }
 
-impl Identifiable for Place {
-    fn id(&mut self) -> &mut String {
-        if self._id.is_none() {
-            self._id = Some(self.id.to_string());
-            eprintln!("Casting id to string: {:#?}", self._id);
-        }
-        self._id.as_mut().unwrap()
+impl_identifiable_for!(Place);
+
+impl Validated for Place {
+    fn schema() -> &'static Schema {
+        Schema::of(Kind::Place)
     }
 }
 
+/// Body sent to a source when creating or updating a place.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct PlaceData {
+    pub name: String,
+    pub description: String,
+    pub address: String,
+    pub postcode: String,
+    pub city: String,
+    pub country: String,
+}
+
 mod repository_to_domain {
-    use super::{Place, Identifiable};
+    use super::Place;
     use crate::contexts::places::domain;
 
-    pub fn place(mut p: Place) -> domain::Place {
+    pub fn place(p: Place) -> domain::Place {
         domain::Place {
-            id   : p.id().to_string(),
+            id   : p.id,
             data : domain::PlaceData {
                 name        : p.name,
                 description : p.description,
@@ -44,32 +58,28 @@ mod repository_to_domain {
             }
         }
     }
-
-    pub fn filters(f: Vec<(String, String)>) -> domain::get::Filters {
-        let mut filters = domain::get::Filters {
-            country: None,
-            city: None,
-        };
-
-        for i in f {
-            match i.0.as_ref() {
-                "country" => filters.country = Some(i.1),
-                "city" => filters.city = Some(i.1),
-                _ => (),
-            }
-        }
-
-        filters
-    }
 }
 mod domain_to_repository {
+    use super::{FilterList, PlaceData, Window};
+    #[cfg(test)]
     use super::Place;
     use crate::contexts::places::domain;
 
+    pub fn place_data(d: domain::PlaceData) -> PlaceData {
+        PlaceData {
+            name        : d.name,
+            description : d.description,
+            address     : d.address,
+            postcode    : d.postcode,
+            city        : d.city,
+            country     : d.country,
+        }
+    }
+
+    #[cfg(test)]
     pub fn place(p: domain::Place) -> Place {
         Place {
-            id          : p.id.parse().expect("Place id may be a RelativeId?"),
-            _id         : Some(p.id),
+            id          : p.id,
             name        : p.data.name,
             description : p.data.description,
             address     : p.data.address,
@@ -79,126 +89,334 @@ mod domain_to_repository {
         }
     }
 
-    pub fn filters(f: domain::get::Filters) -> Vec<(String, String)> {
-        let mut filters = Vec::new();
-
-        if let Some(country) = f.country {
-            filters.push(("country".to_string(), country));
-        }
-        if let Some(city) = f.city {
-            filters.push(("city".to_string(), city));
+    /// Asks the sources for the places up to the end of the window, plus one to know if there are more.
+    /// Unless the sources match and order the places exactly as the core does, they are asked for every place:
+    /// a place the core drops or orders elsewhere would leave out one that belongs in the window.
+    pub fn pagination(sort: domain::get::Sort, window: Window, filters: &domain::get::Filters) -> FilterList {
+        // names and cities are compared regardless of case, accents and word separators
+        if sort != domain::get::Sort::Id || !filters.is_upstream() {
+            return FilterList::new();
         }
-
-        filters
+        vec![
+            (String::from("sort"), String::from("id")),
+            (String::from("limit"), (window.end() + 1).to_string()),
+        ]
     }
 }
 
+/// Clones share the same manager, hence the same cache and circuit breakers.
+#[derive(Clone)]
 pub struct Repository {
-    manager: Manager<Place>,
+    manager: Arc<Manager<Place>>,
 }
 
-impl Default for Repository {
-    fn default() -> Self {
-        let config = Config::from_env();
-        let manager = Manager::<Place>::new(config, reqwest::Client::new());
-        Self { manager }
+impl Repository {
+    pub fn new(upstream: &Upstream) -> Self {
+        Self { manager: Arc::new(Manager::<Place>::shared(upstream)) }
     }
 }
 
 #[async_trait::async_trait]
 impl irepository::get::IRepository for Repository {
-    async fn get(&self, id: domain::PlaceId) -> Result<domain::Place, crate::errors::GetError> {
-        todo!()
+    async fn get(&self, id: domain::PlaceId) -> Result<domain::Place, GetError> {
+        let Ok(RelativeId { source_id, resource_id }) = id.parse() else { return Err(GetError::MalformedId(id)) };
+        match self.manager.get(source_id, &format!("places/{resource_id}")).await {
+            Ok(Some(place)) => Ok(repository_to_domain::place(place)),
+            Ok(None) => Err(GetError::NonExistingId(id)),
+            Err(fetch_err) => Err(get_error(fetch_err, source_id, id)),
+        }
     }
 
-    async fn get_all(&self, filters: domain::get::Filters) -> Result<Vec<domain::Place>, crate::errors::GetAllError> {
-        let path = "places";
-        let (places, errors) = self.manager.dispatch(&path).await;
+    async fn get_all(&self, filters: domain::get::Filters, sort: domain::get::Sort, window: Window) -> Result<Aggregate<domain::Place>, GetAllError> {
+        // countries and cities are matched by the core regardless of case and accents,
+        // a source matching them exactly would leave out places the core finds
+        let pagination = domain_to_repository::pagination(sort, window, &filters);
+        let (places, reports) = self.manager.dispatch("places", pagination).await;
 
-        // print errors to stderr
-        for e in errors {
-            eprintln!("{:#?}", e);
+        Ok(Aggregate {
+            items: places.into_iter().map(repository_to_domain::place).collect(),
+            sources: reports.into_iter().map(common::repository_to_domain::source_status).collect(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::post::IRepository for Repository {
+    async fn create(&self, source_id: domain::SourceId, data: domain::PlaceData) -> Result<domain::Place, CreateError> {
+        match self.manager.post(source_id, "places", &domain_to_repository::place_data(data)).await {
+            Ok(place) => Ok(repository_to_domain::place(place)),
+            Err(fetch_err) => Err(create_error(fetch_err, source_id, source_id.to_string())),
         }
+    }
+}
 
-        let places_list = places
-            .into_iter()
-            .map(|p|  repository_to_domain::place(p.clone()))
-            .collect();
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn update(&self, id: domain::PlaceId, data: domain::PlaceData) -> Result<(), UpdateError> {
+        let Ok(RelativeId { source_id, resource_id }) = id.parse() else { return Err(UpdateError::MalformedId(id)) };
+        match self.manager.put(source_id, &format!("places/{resource_id}"), &domain_to_repository::place_data(data)).await {
+            Ok(StatusCode::NOT_FOUND) => Err(UpdateError::NonExistingId(id)),
+            Ok(status) if status.is_success() => Ok(()),
+            Ok(status) => Err(UpdateError::Source(SourceFailure { source_id, cause: FetchError::Status(status) })),
+            Err(fetch_err) => Err(update_error(fetch_err, source_id, id)),
+        }
+    }
+}
 
-        Ok(places_list)
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, id: domain::PlaceId) -> Result<(), DeleteError> {
+        let Ok(RelativeId { source_id, resource_id }) = id.parse() else { return Err(DeleteError::MalformedId(id)) };
+        match self.manager.delete(source_id, &format!("places/{resource_id}")).await {
+            Ok(StatusCode::NOT_FOUND) => Err(DeleteError::NonExistingId(id)),
+            Ok(status) if status.is_success() => Ok(()),
+            Ok(status) => Err(DeleteError::Source(SourceFailure { source_id, cause: FetchError::Status(status) })),
+            Err(fetch_err) => Err(delete_error(fetch_err, source_id, id)),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use irepository::get::IRepository;
-    use crate::repositories::{common::RelativeId, config::Source};
-
-    impl Repository {
-        pub async fn get_all_routes(&self) -> Vec<Place> {
-            return self
-                .get_all(domain::get::Filters { country: None, city: None }).await
-                .into_iter()
-                .map(domain_to_repository::place)
-                .collect()
+    use irepository::{get::IRepository, post::IRepository as _, put::IRepository as _, delete::IRepository as _};
+    use crate::repositories::config::{Caching, Config, Probing, Resilience, Source};
+
+    fn repository(server: &mockito::ServerGuard) -> Repository {
+        let config = Config::from_str(&format!(
+            r#"
+            [[sources]]
+            name = "Test"
+            id = 1
+            url = "{}"
+            "#,
+            server.url(),
+        ));
+        Repository { manager: Arc::new(Manager::<Place>::new(config, reqwest::Client::new())) }
+    }
+
+    fn place_data() -> domain::PlaceData {
+        domain::PlaceData {
+            name: String::from("COUM"),
+            description: String::from("Centre Omnisport Universitaire de Moulon"),
+            address: String::from("8 rue 128"),
+            postcode: String::from("91190"),
+            city: String::from("Gif-sur-Yvette"),
+            country: String::from("France"),
         }
     }
 
-    // #[tokio::test]
-    // async fn test_get() {
-    //     let mut server = mockito::Server::new();
-
-    //     let config = Config::from_str(&format!(
-    //         r#"
-    //         [[sources]]
-    //         name = "Test"
-    //         id = 1
-    //         url = "{}"
-    //         "#,
-    //         server.url(),
-    //     ));
-
-    //     server
-    //         .mock("GET", "/places/1")
-    //         .with_status(200)
-    //         .with_header("content-type", "application/json")
-    //         .with_body(
-    //             r#"
-    //             {
-    //                 "id": "00000001",
-    //                 "name": "COUM",
-    //                 "description": "Centre Omnisport Universitaire de Moulon",
-    //                 "address": "8 rue 128",
-    //                 "postcode": "91190",
-    //                 "city": "Gif-sur-Yvette",
-    //                 "country": "France"
-    //             }
-    //             "#,
-    //         )
-    //         .create();
-
-    //     let manager = Manager::<Place>::new(config, reqwest::Client::new());
-
-    //     let repo = Repository { manager };
-    //     let place = repo.get("0001-00000001").await.unwrap();
-    //     assert_eq!(place.id, "0001-00000001");
-    //     assert_eq!(place.name, "COUM");
-    //     assert_eq!(
-    //         place.description,
-    //         "Centre Omnisport Universitaire de Moulon"
-    //     );
-    //     assert_eq!(place.address, "8 rue 128");
-    //     assert_eq!(place.postcode, "91190");
-    //     assert_eq!(place.city, "Gif-sur-Yvette");
-    //     assert_eq!(place.country, "France");
-    // }
+    #[tokio::test]
+    async fn test_get() {
+        let mut server = mockito::Server::new_async().await;
+
+        server
+            .mock("GET", "/places/1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"
+                {
+                    "id": "1",
+                    "name": "COUM",
+                    "description": "Centre Omnisport Universitaire de Moulon",
+                    "address": "8 rue 128",
+                    "postcode": "91190",
+                    "city": "Gif-sur-Yvette",
+                    "country": "France"
+                }
+                "#,
+            )
+            .create_async().await;
+
+        let repo = repository(&server);
+        let place = repo.get(String::from("0001-00000001")).await.unwrap();
+        assert_eq!(place.id, "0001-00000001");
+        assert_eq!(place.data.name, "COUM");
+        assert_eq!(place.data.description, "Centre Omnisport Universitaire de Moulon");
+        assert_eq!(place.data.address, "8 rue 128");
+        assert_eq!(place.data.postcode, "91190");
+        assert_eq!(place.data.city, "Gif-sur-Yvette");
+        assert_eq!(place.data.country, "France");
+    }
+
+    #[tokio::test]
+    async fn test_get_all_keeps_filters() {
+        let mut server = mockito::Server::new_async().await;
+        // a source that would match the city exactly
+        server
+            .mock("GET", "/places")
+            .match_query(mockito::Matcher::UrlEncoded(String::from("city"), String::from("Etoile")))
+            .with_status(200)
+            .with_body("[]")
+            .create_async().await;
+        let unfiltered = server
+            .mock("GET", "/places")
+            .match_query(mockito::Matcher::Exact(String::new()))
+            .with_status(200)
+            .with_body(r#"[{ "id": "1", "name": "Le Mur", "description": "", "address": "", "postcode": "", "city": "Étoile", "country": "France" }]"#)
+            .create_async().await;
+
+        let repo = repository(&server);
+        let filters = domain::get::Filters { country: None, city: Some(String::from("Etoile")) };
+        let places = repo.get_all(filters, domain::get::Sort::Id, Window::default()).await.unwrap().items;
+        assert_eq!(places.iter().map(|place| place.data.city.as_str()).collect::<Vec<_>>(), ["Étoile"]);
+        unfiltered.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_all_sends_pagination() {
+        let mut server = mockito::Server::new_async().await;
+        let paginated = server
+            .mock("GET", "/places")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded(String::from("sort"), String::from("id")),
+                mockito::Matcher::UrlEncoded(String::from("limit"), String::from("31")),
+            ]))
+            .with_status(200)
+            .with_body("[]")
+            .create_async().await;
+        // the core matches and orders text its own way, the sources are asked for every place
+        let unpaginated = server
+            .mock("GET", "/places")
+            .match_query(mockito::Matcher::Exact(String::new()))
+            .with_status(200)
+            .with_body("[]")
+            .create_async().await;
+
+        let config = Config::from_str(&format!(
+            r#"
+            [[sources]]
+            name = "Test"
+            id = 1
+            url = "{}"
+            pagination = true
+            "#,
+            server.url()
+        ));
+        let repo = Repository { manager: Arc::new(Manager::<Place>::new(config, reqwest::Client::new())) };
+        let window = Window { offset: 20, limit: 10 };
+        let unfiltered = domain::get::Filters { country: None, city: None };
+        repo.get_all(unfiltered, domain::get::Sort::Id, window).await.unwrap();
+        paginated.assert_async().await;
+
+        let paris = domain::get::Filters { country: None, city: Some(String::from("Paris")) };
+        repo.get_all(paris, domain::get::Sort::Id, window).await.unwrap();
+        // the same unpaginated list, from the cache
+        let france = domain::get::Filters { country: Some(String::from("France")), city: None };
+        repo.get_all(france, domain::get::Sort::Name, window).await.unwrap();
+        unpaginated.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_integer_ids() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/places")
+            .with_status(200)
+            .with_body(r#"[{ "id": 7, "name": "COUM", "description": "", "address": "", "postcode": "", "city": "Gif-sur-Yvette", "country": "France" }]"#)
+            .create_async().await;
+        server
+            .mock("GET", "/places/7")
+            .with_status(200)
+            .with_body(r#"{ "id": 7, "name": "COUM", "description": "", "address": "", "postcode": "", "city": "Gif-sur-Yvette", "country": "France" }"#)
+            .create_async().await;
+
+        let config = Config::from_str(&format!(
+            r#"
+            [[sources]]
+            name = "Test"
+            id = 1
+            url = "{}"
+            strict_schema = true
+            "#,
+            server.url(),
+        ));
+        let repo = Repository { manager: Arc::new(Manager::<Place>::new(config, reqwest::Client::new())) };
+        let places = repo.get_all(domain::get::Filters { country: None, city: None }, domain::get::Sort::Id, Window::default()).await.unwrap();
+        assert_eq!(places.items.iter().map(|place| place.id.as_str()).collect::<Vec<_>>(), ["0001-00000007"]);
+        assert!(places.sources[0].error.is_none());
+        assert_eq!(repo.get(String::from("0001-00000007")).await.unwrap().data.name, "COUM");
+    }
+
+    #[tokio::test]
+    async fn test_get_non_existing() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/places/2").with_status(404).create_async().await;
+
+        let repo = repository(&server);
+        assert!(matches!(
+            repo.get(String::from("0001-00000002")).await,
+            Err(GetError::NonExistingId(id)) if id == "0001-00000002"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("POST", "/places")
+            .match_body(mockito::Matcher::Json(serde_json::to_value(domain_to_repository::place_data(place_data())).unwrap()))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"
+                {
+                    "id": "5",
+                    "name": "COUM",
+                    "description": "Centre Omnisport Universitaire de Moulon",
+                    "address": "8 rue 128",
+                    "postcode": "91190",
+                    "city": "Gif-sur-Yvette",
+                    "country": "France"
+                }
+                "#,
+            )
+            .create_async().await;
+
+        let repo = repository(&server);
+        let place = repo.create(1, place_data()).await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(place.id, "0001-00000005");
+        assert_eq!(place.data.name, "COUM");
+    }
+
+    #[tokio::test]
+    async fn test_update() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PUT", "/places/5")
+            .match_body(mockito::Matcher::PartialJsonString(String::from(r#"{ "city": "Gif-sur-Yvette" }"#)))
+            .with_status(204)
+            .create_async().await;
+        server.mock("PUT", "/places/6").with_status(404).create_async().await;
+
+        let repo = repository(&server);
+        assert!(repo.update(String::from("0001-00000005"), place_data()).await.is_ok());
+        assert!(matches!(repo.update(String::from("0001-00000006"), place_data()).await, Err(UpdateError::NonExistingId(_))));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_delete() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("DELETE", "/places/5").with_status(204).create_async().await;
+        server.mock("DELETE", "/places/6").with_status(404).create_async().await;
+
+        let repo = repository(&server);
+        assert!(repo.delete(String::from("0001-00000005")).await.is_ok());
+        assert!(matches!(repo.delete(String::from("0001-00000006")).await, Err(DeleteError::NonExistingId(_))));
+        mock.assert_async().await;
+    }
 
     #[tokio::test]
     async fn test_get_all() {
         const SOURCES_COUNT: usize = 2;
-        let mut servers: [mockito::ServerGuard; SOURCES_COUNT] =
-            core::array::from_fn(|_| mockito::Server::new());
+        let mut servers = Vec::with_capacity(SOURCES_COUNT);
+        for _ in 0..SOURCES_COUNT {
+            servers.push(mockito::Server::new_async().await);
+        }
 
         let config = Config {
             sources: servers
@@ -208,6 +426,12 @@ mod tests {
                     name: format!("Source {}", i + 1),
                     id: (i + 1) as u16,
                     url: server.url(),
+                    resilience: Resilience::default(),
+                    caching: Caching::default(),
+                    probing: Probing::default(),
+                    pagination: false,
+                    strict_schema: false,
+                    skip_when_down: false,
                 })
                 .collect(),
         };
@@ -233,7 +457,7 @@ mod tests {
             let places_slice = &expected_places[i * 2..(i + 1) * 2]
                 .iter()
                 .map(|place| Place {
-                    id: RelativeId::from_str(&place.id).resource_id.to_string(),
+                    id: place.id.parse::<RelativeId>().unwrap().resource_id.to_string(),
                     ..place.clone()
                 })
                 .collect::<Vec<Place>>();
@@ -244,20 +468,25 @@ mod tests {
                 .with_status(200)
                 .with_header("content-type", "application/json")
                 .with_body(serde_json::to_string(places_slice).unwrap())
-                .create();
+                .create_async().await;
         }
 
         let manager = Manager::<Place>::new(config, reqwest::Client::new());
-        let repo = Repository { manager };
+        let repo = Repository { manager: Arc::new(manager) };
 
-        let places = repo.get_all_routes().await;
+        let places: Vec<Place> = repo.get_all(domain::get::Filters { country: None, city: None }, domain::get::Sort::Id, Window::default()).await
+            .unwrap()
+            .items
+            .into_iter()
+            .map(domain_to_repository::place)
+            .collect();
 
         // assert all places are present
         assert_eq!(places.len(), PLACES_COUNT);
         for expected_place in &expected_places {
-            assert!(places.contains(&expected_place));
+            assert!(places.contains(expected_place));
         }
 
         println!("{:?}", places);
     }
-}
+}
\ No newline at end of file
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::contexts::progress::{domain, irepository};
+use crate::errors::{DeleteError, GetAllError, UpdateError};
+
+/// Marks are kept in memory, keyed by the lowercased username and the route id.
+/// Clones share the same marks.
+#[derive(Clone, Default)]
+pub struct Repository {
+    marks: Arc<Mutex<BTreeMap<(String, domain::RouteId), domain::Marks>>>,
+}
+
+impl Repository {
+    fn marks(&self) -> std::sync::MutexGuard<'_, BTreeMap<(String, domain::RouteId), domain::Marks>> {
+        self.marks.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self, username: domain::Username) -> Result<Vec<domain::Marks>, GetAllError> {
+        let username = username.to_lowercase();
+        Ok(self.marks().iter()
+            .filter(|((user, _), _)| *user == username)
+            .map(|(_, marks)| marks.clone())
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn mark(&self, username: domain::Username, route_id: domain::RouteId, flag: domain::Flag, value: bool) -> Result<domain::Marks, UpdateError> {
+        let key = (username.to_lowercase(), route_id.clone());
+        let mut all = self.marks();
+        let marks = all.entry(key.clone()).or_insert_with(|| domain::Marks::new(route_id));
+        match value {
+            true => marks.set(flag),
+            false => marks.clear(flag),
+        }
+        let marks = marks.clone();
+        if marks.is_empty() {
+            all.remove(&key);
+        }
+        Ok(marks)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn forget(&self, username: domain::Username) -> Result<(), DeleteError> {
+        let username = username.to_lowercase();
+        self.marks().retain(|(user, _), _| *user != username);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use irepository::{get::IRepository, put::IRepository as _};
+
+    fn marks(route_id: &str, liked: bool, project: bool) -> domain::Marks {
+        domain::Marks { route_id: String::from(route_id), liked, project }
+    }
+
+    #[tokio::test]
+    async fn test_mark_and_get() {
+        let repo = Repository::default();
+        repo.mark(String::from("jdoe"), String::from("0001-00000002"), domain::Flag::Liked, true).await.unwrap();
+        repo.mark(String::from("JDoe"), String::from("0001-00000001"), domain::Flag::Project, true).await.unwrap();
+        repo.mark(String::from("asmith"), String::from("0001-00000001"), domain::Flag::Project, true).await.unwrap();
+
+        let route_ids: Vec<String> = repo.get_all(String::from("jdoe")).await.unwrap().into_iter().map(|m| m.route_id).collect();
+        assert_eq!(route_ids, vec![String::from("0001-00000001"), String::from("0001-00000002")]);
+        let liked = repo.mark(String::from("jdoe"), String::from("0001-00000001"), domain::Flag::Liked, true).await.unwrap();
+        assert_eq!(liked, marks("0001-00000001", true, true));
+        assert_eq!(repo.get_all(String::from("asmith")).await.unwrap(), vec![marks("0001-00000001", false, true)]);
+    }
+
+    #[tokio::test]
+    async fn test_mark_empty() {
+        let repo = Repository::default();
+        repo.mark(String::from("jdoe"), String::from("0001-00000001"), domain::Flag::Liked, true).await.unwrap();
+        let cleared = repo.mark(String::from("jdoe"), String::from("0001-00000001"), domain::Flag::Liked, false).await.unwrap();
+        assert!(cleared.is_empty());
+        repo.mark(String::from("jdoe"), String::from("0001-00000002"), domain::Flag::Project, false).await.unwrap();
+
+        assert!(repo.get_all(String::from("jdoe")).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,114 @@
+use rusqlite::{params, Row};
+
+use crate::contexts::progress::{domain, irepository};
+use crate::errors::{DeleteError, GetAllError, UpdateError};
+use crate::repositories::storage::Pool;
+
+/// Marks are kept in the database, keyed by the lowercased username and the route id.
+#[derive(Clone)]
+pub struct Repository {
+    pool: Pool,
+}
+
+impl Repository {
+    pub fn new(pool: Pool) -> Self {
+        Repository { pool }
+    }
+}
+
+fn marks(row: &Row) -> rusqlite::Result<domain::Marks> {
+    Ok(domain::Marks { route_id: row.get(0)?, liked: row.get(1)?, project: row.get(2)? })
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self, username: domain::Username) -> Result<Vec<domain::Marks>, GetAllError> {
+        let username = username.to_lowercase();
+        self.pool.run(move |connection| {
+            connection.prepare("SELECT route_id, liked, project FROM marks WHERE user_key = ?1 ORDER BY route_id")?
+                .query_map([username], marks)?
+                .collect()
+        }).await.map_err(|_| GetAllError::InternalServerError)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn mark(&self, username: domain::Username, route_id: domain::RouteId, flag: domain::Flag, value: bool) -> Result<domain::Marks, UpdateError> {
+        let username = username.to_lowercase();
+        let column = match flag {
+            domain::Flag::Liked => "liked",
+            domain::Flag::Project => "project",
+        };
+        let (liked, project) = (flag == domain::Flag::Liked && value, flag == domain::Flag::Project && value);
+        self.pool.run(move |connection| {
+            let transaction = connection.transaction()?;
+            // only the column of the flag is written, a concurrent change to the other one is kept
+            let marks = transaction.query_row(
+                &format!(
+                    "INSERT INTO marks (user_key, route_id, liked, project) VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT (user_key, route_id) DO UPDATE SET {column} = excluded.{column}
+                     RETURNING route_id, liked, project"
+                ),
+                params![username, route_id, liked, project],
+                marks,
+            )?;
+            if marks.is_empty() {
+                transaction.execute("DELETE FROM marks WHERE user_key = ?1 AND route_id = ?2", params![username, route_id])?;
+            }
+            transaction.commit()?;
+            Ok(marks)
+        }).await.map_err(|_| UpdateError::InternalServerError)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn forget(&self, username: domain::Username) -> Result<(), DeleteError> {
+        let username = username.to_lowercase();
+        self.pool.run(move |connection| connection.execute("DELETE FROM marks WHERE user_key = ?1", [username]))
+            .await.map(|_| ()).map_err(|_| DeleteError::InternalServerError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::storage;
+    use irepository::{get::IRepository, put::IRepository as _};
+
+    fn marks(route_id: &str, liked: bool, project: bool) -> domain::Marks {
+        domain::Marks { route_id: String::from(route_id), liked, project }
+    }
+
+    #[tokio::test]
+    async fn test_mark_and_get() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        repo.mark(String::from("jdoe"), String::from("0001-00000002"), domain::Flag::Liked, true).await.unwrap();
+        repo.mark(String::from("JDoe"), String::from("0001-00000001"), domain::Flag::Project, true).await.unwrap();
+        repo.mark(String::from("asmith"), String::from("0001-00000001"), domain::Flag::Project, true).await.unwrap();
+
+        let route_ids: Vec<String> = repo.get_all(String::from("jdoe")).await.unwrap().into_iter().map(|m| m.route_id).collect();
+        assert_eq!(route_ids, vec![String::from("0001-00000001"), String::from("0001-00000002")]);
+        let liked = repo.mark(String::from("jdoe"), String::from("0001-00000001"), domain::Flag::Liked, true).await.unwrap();
+        assert_eq!(liked, marks("0001-00000001", true, true));
+        assert_eq!(repo.get_all(String::from("asmith")).await.unwrap(), vec![marks("0001-00000001", false, true)]);
+
+        let cleared = repo.mark(String::from("asmith"), String::from("0001-00000001"), domain::Flag::Project, false).await.unwrap();
+        assert!(cleared.is_empty());
+        repo.mark(String::from("asmith"), String::from("0001-00000002"), domain::Flag::Liked, false).await.unwrap();
+        assert!(repo.get_all(String::from("asmith")).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_marks() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        let mark = |flag| repo.mark(String::from("jdoe"), String::from("0001-00000001"), flag, true);
+        let (liked, project) = tokio::join!(mark(domain::Flag::Liked), mark(domain::Flag::Project));
+        liked.unwrap();
+        project.unwrap();
+        assert_eq!(repo.get_all(String::from("jdoe")).await.unwrap(), vec![marks("0001-00000001", true, true)]);
+    }
+}
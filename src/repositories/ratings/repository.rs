@@ -0,0 +1,102 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::contexts::ratings::{domain, irepository};
+use crate::errors::{DeleteError, GetAllError, UpdateError};
+
+/// Ratings are kept in memory, keyed by the route id and the lowercased username.
+/// Clones share the same ratings.
+#[derive(Clone, Default)]
+pub struct Repository {
+    ratings: Arc<Mutex<BTreeMap<(domain::RouteId, String), domain::Rating>>>,
+}
+
+impl Repository {
+    fn ratings(&self) -> std::sync::MutexGuard<'_, BTreeMap<(domain::RouteId, String), domain::Rating>> {
+        self.ratings.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self, route_id: domain::RouteId) -> Result<Vec<domain::Rating>, GetAllError> {
+        Ok(self.ratings().iter()
+            .filter(|((route, _), _)| *route == route_id)
+            .map(|(_, rating)| rating.clone())
+            .collect())
+    }
+
+    async fn scores(&self) -> Result<HashMap<domain::RouteId, domain::Score>, GetAllError> {
+        Ok(domain::scores(self.ratings().iter().map(|((route_id, _), rating)| (route_id.clone(), rating.clone()))))
+    }
+
+    async fn scores_by_route(&self, route_ids: Vec<domain::RouteId>) -> Result<HashMap<domain::RouteId, domain::Score>, GetAllError> {
+        Ok(domain::scores(self.ratings().iter()
+            .filter(|((route, _), _)| route_ids.contains(route))
+            .map(|((route_id, _), rating)| (route_id.clone(), rating.clone()))))
+    }
+}
+
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn save(&self, route_id: domain::RouteId, rating: domain::Rating) -> Result<(), UpdateError> {
+        self.ratings().insert((route_id, rating.username.to_lowercase()), rating);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, route_id: domain::RouteId, username: domain::Username) -> Result<(), DeleteError> {
+        match self.ratings().remove(&(route_id.clone(), username.to_lowercase())) {
+            Some(_) => Ok(()),
+            None => Err(DeleteError::NonExistingId(route_id)),
+        }
+    }
+
+    async fn forget(&self, username: domain::Username) -> Result<(), DeleteError> {
+        let username = username.to_lowercase();
+        self.ratings().retain(|(_, user), _| *user != username);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use irepository::{get::IRepository, put::IRepository as _, delete::IRepository as _};
+
+    fn rating(username: &str, stars: u8) -> domain::Rating {
+        domain::Rating { username: String::from(username), stars }
+    }
+
+    #[tokio::test]
+    async fn test_save_and_scores() {
+        let repo = Repository::default();
+        repo.save(String::from("0001-00000001"), rating("jdoe", 2)).await.unwrap();
+        repo.save(String::from("0001-00000001"), rating("JDoe", 4)).await.unwrap();
+        repo.save(String::from("0001-00000001"), rating("asmith", 5)).await.unwrap();
+        repo.save(String::from("0002-00000001"), rating("jdoe", 1)).await.unwrap();
+
+        assert_eq!(repo.get_all(String::from("0001-00000001")).await.unwrap(), vec![rating("asmith", 5), rating("JDoe", 4)]);
+        let scores = repo.scores().await.unwrap();
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores["0001-00000001"], domain::Score { average: 4.5, count: 2 });
+
+        let scores = repo.scores_by_route(vec![String::from("0002-00000001"), String::from("0003-00000001")]).await.unwrap();
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores["0002-00000001"], domain::Score { average: 1.0, count: 1 });
+        assert!(repo.scores_by_route(Vec::new()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete() {
+        let repo = Repository::default();
+        repo.save(String::from("0001-00000001"), rating("jdoe", 3)).await.unwrap();
+
+        assert!(repo.delete(String::from("0001-00000001"), String::from("JDOE")).await.is_ok());
+        assert!(matches!(repo.delete(String::from("0001-00000001"), String::from("jdoe")).await, Err(DeleteError::NonExistingId(_))));
+        assert!(repo.scores().await.unwrap().is_empty());
+    }
+}
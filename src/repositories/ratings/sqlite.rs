@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use rusqlite::{params, params_from_iter};
+
+use crate::contexts::ratings::{domain, irepository};
+use crate::errors::{DeleteError, GetAllError, UpdateError};
+use crate::repositories::storage::Pool;
+
+/// Ratings are kept in the database, keyed by the route id and the lowercased username.
+#[derive(Clone)]
+pub struct Repository {
+    pool: Pool,
+}
+
+impl Repository {
+    pub fn new(pool: Pool) -> Self {
+        Repository { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self, route_id: domain::RouteId) -> Result<Vec<domain::Rating>, GetAllError> {
+        self.pool.run(move |connection| {
+            connection.prepare("SELECT username, stars FROM ratings WHERE route_id = ?1 ORDER BY user_key")?
+                .query_map([route_id], |row| Ok(domain::Rating { username: row.get(0)?, stars: row.get(1)? }))?
+                .collect()
+        }).await.map_err(|_| GetAllError::InternalServerError)
+    }
+
+    async fn scores(&self) -> Result<HashMap<domain::RouteId, domain::Score>, GetAllError> {
+        let ratings: Vec<(domain::RouteId, domain::Rating)> = self.pool.run(|connection| {
+            connection.prepare("SELECT route_id, username, stars FROM ratings")?
+                .query_map([], |row| Ok((row.get(0)?, domain::Rating { username: row.get(1)?, stars: row.get(2)? })))?
+                .collect()
+        }).await.map_err(|_| GetAllError::InternalServerError)?;
+        Ok(domain::scores(ratings))
+    }
+
+    async fn scores_by_route(&self, route_ids: Vec<domain::RouteId>) -> Result<HashMap<domain::RouteId, domain::Score>, GetAllError> {
+        if route_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let ratings: Vec<(domain::RouteId, domain::Rating)> = self.pool.run(move |connection| {
+            let placeholders = vec!["?"; route_ids.len()].join(", ");
+            connection.prepare(&format!("SELECT route_id, username, stars FROM ratings WHERE route_id IN ({placeholders})"))?
+                .query_map(params_from_iter(route_ids), |row| Ok((row.get(0)?, domain::Rating { username: row.get(1)?, stars: row.get(2)? })))?
+                .collect()
+        }).await.map_err(|_| GetAllError::InternalServerError)?;
+        Ok(domain::scores(ratings))
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn save(&self, route_id: domain::RouteId, rating: domain::Rating) -> Result<(), UpdateError> {
+        self.pool.run(move |connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO ratings (route_id, user_key, username, stars) VALUES (?1, ?2, ?3, ?4)",
+                params![route_id, rating.username.to_lowercase(), rating.username, rating.stars],
+            )
+        }).await.map(|_| ()).map_err(|_| UpdateError::InternalServerError)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, route_id: domain::RouteId, username: domain::Username) -> Result<(), DeleteError> {
+        let route = route_id.clone();
+        let deleted = self.pool.run(move |connection| {
+            connection.execute("DELETE FROM ratings WHERE route_id = ?1 AND user_key = ?2", [route, username.to_lowercase()])
+        }).await.map_err(|_| DeleteError::InternalServerError)?;
+
+        match deleted {
+            0 => Err(DeleteError::NonExistingId(route_id)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn forget(&self, username: domain::Username) -> Result<(), DeleteError> {
+        let username = username.to_lowercase();
+        self.pool.run(move |connection| connection.execute("DELETE FROM ratings WHERE user_key = ?1", [username]))
+            .await.map(|_| ()).map_err(|_| DeleteError::InternalServerError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::storage;
+    use irepository::{get::IRepository, put::IRepository as _, delete::IRepository as _};
+
+    fn rating(username: &str, stars: u8) -> domain::Rating {
+        domain::Rating { username: String::from(username), stars }
+    }
+
+    #[tokio::test]
+    async fn test_save_scores_and_delete() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        repo.save(String::from("0001-00000001"), rating("jdoe", 2)).await.unwrap();
+        repo.save(String::from("0001-00000001"), rating("JDoe", 4)).await.unwrap();
+        repo.save(String::from("0001-00000001"), rating("asmith", 5)).await.unwrap();
+        repo.save(String::from("0002-00000001"), rating("jdoe", 1)).await.unwrap();
+
+        assert_eq!(repo.get_all(String::from("0001-00000001")).await.unwrap(), vec![rating("asmith", 5), rating("JDoe", 4)]);
+        let scores = repo.scores().await.unwrap();
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores["0001-00000001"], domain::Score { average: 4.5, count: 2 });
+
+        let scores = repo.scores_by_route(vec![String::from("0002-00000001"), String::from("0003-00000001")]).await.unwrap();
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores["0002-00000001"], domain::Score { average: 1.0, count: 1 });
+        assert!(repo.scores_by_route(Vec::new()).await.unwrap().is_empty());
+
+        assert!(repo.delete(String::from("0002-00000001"), String::from("JDOE")).await.is_ok());
+        assert!(matches!(repo.delete(String::from("0002-00000001"), String::from("jdoe")).await, Err(DeleteError::NonExistingId(_))));
+        assert_eq!(repo.scores().await.unwrap().len(), 1);
+    }
+}
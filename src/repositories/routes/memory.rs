@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::contexts::routes::{domain, irepository};
+use crate::contexts::sources::domain::{Aggregate, Window};
+use crate::errors::{CreateError, DeleteError, GetAllError, GetError, UpdateError};
+use crate::repositories::common::RelativeId;
+use crate::repositories::places::memory::{self as places, SEED_SOURCE};
+use super::repository::{self, repository_to_domain, to_absolute_place_id};
+
+/// Routes are kept in memory instead of being asked to the sources, keyed by their id written in full.
+/// Routes are on places of the in-memory places, and take the id following the last one of the source of their place.
+/// Clones share the same routes.
+#[derive(Clone)]
+pub struct Repository {
+    routes: Arc<Mutex<BTreeMap<domain::RouteId, domain::RouteData>>>,
+    places: places::Repository,
+}
+
+impl Repository {
+    pub fn new(places: places::Repository) -> Self {
+        Repository { routes: Arc::default(), places }
+    }
+
+    /// The route of `schemas/route/example.json`, on a place of the seed source.
+    pub fn seeded(places: places::Repository) -> Self {
+        let mut example: repository::Route = serde_json::from_str(include_str!("../../../schemas/route/example.json")).expect("The route example is invalid");
+        let resource_id = example.id.parse().expect("The route example has an invalid id");
+        example.id = RelativeId { source_id: SEED_SOURCE, resource_id }.to_string();
+        to_absolute_place_id(&mut example).expect("The route example has an invalid place id");
+        let route = repository_to_domain::route(example);
+
+        let repo = Repository::new(places);
+        repo.routes().insert(route.id, route.data);
+        repo
+    }
+
+    fn routes(&self) -> std::sync::MutexGuard<'_, BTreeMap<domain::RouteId, domain::RouteData>> {
+        self.routes.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The place the route is on, provided it exists.
+    fn place(&self, data: &domain::RouteData) -> Result<RelativeId, Reference> {
+        let place_id = data.place_id.parse::<RelativeId>().map_err(|_| Reference::Malformed)?;
+        match self.places.contains(&place_id) {
+            true => Ok(place_id),
+            false => Err(Reference::Missing),
+        }
+    }
+}
+
+enum Reference {
+    Malformed,
+    Missing,
+}
+
+/// Whether the route has every tag and property of the filters.
+/// Grades and ratings are left to the use case, as with the sources.
+fn matches(filters: &domain::get::Filters, data: &domain::RouteData) -> bool {
+    filters.tags.iter().all(|tag| data.tags.contains(tag))
+        && filters.properties.iter().all(|(key, value)| data.properties.get(key) == Some(value))
+}
+
+fn route(id: domain::RouteId, data: domain::RouteData) -> domain::Route {
+    domain::Route { id, data, community_grade: None, rating: None }
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    /// Every matching route is returned, the use case sorts and pages them.
+    async fn get_all(&self, filters: domain::get::Filters, _sort: domain::get::Sort, _window: Window) -> Result<Aggregate<domain::Route>, GetAllError> {
+        let items = self.routes().iter()
+            .filter(|(_, data)| matches(&filters, data))
+            .map(|(id, data)| route(id.clone(), data.clone()))
+            .collect();
+        Ok(Aggregate { items, sources: Vec::new() })
+    }
+
+    async fn get(&self, id: domain::RouteId) -> Result<domain::Route, GetError> {
+        let Ok(key) = id.parse::<RelativeId>() else { return Err(GetError::MalformedId(id)) };
+        let key = key.to_string();
+        match self.routes().get(&key) {
+            Some(data) => Ok(route(key, data.clone())),
+            None => Err(GetError::NonExistingId(id)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::post::IRepository for Repository {
+    async fn create(&self, mut data: domain::RouteData) -> Result<domain::Route, CreateError> {
+        let place_id = match self.place(&data) {
+            Ok(place_id) => place_id,
+            Err(Reference::Malformed) => return Err(CreateError::MalformedId(data.place_id)),
+            Err(Reference::Missing) => return Err(CreateError::NonExistingReference(data.place_id)),
+        };
+        data.place_id = place_id.to_string();
+
+        let mut routes = self.routes();
+        let id = places::next_id(place_id.source_id, routes.keys());
+        routes.insert(id.clone(), data.clone());
+        Ok(route(id, data))
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn update(&self, id: domain::RouteId, mut data: domain::RouteData) -> Result<(), UpdateError> {
+        let Ok(key) = id.parse::<RelativeId>() else { return Err(UpdateError::MalformedId(id)) };
+        let place_id = match self.place(&data) {
+            Ok(place_id) => place_id,
+            Err(Reference::Malformed) => return Err(UpdateError::MalformedId(data.place_id)),
+            Err(Reference::Missing) => return Err(UpdateError::NonExistingReference(data.place_id)),
+        };
+        data.place_id = place_id.to_string();
+
+        match self.routes().get_mut(&key.to_string()) {
+            Some(stored) => {
+                *stored = data;
+                Ok(())
+            },
+            None => Err(UpdateError::NonExistingId(id)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, id: domain::RouteId) -> Result<(), DeleteError> {
+        let Ok(key) = id.parse::<RelativeId>() else { return Err(DeleteError::MalformedId(id)) };
+        match self.routes().remove(&key.to_string()) {
+            Some(_) => Ok(()),
+            None => Err(DeleteError::NonExistingId(id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use irepository::{get::IRepository, post::IRepository as _, put::IRepository as _, delete::IRepository as _};
+
+    fn filters(tags: &[&str]) -> domain::get::Filters {
+        domain::get::Filters {
+            min_grade  : None,
+            max_grade  : None,
+            min_rating : None,
+            tags       : tags.iter().map(|tag| String::from(*tag)).collect(),
+            properties : domain::RouteProperties::new(),
+        }
+    }
+
+    fn ids(aggregate: Aggregate<domain::Route>) -> Vec<String> {
+        aggregate.items.into_iter().map(|route| route.id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_seeded() {
+        let repo = Repository::seeded(places::Repository::seeded());
+        let route = repo.get(String::from("1-1")).await.unwrap();
+        assert_eq!((route.id.as_str(), route.data.place_id.as_str(), route.data.name.as_str()), ("0001-00000001", "0001-00000001", "Rocky Road"));
+        assert_eq!(route.data.grade.text, "5c");
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get_all() {
+        let repo = Repository::seeded(places::Repository::seeded());
+        let mut data = repo.get(String::from("0001-00000001")).await.unwrap().data;
+        data.place_id = String::from("1-1");
+        data.tags = vec![String::from("twisty")];
+        assert_eq!(repo.create(data.clone()).await.unwrap().id, "0001-00000002");
+
+        assert_eq!(ids(repo.get_all(filters(&[]), domain::get::Sort::Id, Window::default()).await.unwrap()), ["0001-00000001", "0001-00000002"]);
+        assert_eq!(ids(repo.get_all(filters(&["twisty", "challenging"]), domain::get::Sort::Id, Window::default()).await.unwrap()), ["0001-00000001"]);
+
+        data.place_id = String::from("0001-00000002");
+        assert!(matches!(repo.create(data.clone()).await, Err(CreateError::NonExistingReference(_))));
+        data.place_id = String::from("garbage");
+        assert!(matches!(repo.create(data).await, Err(CreateError::MalformedId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete() {
+        let repo = Repository::seeded(places::Repository::seeded());
+        let mut data = repo.get(String::from("0001-00000001")).await.unwrap().data;
+        data.name = String::from("Smooth Road");
+        repo.update(String::from("0001-00000001"), data.clone()).await.unwrap();
+        assert_eq!(repo.get(String::from("0001-00000001")).await.unwrap().data.name, "Smooth Road");
+        assert!(matches!(repo.update(String::from("0001-00000002"), data).await, Err(UpdateError::NonExistingId(_))));
+
+        repo.delete(String::from("0001-00000001")).await.unwrap();
+        assert!(matches!(repo.delete(String::from("0001-00000001")).await, Err(DeleteError::NonExistingId(_))));
+    }
+}
@@ -1,14 +1,15 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use async_trait::async_trait;
 use reqwest::StatusCode;
-use rocket::http::Status;
 
 use crate::repositories::{
-    common::{self, impl_identifiable_for, Identifiable, Manager, FilterList, RelativeId, FetchError},
-    config::Config,
+    common::{self, impl_identifiable_for, Identifiable, Manager, Upstream, FilterList, RelativeId, FetchError, repository_to_domain::{get_error, create_error, update_error, delete_error}},
+    schemas::{Kind, Schema, Validated},
 };
 use crate::typeutil::repositories::Date;
 use crate::contexts::routes::{irepository, domain};
+use crate::contexts::sources::domain::{Aggregate, Window};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 pub struct Rules {
@@ -34,16 +35,50 @@ pub struct Route {
 }
 impl_identifiable_for!(Route);
 
+impl Validated for Route {
+    fn schema() -> &'static Schema {
+        Schema::of(Kind::Route)
+    }
+}
+
+/// Body sent to a source when creating or updating a route, `place_id` is relative to that source.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct RouteData {
+    pub place_id     : String,
+    pub name         : String,
+    pub description  : String,
+    pub grade        : String,
+    pub color        : String,
+    pub sector       : String,
+    pub opening_date : Date,
+    pub closing_date : Option<Date>,
+    pub rules        : Rules,
+    pub tags         : Vec<String>,
+    pub properties   : HashMap<String, String>,
+}
+
+/// Sources only know their own place ids, so the place id is made absolute with the source of the route.
+/// The route id must already be absolute.
+pub(super) fn to_absolute_place_id(route: &mut Route) -> Result<(), FetchError> {
+    let source_id = route.id.parse::<RelativeId>().map_err(|_| FetchError::BadUpstreamId(route.id.clone()))?.source_id;
+    let resource_id = route.place_id.parse::<u32>().map_err(|_| FetchError::BadUpstreamId(route.place_id.clone()))?;
+    route.place_id = RelativeId { source_id, resource_id }.to_string();
+    Ok(())
+}
+
 mod domain_to_repository {
-    use super::{domain, Date, Route, Rules, FilterList};
+    use super::{domain, Date, RouteData, Rules, FilterList, Window};
+    #[cfg(test)]
+    use super::Route;
 
+    #[cfg(test)]
     pub fn route(r: domain::Route) -> Route {
         Route {
             id           : r.id,
             place_id     : r.data.place_id,
             name         : r.data.name,
             description  : r.data.description,
-            grade        : r.data.grade,
+            grade        : r.data.grade.text,
             color        : r.data.color,
             sector       : r.data.sector,
             rules        : rules(r.data.rules),
@@ -54,6 +89,22 @@ mod domain_to_repository {
         }
     }
 
+    pub fn route_data(d: domain::RouteData, place_resource_id: u32) -> RouteData {
+        RouteData {
+            place_id     : place_resource_id.to_string(),
+            name         : d.name,
+            description  : d.description,
+            grade        : d.grade.text,
+            color        : d.color,
+            sector       : d.sector,
+            rules        : rules(d.rules),
+            opening_date : date(d.opening_date),
+            closing_date : d.closing_date.map(date),
+            tags         : d.tags,
+            properties   : d.properties,
+        }
+    }
+
     fn date(d: domain::Date) -> Date { Date(d) }
     fn rules(r: domain::Rules) -> Rules
     {
@@ -64,17 +115,10 @@ mod domain_to_repository {
         }
     }
 
+    /// Grade bounds are not forwarded: sources grade on scales of their own, the core keeps the routes within them afterwards.
     pub fn get_filters(f: domain::get::Filters) -> FilterList {
         let mut filters = FilterList::new();
 
-        if let Some(min_grade) = f.min_grade {
-            filters.push((String::from("min_grade"), min_grade));
-        }
-
-        if let Some(max_grade) = f.max_grade {
-            filters.push((String::from("max_grade"), max_grade));
-        }
-
         for tag in f.tags {
             filters.push((String::from("tags"), tag));
         }
@@ -86,10 +130,31 @@ mod domain_to_repository {
 
         filters
     }
+
+    /// Asks the sources for the routes up to the end of the window, plus one to know if there are more.
+    /// Unless the sources match and order the routes exactly as the core does, they are asked for every route:
+    /// a route the core drops or orders elsewhere would leave out one that belongs in the window.
+    pub fn pagination(sort: domain::get::Sort, window: Window, filters: &domain::get::Filters) -> FilterList {
+        // names are compared regardless of case and grades on the scale of the core
+        let sort = match sort {
+            domain::get::Sort::Id => "id",
+            domain::get::Sort::OpeningDate => "opening_date",
+            domain::get::Sort::Name | domain::get::Sort::Grade | domain::get::Sort::Rating => return FilterList::new(),
+        };
+        if !filters.is_upstream() {
+            return FilterList::new();
+        }
+        vec![
+            (String::from("sort"), String::from(sort)),
+            (String::from("limit"), (window.end() + 1).to_string()),
+        ]
+    }
 }
 
-mod repository_to_domain {
-    use super::{domain, Route, Rules, HashMap, FilterList, Date};
+pub(super) mod repository_to_domain {
+    use super::{domain, Route, Rules, Date};
+    #[cfg(test)]
+    use super::{HashMap, FilterList};
 
     pub fn route(r: Route) -> domain::Route {
         domain::Route {
@@ -98,7 +163,7 @@ mod repository_to_domain {
                 place_id    : r.place_id,
                 name        : r.name,
                 description : r.description,
-                grade       : r.grade,
+                grade       : domain::Grade::parse(&r.grade),
                 color       : r.color,
                 sector      : r.sector,
                 rules       : rules(r.rules),
@@ -106,7 +171,9 @@ mod repository_to_domain {
                 closing_date: r.closing_date.map(date),
                 tags        : r.tags,
                 properties  : r.properties,
-            }
+            },
+            community_grade : None,
+            rating          : None,
         }
     }
     fn date(d: Date) -> domain::Date { *d }
@@ -119,18 +186,18 @@ mod repository_to_domain {
         }
     }
 
+    #[cfg(test)]
     pub fn get_filters(f: FilterList) -> domain::get::Filters {
         let mut filters = domain::get::Filters {
             min_grade  : None,
             max_grade  : None,
+            min_rating : None,
             tags       : Vec::new(),
             properties : HashMap::new(),
         };
 
         for (key, value) in f {
             match key.as_str() {
-                "min_grade" => filters.min_grade = Some(value),
-                "max_grade" => filters.max_grade = Some(value),
                 "tags"      => filters.tags.push(value),
                 // may need to be handled differently
                 prop if prop.starts_with("properties.") => 
@@ -145,15 +212,15 @@ mod repository_to_domain {
     }
 }
 
+/// Clones share the same manager, hence the same cache and circuit breakers.
+#[derive(Clone)]
 pub struct Repository {
-    manager: Manager<Route>,
+    manager: Arc<Manager<Route>>,
 }
 
-impl Default for Repository {
-    fn default() -> Self {
-        let config = Config::from_env();
-        let manager = Manager::<Route>::new(config, reqwest::Client::new());
-        Self { manager }
+impl Repository {
+    pub fn new(upstream: &Upstream) -> Self {
+        Self { manager: Arc::new(Manager::<Route>::shared(upstream)) }
     }
 }
 
@@ -161,23 +228,36 @@ use crate::errors::{GetError, GetAllError};
 #[async_trait]
 impl irepository::get::IRepository for Repository
 {
-    async fn get_all(&self, filters: domain::get::Filters) -> Result<Vec<domain::Route>, GetAllError> 
+    async fn get_all(&self, filters: domain::get::Filters, sort: domain::get::Sort, window: Window) -> Result<Aggregate<domain::Route>, GetAllError> 
     {
-        let (routes, _errors) = self.manager.dispatch(
-            common::path_with_filters("routes", domain_to_repository::get_filters(filters)).as_str()).await;
+        let pagination = domain_to_repository::pagination(sort, window, &filters);
+        let (mut routes, reports) = self.manager.dispatch(
+            common::path_with_filters("routes", domain_to_repository::get_filters(filters)).as_str(),
+            pagination).await;
 
-        Ok(routes.into_iter().map(repository_to_domain::route).collect())
+        // Routes whose place id can't be made absolute are dropped rather than failing the whole list
+        routes.retain_mut(|route| match to_absolute_place_id(route) {
+            Ok(()) => true,
+            Err(fetch_err) => { eprintln!("{fetch_err:?}"); false },
+        });
+        Ok(Aggregate {
+            items   : routes.into_iter().map(repository_to_domain::route).collect(),
+            sources : reports.into_iter().map(common::repository_to_domain::source_status).collect(),
+        })
     }
     
     async fn get(&self, id: domain::RouteId) -> Result<domain::Route, GetError> 
     {
-        let RelativeId{source_id, resource_id} = RelativeId::from_str(id.as_str());
+        let Ok(RelativeId{source_id, resource_id}) = id.parse() else { return Err(GetError::MalformedId(id)) };
         match self.manager.get(source_id, format!("routes/{resource_id}").as_str()).await
         {
-            Ok(route_got) => if let Some(route) = route_got 
-                { Ok(repository_to_domain::route(route)) } else 
-                { Err(GetError::NonExistingId(id)) },
-            Err(fetch_err) => { eprintln!("{fetch_err:?}"); Err(GetError::InternalServerError) },
+            Ok(Some(mut route)) => match to_absolute_place_id(&mut route)
+            {
+                Ok(()) => Ok(repository_to_domain::route(route)),
+                Err(fetch_err) => Err(get_error(fetch_err, source_id, id)),
+            },
+            Ok(None) => Err(GetError::NonExistingId(id)),
+            Err(fetch_err) => Err(get_error(fetch_err, source_id, id)),
         }
     }
 }
@@ -188,24 +268,40 @@ impl irepository::post::IRepository for Repository
 {
     async fn create(&self, data: domain::RouteData) -> Result<domain::Route, CreateError>
     {
-
-        unimplemented!("create")
-        // Ok(domain::Route
-        // {
-        //     id   : domain::RouteId::from("0"),
-        //     data : route_data,
-        // })
+        // The route is created in the source that owns its place
+        let place_id = data.place_id.clone();
+        let Ok(RelativeId{source_id, resource_id: place_resource_id}) = place_id.parse() else { return Err(CreateError::MalformedId(place_id)) };
+        match self.manager.post(source_id, "routes", &domain_to_repository::route_data(data, place_resource_id)).await
+        {
+            Ok(mut route) => match to_absolute_place_id(&mut route)
+            {
+                Ok(()) => Ok(repository_to_domain::route(route)),
+                Err(fetch_err) => Err(create_error(fetch_err, source_id, place_id)),
+            },
+            Err(fetch_err) => Err(create_error(fetch_err, source_id, place_id)),
+        }
     }
 }
 
-use crate::errors::UpdateError;
+use crate::errors::{SourceFailure, UpdateError};
 #[async_trait]
 impl irepository::put::IRepository for Repository
 {
-    async fn update(&self, _id: domain::RouteId, _data: domain::RouteData) -> Result<(), UpdateError> 
+    async fn update(&self, id: domain::RouteId, data: domain::RouteData) -> Result<(), UpdateError> 
     {
-        unimplemented!("update")
-        // Err(NonExistingId(_id))
+        let Ok(RelativeId{source_id, resource_id}) = id.parse() else { return Err(UpdateError::MalformedId(id)) };
+        let Ok(place_id) = data.place_id.parse::<RelativeId>() else { return Err(UpdateError::MalformedId(data.place_id)) };
+
+        match self.manager.put(source_id, format!("routes/{resource_id}").as_str(), &domain_to_repository::route_data(data, place_id.resource_id)).await
+        {
+            Ok(status) => match status
+            {
+                StatusCode::NOT_FOUND => Err(UpdateError::NonExistingId(id)),
+                _ if status.is_success() => Ok(()),
+                _ => Err(UpdateError::Source(SourceFailure { source_id, cause: FetchError::Status(status) })),
+            },
+            Err(fetch_err) => Err(update_error(fetch_err, source_id, id)),
+        }
     }
 }
 
@@ -215,20 +311,192 @@ impl irepository::delete::IRepository for Repository
 {
     async fn delete(&self, id: domain::RouteId) -> Result<(), DeleteError> 
     {
-        let RelativeId{source_id, resource_id} = RelativeId::from_str(id.as_str());
+        let Ok(RelativeId{source_id, resource_id}) = id.parse() else { return Err(DeleteError::MalformedId(id)) };
         match self.manager.delete(source_id, format!("routes/{resource_id}").as_str()).await
         {
             Ok(status) => match status
             {
                 StatusCode::NOT_FOUND => Err(DeleteError::NonExistingId(id)),
                 _ if status.is_success() => Ok(()),
-                _ => Err(DeleteError::InternalServerError),
+                _ => Err(DeleteError::Source(SourceFailure { source_id, cause: FetchError::Status(status) })),
             },
-            Err(fetch_err) => 
-            {
-                eprintln!("{fetch_err:?}");
-                Err(DeleteError::InternalServerError)
-            }
+            Err(fetch_err) => Err(delete_error(fetch_err, source_id, id)),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use irepository::{post::IRepository as _, put::IRepository as _};
+    use crate::repositories::config::Config;
+
+    fn repository(server: &mockito::ServerGuard) -> Repository {
+        let config = Config::from_str(&format!(
+            r#"
+            [[sources]]
+            name = "Test"
+            id = 1
+            url = "{}"
+            "#,
+            server.url(),
+        ));
+        Repository { manager: Arc::new(Manager::<Route>::new(config, reqwest::Client::new())) }
+    }
+
+    fn route_data(place_id: &str) -> domain::RouteData {
+        domain::RouteData {
+            place_id     : String::from(place_id),
+            name         : String::from("Route 1"),
+            description  : String::from("This is a great route"),
+            grade        : domain::Grade::parse("6a"),
+            color        : String::from("green"),
+            sector       : String::from("R1"),
+            opening_date : domain::Date::from_ymd_opt(2021, 1, 1).unwrap(),
+            closing_date : None,
+            rules        : domain::Rules { sitstart: false, modules_allowed: false, edges_allowed: false },
+            tags         : vec![String::from("vertical")],
+            properties   : HashMap::new(),
         }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_create() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("POST", "/routes")
+            .match_body(mockito::Matcher::PartialJsonString(String::from(r#"{ "place_id": "3", "name": "Route 1" }"#)))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"
+                {
+                    "id": "7",
+                    "place_id": "3",
+                    "name": "Route 1",
+                    "description": "This is a great route",
+                    "grade": "6a",
+                    "color": "green",
+                    "sector": "R1",
+                    "opening_date": "2021-01-01",
+                    "closing_date": null,
+                    "rules": { "sitstart": false, "modules_allowed": false, "edges_allowed": false },
+                    "tags": ["vertical"],
+                    "properties": {}
+                }
+                "#,
+            )
+            .create_async().await;
+
+        let repo = repository(&server);
+        let route = domain_to_repository::route(repo.create(route_data("0001-00000003")).await.unwrap());
+
+        mock.assert_async().await;
+        assert_eq!(route.id, "0001-00000007");
+        assert_eq!(route.place_id, "0001-00000003");
+        assert_eq!(route.name, "Route 1");
+        assert_eq!(route.opening_date, Date(domain::Date::from_ymd_opt(2021, 1, 1).unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_create_failure() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/routes").with_status(500).create_async().await;
+
+        let repo = repository(&server);
+        let failure = repo.create(route_data("0001-00000003")).await;
+        assert!(matches!(failure, Err(CreateError::Source(SourceFailure { source_id: 1, cause: FetchError::Status(StatusCode::INTERNAL_SERVER_ERROR) }))));
+    }
+
+    #[tokio::test]
+    async fn test_update() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("PUT", "/routes/7")
+            .match_body(mockito::Matcher::PartialJsonString(String::from(r#"{ "place_id": "3" }"#)))
+            .with_status(204)
+            .create_async().await;
+
+        let repo = repository(&server);
+        assert!(repo.update(String::from("0001-00000007"), route_data("0001-00000003")).await.is_ok());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_update_non_existing() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("PUT", "/routes/8").with_status(404).create_async().await;
+
+        let repo = repository(&server);
+        assert!(matches!(
+            repo.update(String::from("0001-00000008"), route_data("0001-00000003")).await,
+            Err(UpdateError::NonExistingId(id)) if id == "0001-00000008"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_and_unknown_ids() {
+        use irepository::{get::IRepository as _, delete::IRepository as _};
+        let server = mockito::Server::new_async().await;
+
+        let repo = repository(&server);
+        assert!(matches!(repo.get(String::from("garbage")).await, Err(GetError::MalformedId(id)) if id == "garbage"));
+        assert!(matches!(repo.get(String::from("0009-00000001")).await, Err(GetError::UnknownSource(_))));
+        assert!(matches!(repo.delete(String::from("0009-00000001")).await, Err(DeleteError::UnknownSource(_))));
+        assert!(matches!(repo.create(route_data("not-an-id")).await, Err(CreateError::MalformedId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_bad_upstream_id() {
+        use irepository::get::IRepository as _;
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/routes/7")
+            .with_status(200)
+            .with_body(r#"{ "id": "seven", "place_id": "3", "name": "", "description": "", "grade": "6a", "color": "",
+                "sector": "", "opening_date": "2021-01-01", "closing_date": null,
+                "rules": { "sitstart": false, "modules_allowed": false, "edges_allowed": false }, "tags": [], "properties": {} }"#)
+            .create_async().await;
+
+        let repo = repository(&server);
+        assert!(matches!(repo.get(String::from("0001-00000007")).await, Err(GetError::BadUpstreamId(id)) if id == "seven"));
+    }
+
+    #[test]
+    fn test_get_filters() {
+        let filters = domain_to_repository::get_filters(domain::get::Filters {
+            min_grade  : Some(domain::Grade::parse("5c")),
+            max_grade  : None,
+            min_rating : Some(4.0),
+            tags       : vec![String::from("roof")],
+            properties : HashMap::from([(String::from("height"), String::from("10"))]),
+        });
+        // grade bounds and ratings are checked by the core only
+        assert_eq!(filters, vec![
+            (String::from("tags"), String::from("roof")),
+            (String::from("properties.height"), String::from("10")),
+        ]);
+
+        let filters = repository_to_domain::get_filters(filters);
+        assert_eq!(filters.tags, vec![String::from("roof")]);
+        assert_eq!(filters.properties.get("height").map(String::as_str), Some("10"));
+    }
+    #[test]
+    fn test_pagination() {
+        let window = Window { offset: 10, limit: 10 };
+        let filters = repository_to_domain::get_filters(FilterList::new());
+        assert_eq!(domain_to_repository::pagination(domain::get::Sort::OpeningDate, window, &filters), vec![
+            (String::from("sort"), String::from("opening_date")),
+            (String::from("limit"), String::from("21")),
+        ]);
+        // sources know nothing about ratings, and order names and grades their own way
+        assert!(domain_to_repository::pagination(domain::get::Sort::Rating, window, &filters).is_empty());
+        assert!(domain_to_repository::pagination(domain::get::Sort::Name, window, &filters).is_empty());
+        assert!(domain_to_repository::pagination(domain::get::Sort::Grade, window, &filters).is_empty());
+        let rated = domain::get::Filters { min_rating: Some(4.0), ..filters.clone() };
+        assert!(domain_to_repository::pagination(domain::get::Sort::Id, window, &rated).is_empty());
+        let graded = domain::get::Filters { min_grade: Some(domain::Grade::parse("6a")), ..filters };
+        assert!(domain_to_repository::pagination(domain::get::Sort::Id, window, &graded).is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::OnceLock;
+
+use jsonschema::JSONSchema;
+use serde_json::Value;
+
+/// The resources answered by the sources, whose JSON schemas in `schemas/` are compiled into the binary.
+/// The other schemas describe data owned by the core, which never comes from a source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Place,
+    Route,
+}
+
+/// A compiled JSON schema.
+pub struct Schema(JSONSchema);
+
+impl Schema {
+    /// Compiles `schema`, which is part of the binary: a schema that does not compile is a bug.
+    pub fn compile(schema: &str) -> Self {
+        let schema: Value = serde_json::from_str(schema).expect("A schema is not valid JSON");
+        Schema(JSONSchema::compile(&schema).expect("A schema is not a valid JSON schema"))
+    }
+
+    /// The schema of the kind, compiled on first use.
+    pub fn of(kind: Kind) -> &'static Schema {
+        static PLACE: OnceLock<Schema> = OnceLock::new();
+        static ROUTE: OnceLock<Schema> = OnceLock::new();
+        match kind {
+            Kind::Place => PLACE.get_or_init(|| Schema::compile(include_str!("../../schemas/place/schema.json"))),
+            Kind::Route => ROUTE.get_or_init(|| Schema::compile(include_str!("../../schemas/route/schema.json"))),
+        }
+    }
+
+    /// Every violation of the schema by `item`, prefixed by where it occurs in the item (`/` being the item itself).
+    ///
+    /// Ex: `/name: 3 is not of type "string"`
+    pub fn check(&self, item: &Value) -> Result<(), Vec<String>> {
+        self.0.validate(item).map_err(|errors| {
+            errors
+                .map(|error| match error.instance_path.to_string() {
+                    path if path.is_empty() => format!("/: {error}"),
+                    path => format!("{path}: {error}"),
+                })
+                .collect()
+        })
+    }
+}
+
+/// Items answered by the sources, checked against their schema before being read.
+pub trait Validated {
+    fn schema() -> &'static Schema;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        let example = |example: &str| serde_json::from_str::<Value>(example).unwrap();
+        assert_eq!(Schema::of(Kind::Place).check(&example(include_str!("../../schemas/place/example.json"))), Ok(()));
+        assert_eq!(Schema::of(Kind::Route).check(&example(include_str!("../../schemas/route/example.json"))), Ok(()));
+    }
+
+    #[test]
+    fn test_check() {
+        let mut route: Value = serde_json::from_str(include_str!("../../schemas/route/example.json")).unwrap();
+        route["name"] = Value::from(3);
+        route["opening_date"] = Value::from("yesterday");
+        route.as_object_mut().unwrap().remove("tags");
+
+        let mut violations = Schema::of(Kind::Route).check(&route).unwrap_err();
+        violations.sort();
+        assert_eq!(violations, [
+            r#"/: "tags" is a required property"#,
+            r#"/name: 3 is not of type "string""#,
+            r#"/opening_date: "yesterday" is not a "date""#,
+        ]);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::contexts::sources::{domain, irepository};
+use crate::errors::{GetAllError, GetError};
+use crate::repositories::common::Upstream;
+use crate::repositories::config::Source;
+use crate::repositories::health::Checks;
+
+mod repository_to_domain {
+    use super::{Checks, Source};
+    use crate::contexts::sources::domain;
+
+    pub fn health(source: &Source, checks: Option<Checks>) -> domain::Health {
+        let state = match &checks {
+            None => domain::State::Unknown,
+            Some(checks) if checks.up => domain::State::Up,
+            Some(_) => domain::State::Down,
+        };
+        domain::Health {
+            id           : source.id,
+            name         : source.name.clone(),
+            state,
+            latency      : checks.as_ref().map(|checks| checks.latency),
+            last_check   : checks.as_ref().map(|checks| checks.last_check),
+            last_success : checks.as_ref().and_then(|checks| checks.last_success),
+            last_error   : checks.and_then(|checks| checks.last_error),
+        }
+    }
+}
+
+/// The health of the sources of `upstream`, as found out by its probes.
+/// Without upstream, as when places and routes are kept in memory, there is no source.
+#[derive(Clone, Default)]
+pub struct Repository {
+    upstream: Option<Upstream>,
+}
+
+impl Repository {
+    pub fn new(upstream: &Upstream) -> Self {
+        Self { upstream: Some(upstream.clone()) }
+    }
+
+    fn sources(&self) -> &[Source] {
+        self.upstream.as_ref().map(Upstream::sources).unwrap_or_default()
+    }
+
+    fn health(&self, source: &Source) -> domain::Health {
+        repository_to_domain::health(source, self.upstream.as_ref().and_then(|upstream| upstream.checks(source.id)))
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self) -> Result<Vec<domain::Health>, GetAllError> {
+        let mut health: Vec<domain::Health> = self.sources().iter().map(|source| self.health(source)).collect();
+        health.sort_by_key(|health| health.id);
+        Ok(health)
+    }
+
+    async fn get(&self, id: domain::SourceId) -> Result<domain::Health, GetError> {
+        match self.sources().iter().find(|source| source.id == id) {
+            Some(source) => Ok(self.health(source)),
+            None => Err(GetError::NonExistingId(id.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use irepository::get::IRepository;
+    use crate::repositories::config::Config;
+
+    #[tokio::test]
+    async fn test_probe() {
+        let mut up = mockito::Server::new_async().await;
+        up.mock("GET", "/places").with_status(200).with_body("[]").create_async().await;
+        let mut down = mockito::Server::new_async().await;
+        down.mock("GET", "/health").with_status(503).create_async().await;
+
+        let config = Config::from_str(&format!(
+            r#"
+            sources = [
+                {{ id = 2, name = "Down", url = "{}", probe_path = "health" }},
+                {{ id = 1, name = "Up", url = "{}" }},
+                {{ id = 3, name = "Unprobed", url = "http://127.0.0.1:1" }},
+            ]
+            "#,
+            down.url(), up.url(),
+        ));
+        let upstream = Upstream::new(config, reqwest::Client::new());
+        for source in &upstream.sources()[..2] {
+            upstream.probe(source).await;
+        }
+        let repo = Repository::new(&upstream);
+
+        let health = repo.get_all().await.unwrap();
+        let states: Vec<(u16, domain::State)> = health.iter().map(|health| (health.id, health.state)).collect();
+        assert_eq!(states, [(1, domain::State::Up), (2, domain::State::Down), (3, domain::State::Unknown)]);
+        assert!(health[0].last_success.is_some() && health[0].last_error.is_none());
+        assert!(matches!(&health[1].last_error, Some(domain::Failure { error: domain::ErrorKind::Status(503), .. })));
+        assert!(health[2].last_check.is_none());
+
+        assert_eq!(repo.get(2).await.unwrap().name, "Down");
+        assert!(matches!(repo.get(4).await, Err(GetError::NonExistingId(id)) if id == "4"));
+        assert!(Repository::default().get_all().await.unwrap().is_empty());
+    }
+}
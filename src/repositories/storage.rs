@@ -0,0 +1,237 @@
+use std::env;
+use std::path::Path;
+
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{types::Type, Connection};
+
+/// Schema migrations, the version of a database is the number of migrations it went through.
+/// Migrations are only ever appended, a database must be able to go from any version to the latest.
+const MIGRATIONS: &[&str] = &[
+    // 1: users, progress, ascents, comments, grades, moderation and ratings
+    "CREATE TABLE users (
+        key           TEXT PRIMARY KEY,
+        username      TEXT NOT NULL,
+        first_name    TEXT NOT NULL,
+        last_name     TEXT NOT NULL,
+        email         TEXT NOT NULL,
+        password_hash TEXT NOT NULL,
+        role          TEXT NOT NULL
+    );
+    CREATE TABLE user_sources (
+        user_key  TEXT NOT NULL REFERENCES users (key) ON DELETE CASCADE,
+        source_id INTEGER NOT NULL,
+        PRIMARY KEY (user_key, source_id)
+    );
+    CREATE TABLE marks (
+        user_key TEXT NOT NULL,
+        route_id TEXT NOT NULL,
+        liked    INTEGER NOT NULL,
+        project  INTEGER NOT NULL,
+        PRIMARY KEY (user_key, route_id)
+    );
+    CREATE TABLE ascents (
+        id          INTEGER PRIMARY KEY AUTOINCREMENT,
+        climber_key TEXT NOT NULL,
+        route_id    TEXT NOT NULL,
+        date        TEXT NOT NULL,
+        style       TEXT NOT NULL,
+        attempts    INTEGER NOT NULL,
+        grade       TEXT,
+        rating      INTEGER,
+        notes       TEXT NOT NULL
+    );
+    CREATE INDEX ascents_climber ON ascents (climber_key);
+    CREATE TABLE comments (
+        id       INTEGER PRIMARY KEY AUTOINCREMENT,
+        route_id TEXT NOT NULL,
+        reply_to INTEGER,
+        author   TEXT NOT NULL,
+        content  TEXT NOT NULL,
+        date     TEXT NOT NULL,
+        edited   TEXT,
+        deleted  TEXT,
+        hidden   INTEGER NOT NULL
+    );
+    CREATE INDEX comments_route ON comments (route_id);
+    CREATE TABLE revisions (
+        comment_id INTEGER NOT NULL REFERENCES comments (id) ON DELETE CASCADE,
+        position   INTEGER NOT NULL,
+        content    TEXT NOT NULL,
+        date       TEXT NOT NULL,
+        PRIMARY KEY (comment_id, position)
+    );
+    CREATE TABLE votes (
+        route_id TEXT NOT NULL,
+        user_key TEXT NOT NULL,
+        username TEXT NOT NULL,
+        grade    TEXT NOT NULL,
+        PRIMARY KEY (route_id, user_key)
+    );
+    CREATE TABLE reports (
+        comment_id   INTEGER NOT NULL,
+        reporter_key TEXT NOT NULL, -- empty for the reports of the word filter
+        reporter     TEXT,
+        reason       TEXT NOT NULL,
+        date         TEXT NOT NULL,
+        PRIMARY KEY (comment_id, reporter_key)
+    );
+    CREATE TABLE journal (
+        id         INTEGER PRIMARY KEY AUTOINCREMENT,
+        comment_id INTEGER NOT NULL,
+        route_id   TEXT NOT NULL,
+        author     TEXT NOT NULL,
+        moderator  TEXT NOT NULL,
+        action     TEXT NOT NULL,
+        reason     TEXT,
+        date       TEXT NOT NULL
+    );
+    CREATE TABLE ratings (
+        route_id TEXT NOT NULL,
+        user_key TEXT NOT NULL,
+        username TEXT NOT NULL,
+        stars    INTEGER NOT NULL,
+        PRIMARY KEY (route_id, user_key)
+    );",
+    // 2: accounts, telling apart the users later registered with the same username
+    "ALTER TABLE users ADD COLUMN account_id TEXT NOT NULL DEFAULT '';
+    UPDATE users SET account_id = lower(hex(randomblob(16)));",
+];
+
+/// Connections the pool keeps at most, SQLite serializes the writes anyway.
+const MAX_CONNECTIONS: u32 = 8;
+
+// Causes are only read through `Debug` when logging
+#[derive(Debug)]
+pub enum Error {
+    Pool(#[allow(dead_code)] r2d2::Error),
+    Sqlite(#[allow(dead_code)] rusqlite::Error),
+    /// The database went through more migrations than this build knows.
+    Version(#[allow(dead_code)] usize),
+    /// The blocking task running the queries panicked.
+    Task,
+}
+
+impl From<r2d2::Error> for Error {
+    fn from(e: r2d2::Error) -> Self {
+        Error::Pool(e)
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(e: rusqlite::Error) -> Self {
+        Error::Sqlite(e)
+    }
+}
+
+/// A pool of connections to the SQLite database holding the data owned by the core.
+/// Clones share the same connections.
+#[derive(Clone)]
+pub struct Pool {
+    connections: r2d2::Pool<SqliteConnectionManager>,
+}
+
+impl Pool {
+    /// Opens the database file, creating it if needed, and brings its schema to the latest version.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let manager = SqliteConnectionManager::file(path)
+            .with_init(|connection| connection.execute_batch("PRAGMA foreign_keys = ON; PRAGMA busy_timeout = 5000;"));
+        let connections = r2d2::Pool::builder().max_size(MAX_CONNECTIONS).build(manager)?;
+
+        let mut connection = connections.get()?;
+        // readers don't wait for the writer
+        connection.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        migrate(&mut connection)?;
+        Ok(Pool { connections })
+    }
+
+    /// The database at `CLIMBHUB_DATABASE`, none when it is not set.
+    pub fn from_env() -> Option<Self> {
+        let path = env::var("CLIMBHUB_DATABASE").ok()?;
+        Some(Pool::open(&path).unwrap_or_else(|error| panic!("Opening the database {path}: {error:?}")))
+    }
+
+    /// Runs the queries on a connection of the pool, away from the async workers since SQLite blocks.
+    pub async fn run<T, F>(&self, queries: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let connections = self.connections.clone();
+        let result = tokio::task::spawn_blocking(move || -> Result<T, Error> {
+            let mut connection = connections.get()?;
+            Ok(queries(&mut connection)?)
+        }).await.unwrap_or(Err(Error::Task));
+
+        if let Err(error) = &result {
+            eprintln!("Storage error: {:?}", error);
+        }
+        result
+    }
+}
+
+/// Applies the migrations the database did not go through yet, each in its own transaction.
+fn migrate(connection: &mut Connection) -> Result<(), Error> {
+    let version: usize = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > MIGRATIONS.len() {
+        return Err(Error::Version(version));
+    }
+    for (applied, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", applied + 1)?;
+        transaction.commit()?;
+    }
+    Ok(())
+}
+
+/// The error for a stored value that can't be read back, such as an unknown enum variant.
+pub fn unreadable(column: usize, value: &str) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(column, Type::Text, format!("unexpected value `{value}`").into())
+}
+
+/// A pool on a fresh database, which lasts as long as the returned directory.
+#[cfg(test)]
+pub fn temporary() -> (tempfile::TempDir, Pool) {
+    let directory = tempfile::tempdir().unwrap();
+    let pool = Pool::open(directory.path().join("climbhub.db")).unwrap();
+    (directory, pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(pool: &Pool) -> usize {
+        pool.connections.get().unwrap().pragma_query_value(None, "user_version", |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_migrations() {
+        let (directory, pool) = temporary();
+        assert_eq!(version(&pool), MIGRATIONS.len());
+        drop(pool);
+
+        // reopening leaves the schema as it is
+        let pool = Pool::open(directory.path().join("climbhub.db")).unwrap();
+        assert_eq!(version(&pool), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn test_newer_database() {
+        let (directory, pool) = temporary();
+        pool.connections.get().unwrap().pragma_update(None, "user_version", MIGRATIONS.len() + 1).unwrap();
+        drop(pool);
+
+        assert!(matches!(Pool::open(directory.path().join("climbhub.db")), Err(Error::Version(_))));
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        let (_directory, pool) = temporary();
+        let tables: usize = pool.run(|connection| {
+            connection.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'users'", [], |row| row.get(0))
+        }).await.unwrap();
+        assert_eq!(tables, 1);
+        assert!(matches!(pool.run(|connection| connection.execute("DELETE FROM nowhere", [])).await, Err(Error::Sqlite(_))));
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::contexts::auth;
+use crate::contexts::users::{domain, irepository};
+use crate::errors::{AuthError, CreateError, DeleteError, GetAllError, GetError, UpdateError};
+
+/// Users are kept in memory, keyed by their lowercased username so that usernames are unique regardless of case.
+/// Clones share the same users.
+#[derive(Clone, Default)]
+pub struct Repository {
+    users: Arc<Mutex<BTreeMap<String, Account>>>,
+}
+
+struct Account {
+    user: domain::User,
+    id: auth::domain::AccountId,
+    password_hash: String,
+    access: auth::domain::Access,
+}
+
+impl Repository {
+    fn users(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Account>> {
+        self.users.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn key(username: &str) -> String {
+    username.to_lowercase()
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self) -> Result<Vec<domain::User>, GetAllError> {
+        Ok(self.users().values().map(|account| account.user.clone()).collect())
+    }
+
+    async fn get(&self, username: domain::Username) -> Result<domain::User, GetError> {
+        self.users().get(&key(&username)).map(|account| account.user.clone()).ok_or(GetError::NonExistingId(username))
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::post::IRepository for Repository {
+    async fn create(&self, user: domain::User, password_hash: String, account: auth::domain::AccountId) -> Result<domain::User, CreateError> {
+        let mut users = self.users();
+        let key = key(&user.username);
+        if users.contains_key(&key) {
+            return Err(CreateError::AlreadyExists(user.username));
+        }
+        users.insert(key, Account { user: user.clone(), id: account, password_hash, access: auth::domain::Access::default() });
+        Ok(user)
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn update(&self, username: domain::Username, profile: domain::Profile) -> Result<(), UpdateError> {
+        match self.users().get_mut(&key(&username)) {
+            Some(account) => {
+                account.user.profile = profile;
+                Ok(())
+            },
+            None => Err(UpdateError::NonExistingId(username)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, username: domain::Username) -> Result<(), DeleteError> {
+        match self.users().remove(&key(&username)) {
+            Some(_) => Ok(()),
+            None => Err(DeleteError::NonExistingId(username)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl auth::irepository::get::IRepository for Repository {
+    async fn credentials(&self, username: domain::Username) -> Result<Option<auth::domain::Credentials>, AuthError> {
+        Ok(self.users().get(&key(&username)).map(|account| auth::domain::Credentials {
+            username: account.user.username.clone(),
+            account: account.id.clone(),
+            password_hash: account.password_hash.clone(),
+        }))
+    }
+
+    async fn access(&self, username: domain::Username) -> Result<auth::domain::Access, GetError> {
+        self.users().get(&key(&username)).map(|account| account.access.clone()).ok_or(GetError::NonExistingId(username))
+    }
+}
+
+#[async_trait::async_trait]
+impl auth::irepository::put::IRepository for Repository {
+    async fn set_access(&self, username: domain::Username, access: auth::domain::Access) -> Result<(), UpdateError> {
+        match self.users().get_mut(&key(&username)) {
+            Some(account) => {
+                account.access = access;
+                Ok(())
+            },
+            None => Err(UpdateError::NonExistingId(username)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use irepository::{get::IRepository, post::IRepository as _, put::IRepository as _, delete::IRepository as _};
+    use auth::irepository::{get::IRepository as _, put::IRepository as _};
+
+    fn user(username: &str) -> domain::User {
+        domain::User {
+            username: String::from(username),
+            profile: domain::Profile {
+                first_name: String::from("John"),
+                last_name: String::from("Doe"),
+                email: String::from("john.doe@example.com"),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get() {
+        let repo = Repository::default();
+        repo.create(user("jdoe"), String::from("hash"), String::from("jdoe")).await.unwrap();
+        repo.create(user("asmith"), String::from("hash"), String::from("asmith")).await.unwrap();
+
+        assert_eq!(repo.get(String::from("JDoe")).await.unwrap(), user("jdoe"));
+        let usernames: Vec<String> = repo.get_all().await.unwrap().into_iter().map(|user| user.username).collect();
+        assert_eq!(usernames, vec![String::from("asmith"), String::from("jdoe")]);
+        assert!(matches!(repo.get(String::from("nobody")).await, Err(GetError::NonExistingId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unique_username() {
+        let repo = Repository::default();
+        repo.create(user("jdoe"), String::from("hash"), String::from("jdoe")).await.unwrap();
+        assert!(matches!(repo.create(user("JDOE"), String::from("hash"), String::from("JDOE")).await, Err(CreateError::AlreadyExists(username)) if username == "JDOE"));
+    }
+
+    #[tokio::test]
+    async fn test_update() {
+        let repo = Repository::default();
+        repo.create(user("jdoe"), String::from("hash"), String::from("jdoe")).await.unwrap();
+
+        let profile = domain::Profile { email: String::from("jdoe@example.org"), ..user("jdoe").profile };
+        repo.update(String::from("jdoe"), profile.clone()).await.unwrap();
+        assert_eq!(repo.get(String::from("jdoe")).await.unwrap().profile, profile);
+        assert!(matches!(repo.update(String::from("nobody"), profile).await, Err(UpdateError::NonExistingId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete() {
+        let repo = Repository::default();
+        repo.create(user("jdoe"), String::from("hash"), String::from("jdoe")).await.unwrap();
+
+        repo.delete(String::from("jdoe")).await.unwrap();
+        assert!(matches!(repo.get(String::from("jdoe")).await, Err(GetError::NonExistingId(_))));
+        assert!(matches!(repo.delete(String::from("jdoe")).await, Err(DeleteError::NonExistingId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_credentials() {
+        let repo = Repository::default();
+        repo.create(user("jdoe"), String::from("hash"), String::from("jdoe")).await.unwrap();
+
+        let credentials = repo.credentials(String::from("JDoe")).await.unwrap().unwrap();
+        assert_eq!((credentials.username.as_str(), credentials.account.as_str(), credentials.password_hash.as_str()), ("jdoe", "jdoe", "hash"));
+        assert!(repo.credentials(String::from("nobody")).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_access() {
+        let repo = Repository::default();
+        repo.create(user("jdoe"), String::from("hash"), String::from("jdoe")).await.unwrap();
+        assert_eq!(repo.access(String::from("jdoe")).await.unwrap(), auth::domain::Access::default());
+
+        let access = auth::domain::Access { role: auth::domain::Role::Setter, sources: [1, 2].into_iter().collect() };
+        repo.set_access(String::from("JDoe"), access.clone()).await.unwrap();
+        assert_eq!(repo.access(String::from("jdoe")).await.unwrap(), access);
+        assert!(matches!(repo.access(String::from("nobody")).await, Err(GetError::NonExistingId(_))));
+        assert!(matches!(repo.set_access(String::from("nobody"), access).await, Err(UpdateError::NonExistingId(_))));
+    }
+}
@@ -0,0 +1,231 @@
+use rusqlite::{params, OptionalExtension, Row};
+
+use crate::contexts::auth;
+use crate::contexts::users::{domain, irepository};
+use crate::errors::{AuthError, CreateError, DeleteError, GetAllError, GetError, UpdateError};
+use crate::repositories::storage::{self, Pool};
+
+/// Users are kept in the database, keyed by their lowercased username so that usernames are unique regardless of case.
+#[derive(Clone)]
+pub struct Repository {
+    pool: Pool,
+}
+
+impl Repository {
+    pub fn new(pool: Pool) -> Self {
+        Repository { pool }
+    }
+}
+
+fn key(username: &str) -> String {
+    username.to_lowercase()
+}
+
+const USER: &str = "SELECT username, first_name, last_name, email FROM users";
+
+fn user(row: &Row) -> rusqlite::Result<domain::User> {
+    Ok(domain::User {
+        username: row.get(0)?,
+        profile: domain::Profile {
+            first_name: row.get(1)?,
+            last_name: row.get(2)?,
+            email: row.get(3)?,
+        },
+    })
+}
+
+fn role(role: auth::domain::Role) -> &'static str {
+    match role {
+        auth::domain::Role::Climber => "climber",
+        auth::domain::Role::Setter => "setter",
+        auth::domain::Role::GymAdmin => "gym_admin",
+        auth::domain::Role::PlatformAdmin => "platform_admin",
+    }
+}
+
+fn parse_role(text: &str) -> rusqlite::Result<auth::domain::Role> {
+    match text {
+        "climber" => Ok(auth::domain::Role::Climber),
+        "setter" => Ok(auth::domain::Role::Setter),
+        "gym_admin" => Ok(auth::domain::Role::GymAdmin),
+        "platform_admin" => Ok(auth::domain::Role::PlatformAdmin),
+        _ => Err(storage::unreadable(0, text)),
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::get::IRepository for Repository {
+    async fn get_all(&self) -> Result<Vec<domain::User>, GetAllError> {
+        self.pool.run(|connection| {
+            connection.prepare(&format!("{USER} ORDER BY key"))?.query_map([], user)?.collect()
+        }).await.map_err(|_| GetAllError::InternalServerError)
+    }
+
+    async fn get(&self, username: domain::Username) -> Result<domain::User, GetError> {
+        let key = key(&username);
+        match self.pool.run(move |connection| connection.query_row(&format!("{USER} WHERE key = ?1"), [key], user).optional()).await {
+            Ok(Some(user)) => Ok(user),
+            Ok(None) => Err(GetError::NonExistingId(username)),
+            Err(_) => Err(GetError::InternalServerError),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::post::IRepository for Repository {
+    async fn create(&self, user: domain::User, password_hash: String, account: auth::domain::AccountId) -> Result<domain::User, CreateError> {
+        let stored = user.clone();
+        let created = self.pool.run(move |connection| {
+            connection.execute(
+                "INSERT OR IGNORE INTO users (key, username, first_name, last_name, email, password_hash, role, account_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![key(&stored.username), stored.username, stored.profile.first_name, stored.profile.last_name, stored.profile.email, password_hash, role(auth::domain::Role::default()), account],
+            )
+        }).await.map_err(|_| CreateError::InternalServerError)?;
+
+        match created {
+            0 => Err(CreateError::AlreadyExists(user.username)),
+            _ => Ok(user),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::put::IRepository for Repository {
+    async fn update(&self, username: domain::Username, profile: domain::Profile) -> Result<(), UpdateError> {
+        let key = key(&username);
+        let updated = self.pool.run(move |connection| {
+            connection.execute(
+                "UPDATE users SET first_name = ?2, last_name = ?3, email = ?4 WHERE key = ?1",
+                params![key, profile.first_name, profile.last_name, profile.email],
+            )
+        }).await.map_err(|_| UpdateError::InternalServerError)?;
+
+        match updated {
+            0 => Err(UpdateError::NonExistingId(username)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl irepository::delete::IRepository for Repository {
+    async fn delete(&self, username: domain::Username) -> Result<(), DeleteError> {
+        let key = key(&username);
+        let deleted = self.pool.run(move |connection| connection.execute("DELETE FROM users WHERE key = ?1", [key]))
+            .await.map_err(|_| DeleteError::InternalServerError)?;
+
+        match deleted {
+            0 => Err(DeleteError::NonExistingId(username)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl auth::irepository::get::IRepository for Repository {
+    async fn credentials(&self, username: domain::Username) -> Result<Option<auth::domain::Credentials>, AuthError> {
+        let key = key(&username);
+        self.pool.run(move |connection| {
+            connection.query_row("SELECT username, account_id, password_hash FROM users WHERE key = ?1", [key], |row| {
+                Ok(auth::domain::Credentials { username: row.get(0)?, account: row.get(1)?, password_hash: row.get(2)? })
+            }).optional()
+        }).await.map_err(|_| AuthError::InternalServerError)
+    }
+
+    async fn access(&self, username: domain::Username) -> Result<auth::domain::Access, GetError> {
+        let key = key(&username);
+        let access = self.pool.run(move |connection| {
+            let role: Option<String> = connection.query_row("SELECT role FROM users WHERE key = ?1", [&key], |row| row.get(0)).optional()?;
+            let Some(role) = role else { return Ok(None) };
+            let sources = connection.prepare("SELECT source_id FROM user_sources WHERE user_key = ?1")?
+                .query_map([&key], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(Some(auth::domain::Access { role: parse_role(&role)?, sources }))
+        }).await.map_err(|_| GetError::InternalServerError)?;
+
+        access.ok_or(GetError::NonExistingId(username))
+    }
+}
+
+#[async_trait::async_trait]
+impl auth::irepository::put::IRepository for Repository {
+    async fn set_access(&self, username: domain::Username, access: auth::domain::Access) -> Result<(), UpdateError> {
+        let key = key(&username);
+        let updated = self.pool.run(move |connection| {
+            let transaction = connection.transaction()?;
+            let updated = transaction.execute("UPDATE users SET role = ?2 WHERE key = ?1", params![key, role(access.role)])?;
+            transaction.execute("DELETE FROM user_sources WHERE user_key = ?1", [&key])?;
+            if updated != 0 {
+                for source_id in &access.sources {
+                    transaction.execute("INSERT INTO user_sources (user_key, source_id) VALUES (?1, ?2)", params![key, source_id])?;
+                }
+            }
+            transaction.commit()?;
+            Ok(updated)
+        }).await.map_err(|_| UpdateError::InternalServerError)?;
+
+        match updated {
+            0 => Err(UpdateError::NonExistingId(username)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use irepository::{get::IRepository, post::IRepository as _, put::IRepository as _, delete::IRepository as _};
+    use auth::irepository::{get::IRepository as _, put::IRepository as _};
+
+    fn user(username: &str) -> domain::User {
+        domain::User {
+            username: String::from(username),
+            profile: domain::Profile {
+                first_name: String::from("John"),
+                last_name: String::from("Doe"),
+                email: String::from("john.doe@example.com"),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_update_and_delete() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        repo.create(user("jdoe"), String::from("hash"), String::from("jdoe")).await.unwrap();
+        repo.create(user("asmith"), String::from("hash"), String::from("asmith")).await.unwrap();
+        assert!(matches!(repo.create(user("JDOE"), String::from("hash"), String::from("JDOE")).await, Err(CreateError::AlreadyExists(username)) if username == "JDOE"));
+
+        let usernames: Vec<String> = repo.get_all().await.unwrap().into_iter().map(|user| user.username).collect();
+        assert_eq!(usernames, vec![String::from("asmith"), String::from("jdoe")]);
+
+        let profile = domain::Profile { email: String::from("jdoe@example.org"), ..user("jdoe").profile };
+        repo.update(String::from("JDoe"), profile.clone()).await.unwrap();
+        assert_eq!(repo.get(String::from("jdoe")).await.unwrap().profile, profile);
+        assert!(matches!(repo.update(String::from("nobody"), profile).await, Err(UpdateError::NonExistingId(_))));
+
+        repo.delete(String::from("jdoe")).await.unwrap();
+        assert!(matches!(repo.get(String::from("jdoe")).await, Err(GetError::NonExistingId(_))));
+        assert!(matches!(repo.delete(String::from("jdoe")).await, Err(DeleteError::NonExistingId(_))));
+    }
+
+    #[tokio::test]
+    async fn test_credentials_and_access() {
+        let (_directory, pool) = storage::temporary();
+        let repo = Repository::new(pool);
+        repo.create(user("jdoe"), String::from("hash"), String::from("jdoe")).await.unwrap();
+
+        let credentials = repo.credentials(String::from("JDoe")).await.unwrap().unwrap();
+        assert_eq!((credentials.username.as_str(), credentials.account.as_str(), credentials.password_hash.as_str()), ("jdoe", "jdoe", "hash"));
+        assert!(repo.credentials(String::from("nobody")).await.unwrap().is_none());
+
+        assert_eq!(repo.access(String::from("jdoe")).await.unwrap(), auth::domain::Access::default());
+        let access = auth::domain::Access { role: auth::domain::Role::Setter, sources: [1, 2].into_iter().collect() };
+        repo.set_access(String::from("JDoe"), access.clone()).await.unwrap();
+        assert_eq!(repo.access(String::from("jdoe")).await.unwrap(), access);
+        repo.set_access(String::from("jdoe"), auth::domain::Access::platform_admin()).await.unwrap();
+        assert_eq!(repo.access(String::from("jdoe")).await.unwrap(), auth::domain::Access::platform_admin());
+        assert!(matches!(repo.access(String::from("nobody")).await, Err(GetError::NonExistingId(_))));
+        assert!(matches!(repo.set_access(String::from("nobody"), access).await, Err(UpdateError::NonExistingId(_))));
+    }
+}
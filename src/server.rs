@@ -1,4 +1,4 @@
-use rocket::{Rocket, Build};
+use rocket::{Rocket, Build, catchers, fairing::AdHoc};
 use rocket_okapi::{
     openapi_get_routes,
     swagger_ui::{make_swagger_ui, SwaggerUIConfig},
@@ -6,12 +6,44 @@ use rocket_okapi::{
 
 use crate::contexts::
 {
-    places::use_cases::get::UseCase    as PlaceGetUseCase,
-    routes::use_cases::get::UseCase    as RouteGetUseCase,
-    routes::use_cases::post::UseCase   as RoutePostUseCase,
-    routes::use_cases::put::UseCase    as RoutePutUseCase,
-    routes::use_cases::delete::UseCase as RouteDeleteUseCase,
+    places::use_cases::get::UseCase      as PlaceGetUseCase,
+    places::use_cases::post::UseCase     as PlacePostUseCase,
+    places::use_cases::put::UseCase      as PlacePutUseCase,
+    places::use_cases::delete::UseCase   as PlaceDeleteUseCase,
+    routes::use_cases::get::UseCase      as RouteGetUseCase,
+    routes::use_cases::post::UseCase     as RoutePostUseCase,
+    routes::use_cases::put::UseCase      as RoutePutUseCase,
+    routes::use_cases::delete::UseCase   as RouteDeleteUseCase,
+    sources::use_cases::get::UseCase     as SourceGetUseCase,
+    users::use_cases::get::UseCase       as UserGetUseCase,
+    users::use_cases::post::UseCase      as UserPostUseCase,
+    users::use_cases::put::UseCase       as UserPutUseCase,
+    users::use_cases::delete::UseCase    as UserDeleteUseCase,
+    comments::use_cases::get::UseCase    as CommentGetUseCase,
+    comments::use_cases::post::UseCase   as CommentPostUseCase,
+    comments::use_cases::put::UseCase    as CommentPutUseCase,
+    comments::use_cases::delete::UseCase as CommentDeleteUseCase,
+    moderation::use_cases::get::UseCase  as ModerationGetUseCase,
+    moderation::use_cases::post::UseCase as ModerationPostUseCase,
+    moderation::use_cases::put::UseCase  as ModerationPutUseCase,
+    ascents::use_cases::get::UseCase     as AscentGetUseCase,
+    ascents::use_cases::post::UseCase    as AscentPostUseCase,
+    ascents::use_cases::put::UseCase     as AscentPutUseCase,
+    ascents::use_cases::delete::UseCase  as AscentDeleteUseCase,
+    grades::use_cases::get::UseCase      as GradeGetUseCase,
+    grades::use_cases::put::UseCase      as GradePutUseCase,
+    grades::use_cases::delete::UseCase   as GradeDeleteUseCase,
+    ratings::use_cases::get::UseCase     as RatingGetUseCase,
+    ratings::use_cases::put::UseCase     as RatingPutUseCase,
+    ratings::use_cases::delete::UseCase  as RatingDeleteUseCase,
+    progress::use_cases::get::UseCase    as ProgressGetUseCase,
+    progress::use_cases::put::UseCase    as ProgressPutUseCase,
+    auth::use_cases::get::UseCase        as AuthGetUseCase,
+    auth::use_cases::post::UseCase       as AuthPostUseCase,
+    auth::use_cases::put::UseCase        as AuthPutUseCase,
+    auth::domain::tokens::Issuer         as TokenIssuer,
 };
+use crate::repositories::{storage::Pool, Upstream};
 
 pub struct Server
 {
@@ -20,24 +52,138 @@ pub struct Server
 
 impl Server
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        place_get_uc  : PlaceGetUseCase,
-        route_get_uc  : RouteGetUseCase,
-        route_post_uc : RoutePostUseCase,
-        route_put_uc  : RoutePutUseCase,
-        route_del_uc  : RouteDeleteUseCase,
+        place_get_uc       : PlaceGetUseCase,
+        place_post_uc      : PlacePostUseCase,
+        place_put_uc       : PlacePutUseCase,
+        place_del_uc       : PlaceDeleteUseCase,
+        route_get_uc       : RouteGetUseCase,
+        route_post_uc      : RoutePostUseCase,
+        route_put_uc       : RoutePutUseCase,
+        route_del_uc       : RouteDeleteUseCase,
+        source_get_uc      : SourceGetUseCase,
+        user_get_uc        : UserGetUseCase,
+        user_post_uc       : UserPostUseCase,
+        user_put_uc        : UserPutUseCase,
+        user_del_uc        : UserDeleteUseCase,
+        comment_get_uc     : CommentGetUseCase,
+        comment_post_uc    : CommentPostUseCase,
+        comment_put_uc     : CommentPutUseCase,
+        comment_del_uc     : CommentDeleteUseCase,
+        moderation_get_uc  : ModerationGetUseCase,
+        moderation_post_uc : ModerationPostUseCase,
+        moderation_put_uc  : ModerationPutUseCase,
+        ascent_get_uc      : AscentGetUseCase,
+        ascent_post_uc     : AscentPostUseCase,
+        ascent_put_uc      : AscentPutUseCase,
+        ascent_del_uc      : AscentDeleteUseCase,
+        grade_get_uc       : GradeGetUseCase,
+        grade_put_uc       : GradePutUseCase,
+        grade_del_uc       : GradeDeleteUseCase,
+        rating_get_uc      : RatingGetUseCase,
+        rating_put_uc      : RatingPutUseCase,
+        rating_del_uc      : RatingDeleteUseCase,
+        progress_get_uc    : ProgressGetUseCase,
+        progress_put_uc    : ProgressPutUseCase,
+        auth_get_uc        : AuthGetUseCase,
+        auth_post_uc       : AuthPostUseCase,
+        auth_put_uc        : AuthPutUseCase,
+        token_issuer       : TokenIssuer,
     ) -> Self
     {
         Server {
             rocket_build: build()
                 .manage(place_get_uc)
+                .manage(place_post_uc)
+                .manage(place_put_uc)
+                .manage(place_del_uc)
                 .manage(route_get_uc)
                 .manage(route_post_uc)
                 .manage(route_put_uc)
                 .manage(route_del_uc)
+                .manage(source_get_uc)
+                .manage(user_get_uc)
+                .manage(user_post_uc)
+                .manage(user_put_uc)
+                .manage(user_del_uc)
+                .manage(comment_get_uc)
+                .manage(comment_post_uc)
+                .manage(comment_put_uc)
+                .manage(comment_del_uc)
+                .manage(moderation_get_uc)
+                .manage(moderation_post_uc)
+                .manage(moderation_put_uc)
+                .manage(ascent_get_uc)
+                .manage(ascent_post_uc)
+                .manage(ascent_put_uc)
+                .manage(ascent_del_uc)
+                .manage(grade_get_uc)
+                .manage(grade_put_uc)
+                .manage(grade_del_uc)
+                .manage(rating_get_uc)
+                .manage(rating_put_uc)
+                .manage(rating_del_uc)
+                .manage(progress_get_uc)
+                .manage(progress_put_uc)
+                .manage(auth_get_uc)
+                .manage(auth_post_uc)
+                .manage(auth_put_uc)
+                .manage(token_issuer)
         }
     }
 
+    /// Shares the pool of the database with the routes, as Rocket state.
+    pub fn storage(self, pool: Pool) -> Self
+    {
+        Server { rocket_build: self.rocket_build.manage(pool) }
+    }
+
+    /// Grants platform admin access to the accounts of `usernames` before the server runs.
+    pub fn platform_admins(self, usernames: Vec<String>) -> Self
+    {
+        if usernames.is_empty()
+        {
+            return self;
+        }
+        Server {
+            rocket_build: self.rocket_build.attach(AdHoc::on_ignite("Platform admins", |rocket| Box::pin(async move
+            {
+                let granted = match rocket.state::<AuthPutUseCase>()
+                {
+                    Some(use_case) => use_case.grant_platform_admin(usernames).await,
+                    None => return rocket,
+                };
+                match granted
+                {
+                    Ok(unregistered) => for username in unregistered
+                    {
+                        eprintln!("Platform admin `{username}` is not registered, ignored");
+                    },
+                    Err(error) => eprintln!("Platform admins could not be granted: {:?}", error),
+                }
+                rocket
+            }))),
+        }
+    }
+
+    /// Probes the sources of `upstream` in the background once the server runs.
+    pub fn probing(self, upstream: Upstream) -> Self
+    {
+        Server {
+            rocket_build: self.rocket_build.attach(AdHoc::on_liftoff("Source probes", |_| Box::pin(async move
+            {
+                rocket::tokio::spawn(upstream.probe_forever());
+            }))),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn rocket(self) -> Rocket<Build>
+    {
+        self.rocket_build
+    }
+
     pub fn serve(self)
     {
         // FIXME: The `workers` value won't reflect swaps of `Rocket` in attach
@@ -61,13 +207,52 @@ impl Server
 fn build() -> Rocket<Build>
 {
     rocket::build()
+    .register("/", catchers![crate::problems::default_catcher])
     .mount("/", openapi_get_routes![
         crate::contexts::places::router::get::get_all_places,
+        crate::contexts::places::router::get::get_place,
+        crate::contexts::places::router::post::create_place,
+        crate::contexts::places::router::put::update_place,
+        crate::contexts::places::router::delete::delete_place,
         crate::contexts::routes::router::get::get_all_routes,
         crate::contexts::routes::router::get::get_route,
         crate::contexts::routes::router::post::create_route,
         crate::contexts::routes::router::put::update_route,
         crate::contexts::routes::router::delete::delete_route,
+        crate::contexts::sources::router::get::get_sources,
+        crate::contexts::sources::router::get::get_health,
+        crate::contexts::users::router::get::get_all_users,
+        crate::contexts::users::router::get::get_user,
+        crate::contexts::users::router::post::register_user,
+        crate::contexts::users::router::put::update_user,
+        crate::contexts::users::router::delete::delete_user,
+        crate::contexts::comments::router::get::get_comments,
+        crate::contexts::comments::router::get::get_comment,
+        crate::contexts::comments::router::post::post_comment,
+        crate::contexts::comments::router::put::edit_comment,
+        crate::contexts::comments::router::delete::delete_comment,
+        crate::contexts::moderation::router::get::get_queue,
+        crate::contexts::moderation::router::get::get_audit,
+        crate::contexts::moderation::router::post::report_comment,
+        crate::contexts::moderation::router::post::moderate_comment,
+        crate::contexts::ascents::router::get::get_ascents,
+        crate::contexts::ascents::router::get::get_ascent,
+        crate::contexts::ascents::router::post::log_ascent,
+        crate::contexts::ascents::router::put::edit_ascent,
+        crate::contexts::ascents::router::delete::delete_ascent,
+        crate::contexts::grades::router::get::get_grades,
+        crate::contexts::grades::router::put::suggest_grade,
+        crate::contexts::grades::router::delete::withdraw_grade,
+        crate::contexts::ratings::router::get::get_score,
+        crate::contexts::ratings::router::put::rate_route,
+        crate::contexts::ratings::router::delete::withdraw_rating,
+        crate::contexts::progress::router::get::get_progress,
+        crate::contexts::progress::router::put::set_progress,
+        crate::contexts::progress::router::delete::clear_progress,
+        crate::contexts::auth::router::post::login,
+        crate::contexts::auth::router::post::refresh,
+        crate::contexts::auth::router::get::get_access,
+        crate::contexts::auth::router::put::update_access,
     ])
     .mount(
         "/docs/",
@@ -1,12 +1,90 @@
+pub mod ids
+{
+    use std::{fmt, num::ParseIntError, str::FromStr};
+
+    /// Absolute identifier of a resource: the id of its source and its id within that source.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct RelativeId {
+        pub source_id: u16,
+        pub resource_id: u32,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum ParseIdError {
+        /// The id is not made of two parts separated by a `-`.
+        Format(String),
+        SourceId(String, ParseIntError),
+        ResourceId(String, ParseIntError),
+    }
+
+    impl FromStr for RelativeId {
+        type Err = ParseIdError;
+
+        /// Parses a string of the form `FFFF-FFFFFFFF` into a `RelativeId`.
+        fn from_str(id: &str) -> Result<Self, Self::Err> {
+            let (source_id, resource_id) = id.split_once('-').ok_or_else(|| ParseIdError::Format(id.to_owned()))?;
+            Ok(Self {
+                source_id: u16::from_str_radix(source_id, 16).map_err(|e| ParseIdError::SourceId(id.to_owned(), e))?,
+                resource_id: u32::from_str_radix(resource_id, 16).map_err(|e| ParseIdError::ResourceId(id.to_owned(), e))?,
+            })
+        }
+    }
+
+    impl fmt::Display for RelativeId {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{:04X}-{:08X}", self.source_id, self.resource_id)
+        }
+    }
+
+    impl fmt::Display for ParseIdError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ParseIdError::Format(id) => write!(f, "`{id}` is not an id of the form `FFFF-FFFFFFFF`"),
+                ParseIdError::SourceId(id, e) => write!(f, "`{id}` has an invalid source id: {e}"),
+                ParseIdError::ResourceId(id, e) => write!(f, "`{id}` has an invalid resource id: {e}"),
+            }
+        }
+    }
+
+    impl std::error::Error for ParseIdError {}
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_from_str() {
+            let id: RelativeId = "0199-58B583BD".parse().unwrap();
+            assert_eq!(id.source_id, 409);
+            assert_eq!(id.resource_id, 1488290749);
+        }
+
+        #[test]
+        fn test_to_string() {
+            let id: RelativeId = "0199-58B583BD".parse().unwrap();
+            assert_eq!(id.to_string(), "0199-58B583BD");
+        }
+
+        #[test]
+        fn test_from_str_malformed() {
+            assert!(matches!("garbage".parse::<RelativeId>(), Err(ParseIdError::Format(_))));
+            assert!(matches!("10000-1".parse::<RelativeId>(), Err(ParseIdError::SourceId(_, _))));
+            assert!(matches!("1-XYZ".parse::<RelativeId>(), Err(ParseIdError::ResourceId(_, _))));
+            assert!(matches!("1-2-3".parse::<RelativeId>(), Err(ParseIdError::ResourceId(_, _))));
+        }
+    }
+}
+
 pub mod routers
 {
-    use rocket::form::FromFormField;
+    use rocket::{form::FromFormField, request::FromParam};
     use rocket_okapi::{
         JsonSchema, 
-        okapi::schemars::{gen::SchemaGenerator, schema::{Schema, SchemaObject, InstanceType}}
+        okapi::schemars::{gen::SchemaGenerator, schema::{Schema, SchemaObject, InstanceType, StringValidation}}
     };
+    use super::ids::{RelativeId, ParseIdError};
     use serde::{Serialize, Deserialize};
-    use chrono::{NaiveDate};
+    use chrono::{NaiveDate, Utc};
 
     #[derive(Serialize, Deserialize, Debug)]
     #[serde(rename_all = "camelCase")]
@@ -24,8 +102,8 @@ pub mod routers
         {
             match field.value.parse::<NaiveDate>()
             {
-                Ok(date) => Ok(Date(date.into())),
-                Err(_) => panic!(),
+                Ok(date) => Ok(Date(date)),
+                Err(e) => Err(rocket::form::Error::validation(format!("invalid date: {e}")).into()),
             }
         }
     }
@@ -42,6 +120,51 @@ pub mod routers
             }.into()
         }
     }
+
+    /// Instant in UTC, given in RFC 3339.
+    #[derive(Serialize, Deserialize, Debug)]
+    pub struct DateTime(pub chrono::DateTime<Utc>);
+
+    impl JsonSchema for DateTime
+    {
+        fn schema_name() -> String { String::from("DateTime") }
+        fn json_schema(_gen: &mut SchemaGenerator) -> Schema
+        {
+            SchemaObject {
+                instance_type : Some(InstanceType::String.into()),
+                format        : Some("date-time".to_owned()),
+                ..Default::default()
+            }.into()
+        }
+    }
+
+    /// Path parameter holding an absolute id.
+    ///
+    /// Malformed ids are kept so that routers can answer `400 Bad Request` instead of forwarding the request.
+    #[derive(Debug)]
+    pub struct Id(pub Result<RelativeId, ParseIdError>);
+
+    impl<'a> FromParam<'a> for Id
+    {
+        type Error = std::convert::Infallible;
+        fn from_param(param: &'a str) -> Result<Self, Self::Error> { Ok(Id(param.parse())) }
+    }
+
+    impl JsonSchema for Id
+    {
+        fn schema_name() -> String { String::from("Id") }
+        fn json_schema(_gen: &mut SchemaGenerator) -> Schema
+        {
+            SchemaObject {
+                instance_type : Some(InstanceType::String.into()),
+                string        : Some(Box::new(StringValidation {
+                    pattern : Some(String::from("^[0-9A-Fa-f]{1,4}-[0-9A-Fa-f]{1,8}$")),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }.into()
+        }
+    }
 }
 
 pub mod repositories